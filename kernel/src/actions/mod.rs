@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 
+use arrow_array::cast::AsArray;
 use arrow_array::{
-    BooleanArray, Int32Array, Int64Array, ListArray, MapArray, RecordBatch, StringArray,
+    Array, BooleanArray, Int32Array, Int64Array, ListArray, MapArray, RecordBatch, StringArray,
     StructArray,
 };
+use arrow_schema::DataType as ArrowDataType;
 use either::Either;
 use fix_hidden_lifetime_bug::fix_hidden_lifetime_bug;
 use itertools::izip;
+use serde::{Deserialize, Serialize};
 
 use crate::{DeltaResult, Error};
 
@@ -34,15 +37,27 @@ pub enum ActionType {
     Remove,
     /// The Row ID high-water mark tracks the largest ID that has been assigned to a row in the table.
     RowIdHighWaterMark,
+    /// references a file holding part of the actions of a V2 checkpoint
+    Sidecar,
     Txn,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// A single action recorded in the delta log.
+///
+/// (De)serializes to the JSON encoding used in commit files, i.e. an object with a single key
+/// naming the action type, e.g. `{"add": {...}}`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum Action {
+    #[serde(rename = "metaData")]
     Metadata(Metadata),
     Protocol(Protocol),
     Add(Add),
     Remove(Remove),
+    Txn(Txn),
+    CommitInfo(CommitInfo),
+    Cdc(Cdc),
+    DomainMetadata(DomainMetadata),
 }
 
 #[fix_hidden_lifetime_bug]
@@ -66,6 +81,10 @@ pub(crate) fn parse_action(
         ActionType::Protocol => "protocol",
         ActionType::Add => "add",
         ActionType::Remove => "remove",
+        ActionType::Cdc => "cdc",
+        ActionType::Txn => "txn",
+        ActionType::CommitInfo => "commitInfo",
+        ActionType::DomainMetadata => "domainMetadata",
         _ => unimplemented!(),
     };
 
@@ -83,6 +102,10 @@ pub(crate) fn parse_action(
         ActionType::Protocol => parse_action_protocol(arr),
         ActionType::Add => parse_actions_add(arr),
         ActionType::Remove => parse_actions_remove(arr),
+        ActionType::Cdc => parse_actions_cdc(arr),
+        ActionType::Txn => parse_actions_txn(arr),
+        ActionType::CommitInfo => parse_actions_commit_info(arr),
+        ActionType::DomainMetadata => parse_actions_domain_metadata(arr),
         _ => todo!(),
     }
 }
@@ -92,7 +115,7 @@ fn parse_action_metadata(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item
     let schema_strings = cast_struct_column::<StringArray>(arr, "schemaString")?;
     let metadata = ids
         .into_iter()
-        .zip(schema_strings.into_iter())
+        .zip(schema_strings)
         .filter_map(|(maybe_id, maybe_schema_string)| {
             if let (Some(id), Some(schema_string)) = (maybe_id, maybe_schema_string) {
                 Some(Metadata::new(
@@ -169,7 +192,7 @@ fn parse_action_metadata(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item
             .ok_or(Error::MissingData("expected value column in map".into()))?;
         metadata.configuration = keys
             .into_iter()
-            .zip(values.into_iter())
+            .zip(values)
             .filter_map(|(k, v)| k.map(|key| (key.to_string(), v.map(|vv| vv.to_string()))))
             .collect::<HashMap<_, _>>();
     };
@@ -182,7 +205,7 @@ fn parse_action_protocol(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item
     let min_writer = cast_struct_column::<Int32Array>(arr, "minWriterVersion")?;
     let protocol = min_reader
         .into_iter()
-        .zip(min_writer.into_iter())
+        .zip(min_writer)
         .filter_map(|(r, w)| {
             if let (Some(min_reader_version), Some(min_wrriter_version)) = (r, w) {
                 Some(Protocol::new(min_reader_version, min_wrriter_version))
@@ -247,9 +270,21 @@ fn parse_actions_add(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item = A
     let sizes = cast_struct_column::<Int64Array>(arr, "size")?;
     let modification_times = cast_struct_column::<Int64Array>(arr, "modificationTime")?;
     let data_changes = cast_struct_column::<BooleanArray>(arr, "dataChange")?;
+    // checkpoints may store the partition values typed in addition to the string map
+    let mut parsed_partition_values =
+        match cast_struct_column::<StructArray>(arr, "partitionValues_parsed") {
+            Ok(parsed) => Some(parse_partition_values(parsed)?.into_iter()),
+            Err(_) => None,
+        };
     let partition_values = cast_struct_column::<MapArray>(arr, "partitionValues")?
         .iter()
-        .map(|data| data.map(|d| struct_array_to_map(&d).unwrap()));
+        .map(move |data| {
+            parsed_partition_values
+                .as_mut()
+                .and_then(|parsed| parsed.next())
+                .flatten()
+                .or_else(|| data.map(|d| struct_array_to_map(&d).unwrap()))
+        });
 
     let tags = if let Ok(stats) = cast_struct_column::<MapArray>(arr, "tags") {
         Either::Left(
@@ -258,33 +293,33 @@ fn parse_actions_add(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item = A
                 .map(|data| data.map(|d| struct_array_to_map(&d).unwrap())),
         )
     } else {
-        Either::Right(std::iter::repeat(None).take(sizes.len()))
+        Either::Right(std::iter::repeat_n(None, sizes.len()))
     };
 
     let stats = if let Ok(stats) = cast_struct_column::<StringArray>(arr, "stats") {
         Either::Left(stats.into_iter())
     } else {
-        Either::Right(std::iter::repeat(None).take(sizes.len()))
+        Either::Right(std::iter::repeat_n(None, sizes.len()))
     };
 
     let base_row_ids = if let Ok(row_ids) = cast_struct_column::<Int64Array>(arr, "baseRowId") {
         Either::Left(row_ids.into_iter())
     } else {
-        Either::Right(std::iter::repeat(None).take(sizes.len()))
+        Either::Right(std::iter::repeat_n(None, sizes.len()))
     };
 
     let commit_versions =
         if let Ok(versions) = cast_struct_column::<Int64Array>(arr, "defaultRowCommitVersion") {
             Either::Left(versions.into_iter())
         } else {
-            Either::Right(std::iter::repeat(None).take(sizes.len()))
+            Either::Right(std::iter::repeat_n(None, sizes.len()))
         };
 
     let deletion_vectors = if let Ok(dvs) = cast_struct_column::<StructArray>(arr, "deletionVector")
     {
         Either::Left(parse_dv(dvs)?)
     } else {
-        Either::Right(std::iter::repeat(None).take(sizes.len()))
+        Either::Right(std::iter::repeat_n(None, sizes.len()))
     };
 
     let zipped = izip!(
@@ -339,6 +374,44 @@ fn parse_actions_add(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item = A
     Ok(Box::new(zipped.flatten().map(Action::Add)))
 }
 
+/// Partition values as stored in the `partitionValues` map of add actions.
+type PartitionValues = HashMap<String, Option<String>>;
+
+/// Serialize the typed partition values of a checkpoint's `partitionValues_parsed` column as
+/// in the `partitionValues` map, or `None` for rows without typed partition values.
+fn parse_partition_values(arr: &StructArray) -> DeltaResult<Vec<Option<PartitionValues>>> {
+    let columns = arr
+        .fields()
+        .iter()
+        .zip(arr.columns())
+        .map(|(field, column)| {
+            // timestamps are serialized in UTC without time zone, as in the string map
+            let column = match column.data_type() {
+                ArrowDataType::Timestamp(unit, Some(_)) => {
+                    arrow_cast::cast(column, &ArrowDataType::Timestamp(unit.clone(), None))?
+                }
+                _ => column.clone(),
+            };
+            let values = arrow_cast::cast(&column, &ArrowDataType::Utf8)?;
+            Ok((field.name().clone(), values))
+        })
+        .collect::<DeltaResult<Vec<_>>>()?;
+    Ok((0..arr.len())
+        .map(|idx| {
+            arr.is_valid(idx).then(|| {
+                columns
+                    .iter()
+                    .map(|(name, values)| {
+                        let values = values.as_string::<i32>();
+                        let value = values.is_valid(idx).then(|| values.value(idx).to_string());
+                        (name.clone(), value)
+                    })
+                    .collect()
+            })
+        })
+        .collect())
+}
+
 fn parse_actions_remove(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item = Action> + '_>> {
     let paths = cast_struct_column::<StringArray>(arr, "path")?;
     let data_changes = cast_struct_column::<BooleanArray>(arr, "dataChange")?;
@@ -347,14 +420,14 @@ fn parse_actions_remove(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item
         if let Ok(ts) = cast_struct_column::<Int64Array>(arr, "deletionTimestamp") {
             Either::Left(ts.into_iter())
         } else {
-            Either::Right(std::iter::repeat(None).take(data_changes.len()))
+            Either::Right(std::iter::repeat_n(None, data_changes.len()))
         };
 
     let extended_file_metadata =
         if let Ok(metas) = cast_struct_column::<BooleanArray>(arr, "extendedFileMetadata") {
             Either::Left(metas.into_iter())
         } else {
-            Either::Right(std::iter::repeat(None).take(data_changes.len()))
+            Either::Right(std::iter::repeat_n(None, data_changes.len()))
         };
 
     let partition_values =
@@ -365,13 +438,13 @@ fn parse_actions_remove(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item
                     .map(|data| data.map(|d| struct_array_to_map(&d).unwrap())),
             )
         } else {
-            Either::Right(std::iter::repeat(None).take(data_changes.len()))
+            Either::Right(std::iter::repeat_n(None, data_changes.len()))
         };
 
     let sizes = if let Ok(size) = cast_struct_column::<Int64Array>(arr, "size") {
         Either::Left(size.into_iter())
     } else {
-        Either::Right(std::iter::repeat(None).take(data_changes.len()))
+        Either::Right(std::iter::repeat_n(None, data_changes.len()))
     };
 
     let tags = if let Ok(tags) = cast_struct_column::<MapArray>(arr, "tags") {
@@ -380,27 +453,27 @@ fn parse_actions_remove(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item
                 .map(|data| data.map(|d| struct_array_to_map(&d).unwrap())),
         )
     } else {
-        Either::Right(std::iter::repeat(None).take(data_changes.len()))
+        Either::Right(std::iter::repeat_n(None, data_changes.len()))
     };
 
     let deletion_vectors = if let Ok(dvs) = cast_struct_column::<StructArray>(arr, "deletionVector")
     {
         Either::Left(parse_dv(dvs)?)
     } else {
-        Either::Right(std::iter::repeat(None).take(data_changes.len()))
+        Either::Right(std::iter::repeat_n(None, data_changes.len()))
     };
 
     let base_row_ids = if let Ok(row_ids) = cast_struct_column::<Int64Array>(arr, "baseRowId") {
         Either::Left(row_ids.into_iter())
     } else {
-        Either::Right(std::iter::repeat(None).take(data_changes.len()))
+        Either::Right(std::iter::repeat_n(None, data_changes.len()))
     };
 
     let commit_versions =
         if let Ok(row_ids) = cast_struct_column::<Int64Array>(arr, "defaultRowCommitVersion") {
             Either::Left(row_ids.into_iter())
         } else {
-            Either::Right(std::iter::repeat(None).take(data_changes.len()))
+            Either::Right(std::iter::repeat_n(None, data_changes.len()))
         };
 
     let zipped = izip!(
@@ -451,6 +524,153 @@ fn parse_actions_remove(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item
     Ok(Box::new(zipped.flatten().map(Action::Remove)))
 }
 
+fn parse_actions_cdc(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item = Action> + '_>> {
+    let paths = cast_struct_column::<StringArray>(arr, "path")?;
+    let sizes = cast_struct_column::<Int64Array>(arr, "size")?;
+    let data_changes = cast_struct_column::<BooleanArray>(arr, "dataChange")?;
+
+    let partition_values =
+        if let Ok(values) = cast_struct_column::<MapArray>(arr, "partitionValues") {
+            Either::Left(
+                values
+                    .iter()
+                    .map(|data| data.map(|d| struct_array_to_map(&d).unwrap())),
+            )
+        } else {
+            Either::Right(std::iter::repeat_n(None, sizes.len()))
+        };
+
+    let tags = if let Ok(tags) = cast_struct_column::<MapArray>(arr, "tags") {
+        Either::Left(
+            tags.iter()
+                .map(|data| data.map(|d| struct_array_to_map(&d).unwrap())),
+        )
+    } else {
+        Either::Right(std::iter::repeat_n(None, sizes.len()))
+    };
+
+    let zipped = izip!(paths, sizes, data_changes, partition_values, tags);
+    let zipped = zipped.map(
+        |(maybe_path, maybe_size, maybe_data_change, partition_values, tags)| {
+            if let (Some(path), Some(size), Some(data_change)) =
+                (maybe_path, maybe_size, maybe_data_change)
+            {
+                Some(Cdc {
+                    path: path.into(),
+                    partition_values: partition_values.unwrap_or_default(),
+                    size,
+                    data_change,
+                    tags,
+                })
+            } else {
+                None
+            }
+        },
+    );
+
+    Ok(Box::new(zipped.flatten().map(Action::Cdc)))
+}
+
+fn parse_actions_txn(arr: &StructArray) -> DeltaResult<Box<dyn Iterator<Item = Action> + '_>> {
+    let app_ids = cast_struct_column::<StringArray>(arr, "appId")?;
+    let versions = cast_struct_column::<Int64Array>(arr, "version")?;
+    let last_updated =
+        if let Ok(last_updated) = cast_struct_column::<Int64Array>(arr, "lastUpdated") {
+            Either::Left(last_updated.into_iter())
+        } else {
+            Either::Right(std::iter::repeat_n(None, versions.len()))
+        };
+
+    let zipped = izip!(app_ids, versions, last_updated);
+    let zipped = zipped.map(|(maybe_app_id, maybe_version, last_updated)| {
+        if let (Some(app_id), Some(version)) = (maybe_app_id, maybe_version) {
+            Some(Txn {
+                app_id: app_id.into(),
+                version,
+                last_updated,
+            })
+        } else {
+            None
+        }
+    });
+
+    Ok(Box::new(zipped.flatten().map(Action::Txn)))
+}
+
+fn parse_actions_domain_metadata(
+    arr: &StructArray,
+) -> DeltaResult<Box<dyn Iterator<Item = Action> + '_>> {
+    let domains = cast_struct_column::<StringArray>(arr, "domain")?;
+    let configurations = cast_struct_column::<StringArray>(arr, "configuration")?;
+    let removed = cast_struct_column::<BooleanArray>(arr, "removed")?;
+
+    let zipped = izip!(domains, configurations, removed);
+    let zipped = zipped.map(|(maybe_domain, maybe_configuration, maybe_removed)| {
+        if let (Some(domain), Some(configuration), Some(removed)) =
+            (maybe_domain, maybe_configuration, maybe_removed)
+        {
+            Some(DomainMetadata {
+                domain: domain.into(),
+                configuration: configuration.into(),
+                removed,
+            })
+        } else {
+            None
+        }
+    });
+
+    Ok(Box::new(zipped.flatten().map(Action::DomainMetadata)))
+}
+
+fn parse_actions_commit_info(
+    arr: &StructArray,
+) -> DeltaResult<Box<dyn Iterator<Item = Action> + '_>> {
+    // all fields of the commit info are optional, so rows are identified by the struct validity
+    let timestamps = cast_optional_struct_column::<Int64Array>(arr, "timestamp")?;
+    let operations = cast_optional_struct_column::<StringArray>(arr, "operation")?;
+    let isolation_levels = cast_optional_struct_column::<StringArray>(arr, "isolationLevel")?;
+    let blind_appends = cast_optional_struct_column::<BooleanArray>(arr, "isBlindAppend")?;
+    let txn_ids = cast_optional_struct_column::<StringArray>(arr, "txnId")?;
+    let read_versions = cast_optional_struct_column::<Int32Array>(arr, "readVersion")?;
+    let parameters = cast_optional_struct_column::<MapArray>(arr, "operationParameters")?;
+    let metrics = cast_optional_struct_column::<MapArray>(arr, "operationMetrics")?;
+
+    fn string_value(arr: Option<&StringArray>, idx: usize) -> Option<String> {
+        arr.filter(|arr| arr.is_valid(idx))
+            .map(|arr| arr.value(idx).to_string())
+    }
+    fn map_value(
+        arr: Option<&MapArray>,
+        idx: usize,
+    ) -> DeltaResult<Option<HashMap<String, Option<String>>>> {
+        arr.filter(|arr| arr.is_valid(idx))
+            .map(|arr| struct_array_to_map(&arr.value(idx)))
+            .transpose()
+    }
+
+    let mut commit_infos = Vec::new();
+    for idx in (0..arr.len()).filter(|idx| arr.is_valid(*idx)) {
+        commit_infos.push(Action::CommitInfo(CommitInfo {
+            timestamp: timestamps
+                .filter(|arr| arr.is_valid(idx))
+                .map(|arr| arr.value(idx)),
+            operation: string_value(operations, idx),
+            operation_parameters: map_value(parameters, idx)?,
+            operation_metrics: map_value(metrics, idx)?,
+            isolation_level: string_value(isolation_levels, idx),
+            is_blind_append: blind_appends
+                .filter(|arr| arr.is_valid(idx))
+                .map(|arr| arr.value(idx)),
+            txn_id: string_value(txn_ids, idx),
+            read_version: read_versions
+                .filter(|arr| arr.is_valid(idx))
+                .map(|arr| arr.value(idx) as i64),
+        }));
+    }
+
+    Ok(Box::new(commit_infos.into_iter()))
+}
+
 fn parse_dv(
     arr: &StructArray,
 ) -> DeltaResult<impl Iterator<Item = Option<DeletionVectorDescriptor>> + '_> {
@@ -462,7 +682,7 @@ fn parse_dv(
     let offsets = if let Ok(offsets) = cast_struct_column::<Int32Array>(arr, "offset") {
         Either::Left(offsets.into_iter())
     } else {
-        Either::Right(std::iter::repeat(None).take(cardinalities.len()))
+        Either::Right(std::iter::repeat_n(None, cardinalities.len()))
     };
 
     let zipped = izip!(
@@ -510,12 +730,23 @@ fn cast_struct_column<T: 'static>(arr: &StructArray, name: impl AsRef<str>) -> D
         ))
 }
 
+/// Like [`cast_struct_column`], but `None` if the struct has no column `name`.
+fn cast_optional_struct_column<T: 'static>(
+    arr: &StructArray,
+    name: impl AsRef<str>,
+) -> DeltaResult<Option<&T>> {
+    match arr.column_by_name(name.as_ref()) {
+        Some(_) => cast_struct_column(arr, name).map(Some),
+        None => Ok(None),
+    }
+}
+
 fn struct_array_to_map(arr: &StructArray) -> DeltaResult<HashMap<String, Option<String>>> {
     let keys = cast_struct_column::<StringArray>(arr, "key")?;
     let values = cast_struct_column::<StringArray>(arr, "value")?;
     Ok(keys
         .into_iter()
-        .zip(values.into_iter())
+        .zip(values)
         .filter_map(|(k, v)| k.map(|key| (key.to_string(), v.map(|vv| vv.to_string()))))
         .collect())
 }
@@ -524,6 +755,8 @@ fn struct_array_to_map(arr: &StructArray) -> DeltaResult<HashMap<String, Option<
 mod tests {
     use std::sync::Arc;
 
+    use arrow_array::ArrayRef;
+    use arrow_schema::{DataType, Field};
     use object_store::local::LocalFileSystem;
 
     use super::*;
@@ -615,4 +848,217 @@ mod tests {
             .collect::<Vec<_>>();
         println!("{:?}", actions)
     }
+
+    #[test]
+    fn test_parse_commit_info() {
+        let batch = action_batch();
+        let action = parse_action(&batch, &ActionType::CommitInfo)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(action.len(), 1);
+        let Action::CommitInfo(commit_info) = &action[0] else {
+            panic!("expected commit info, got {:?}", action[0]);
+        };
+        assert_eq!(commit_info.timestamp, Some(1677811178585));
+        assert_eq!(commit_info.operation.as_deref(), Some("WRITE"));
+        assert_eq!(commit_info.is_blind_append, Some(true));
+        assert_eq!(
+            commit_info.operation_metrics.as_ref().unwrap()["numFiles"],
+            Some("1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_commit_info_columns() {
+        let commit_info = |timestamp: ArrayRef| {
+            let fields = vec![Field::new("timestamp", timestamp.data_type().clone(), true)];
+            StructArray::try_new(fields.into(), vec![timestamp], None).unwrap()
+        };
+
+        // columns missing from the struct are not set
+        let arr = StructArray::try_new(
+            vec![Field::new("operation", DataType::Utf8, true)].into(),
+            vec![Arc::new(StringArray::from(vec!["WRITE"]))],
+            None,
+        )
+        .unwrap();
+        let actions = parse_actions_commit_info(&arr).unwrap().collect::<Vec<_>>();
+        let Action::CommitInfo(info) = &actions[0] else {
+            panic!("expected commit info, got {:?}", actions[0]);
+        };
+        assert_eq!(info.timestamp, None);
+        assert_eq!(info.operation.as_deref(), Some("WRITE"));
+
+        let arr = commit_info(Arc::new(Int64Array::from(vec![5])));
+        assert_eq!(parse_actions_commit_info(&arr).unwrap().count(), 1);
+
+        // but columns of an unexpected type are an error
+        let arr = commit_info(Arc::new(StringArray::from(vec!["5"])));
+        assert!(matches!(
+            parse_actions_commit_info(&arr).map(|actions| actions.count()),
+            Err(Error::UnexpectedColumnType(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_txn() {
+        let store = Arc::new(LocalFileSystem::new());
+        let handler = DefaultJsonHandler::new(store);
+        let json_strings: StringArray = vec![
+            r#"{"txn":{"appId":"streaming-app","version":3,"lastUpdated":1670892998177}}"#,
+            r#"{"txn":{"appId":"batch-app","version":7}}"#,
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+        ]
+        .into();
+        let batch = handler
+            .parse_json(json_strings, Arc::new(get_log_schema()))
+            .unwrap();
+
+        let actions = parse_action(&batch, &ActionType::Txn)
+            .unwrap()
+            .collect::<Vec<_>>();
+        let expected = vec![
+            Action::Txn(Txn {
+                app_id: "streaming-app".into(),
+                version: 3,
+                last_updated: Some(1670892998177),
+            }),
+            Action::Txn(Txn {
+                app_id: "batch-app".into(),
+                version: 7,
+                last_updated: None,
+            }),
+        ];
+        assert_eq!(actions, expected)
+    }
+
+    #[test]
+    fn test_parse_domain_metadata() {
+        let store = Arc::new(LocalFileSystem::new());
+        let handler = DefaultJsonHandler::new(store);
+        let json_strings: StringArray = vec![
+            r#"{"domainMetadata":{"domain":"delta.clustering","configuration":"{\"clusteringColumns\":[[\"value\"]]}","removed":false}}"#,
+            r#"{"domainMetadata":{"domain":"app","configuration":"","removed":true}}"#,
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+        ]
+        .into();
+        let batch = handler
+            .parse_json(json_strings, Arc::new(get_log_schema()))
+            .unwrap();
+
+        let actions = parse_action(&batch, &ActionType::DomainMetadata)
+            .unwrap()
+            .collect::<Vec<_>>();
+        let expected = vec![
+            Action::DomainMetadata(DomainMetadata {
+                domain: "delta.clustering".into(),
+                configuration: r#"{"clusteringColumns":[["value"]]}"#.into(),
+                removed: false,
+            }),
+            Action::DomainMetadata(DomainMetadata {
+                domain: "app".into(),
+                configuration: "".into(),
+                removed: true,
+            }),
+        ];
+        assert_eq!(actions, expected)
+    }
+
+    #[test]
+    fn test_deserialize_actions() {
+        let parse = |json: &str| serde_json::from_str::<Action>(json).unwrap();
+
+        let action = parse(
+            r#"{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[]}","partitionColumns":["c1"],"configuration":{"delta.enableChangeDataFeed":"true"},"createdTime":1677811175819}}"#,
+        );
+        let Action::Metadata(metadata) = action else {
+            panic!("expected metadata, got {action:?}");
+        };
+        assert_eq!(metadata.id, "testId");
+        assert_eq!(metadata.partition_columns, vec!["c1".to_string()]);
+        assert_eq!(
+            metadata.config_bool("delta.enableChangeDataFeed").unwrap(),
+            Some(true)
+        );
+        assert_eq!(metadata.created_time, Some(1677811175819));
+
+        let action = parse(
+            r#"{"protocol":{"minReaderVersion":3,"minWriterVersion":7,"readerFeatures":["deletionVectors"],"writerFeatures":["deletionVectors"]}}"#,
+        );
+        let expected = Protocol::new(3, 7)
+            .with_reader_features(["deletionVectors"])
+            .with_writer_features(["deletionVectors"]);
+        assert_eq!(action, Action::Protocol(expected));
+
+        let action = parse(
+            r#"{"add":{"path":"part-00000.parquet","partitionValues":{"c1":"4","c2":null},"size":635,"modificationTime":1677811178336,"dataChange":true,"stats":"{\"numRecords\":10}","deletionVector":{"storageType":"u","pathOrInlineDv":"vBn[lx{q8@P<9BNH/isA","offset":1,"sizeInBytes":36,"cardinality":2}}}"#,
+        );
+        let Action::Add(add) = action else {
+            panic!("expected add, got {action:?}");
+        };
+        assert_eq!(add.path, "part-00000.parquet");
+        assert_eq!(add.partition_values["c1"], Some("4".to_string()));
+        assert_eq!(add.partition_values["c2"], None);
+        assert_eq!(add.num_records(), Some(10));
+        assert!(add.tags.is_empty());
+        assert_eq!(add.deletion_vector.unwrap().cardinality, 2);
+
+        let action = parse(
+            r#"{"remove":{"path":"part-00000.parquet","deletionTimestamp":1677811194426,"dataChange":true,"extendedFileMetadata":true,"partitionValues":{},"size":635}}"#,
+        );
+        let Action::Remove(remove) = action else {
+            panic!("expected remove, got {action:?}");
+        };
+        assert_eq!(remove.deletion_timestamp, Some(1677811194426));
+        assert_eq!(remove.size, Some(635));
+        assert_eq!(remove.tags, None);
+
+        let action = parse(r#"{"txn":{"appId":"streaming-app","version":3}}"#);
+        let expected = Txn {
+            app_id: "streaming-app".into(),
+            version: 3,
+            last_updated: None,
+        };
+        assert_eq!(action, Action::Txn(expected));
+
+        let action = parse(
+            r#"{"commitInfo":{"timestamp":1677811178585,"operation":"WRITE","operationParameters":{"mode":"ErrorIfExists","partitionBy":"[]"},"isBlindAppend":true,"engineInfo":"Databricks-Runtime/<unknown>","txnId":"a6a94671-55ef-450e-9546-b8465b9147de"}}"#,
+        );
+        let Action::CommitInfo(commit_info) = action else {
+            panic!("expected commit info, got {action:?}");
+        };
+        assert_eq!(commit_info.timestamp, Some(1677811178585));
+        assert_eq!(
+            commit_info.operation_parameters.unwrap()["mode"],
+            Some("ErrorIfExists".to_string())
+        );
+        assert_eq!(commit_info.read_version, None);
+
+        let action = parse(
+            r#"{"cdc":{"path":"_change_data/cdc-00000.parquet","partitionValues":{},"size":1213,"dataChange":false}}"#,
+        );
+        let expected = Cdc {
+            path: "_change_data/cdc-00000.parquet".into(),
+            partition_values: HashMap::new(),
+            size: 1213,
+            data_change: false,
+            tags: None,
+        };
+        assert_eq!(action, Action::Cdc(expected));
+    }
+
+    #[test]
+    fn test_serialize_action() {
+        let action = Action::Txn(Txn {
+            app_id: "streaming-app".into(),
+            version: 3,
+            last_updated: Some(1670892998177),
+        });
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(
+            json,
+            r#"{"txn":{"appId":"streaming-app","version":3,"lastUpdated":1670892998177}}"#
+        );
+        assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+    }
 }
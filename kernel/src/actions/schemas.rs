@@ -15,6 +15,7 @@ impl ActionType {
             Self::Protocol => get_root("protocol", self.fields()),
             Self::Remove => get_root("remove", self.fields()),
             Self::RowIdHighWaterMark => get_root("rowIdHighWaterMark", self.fields()),
+            Self::Sidecar => get_root("sidecar", self.fields()),
             Self::Txn => get_root("txn", self.fields()),
         }
     }
@@ -29,6 +30,7 @@ impl ActionType {
             Self::Protocol => protocol_fields(),
             Self::Remove => remove_fields(),
             Self::RowIdHighWaterMark => watermark_fields(),
+            Self::Sidecar => sidecar_fields(),
             Self::Txn => txn_fields(),
         }
     }
@@ -72,17 +74,7 @@ fn add_fields() -> Vec<Field> {
             DataType::Map(Arc::new(get_map_field()), false),
             true,
         ),
-        Field::new(
-            "deletionVector",
-            DataType::Struct(Fields::from(vec![
-                Field::new("storageType", DataType::Utf8, false),
-                Field::new("pathOrInlineDv", DataType::Utf8, false),
-                Field::new("offset", DataType::Int32, true),
-                Field::new("sizeInBytes", DataType::Int32, false),
-                Field::new("cardinality", DataType::Int64, false),
-            ])),
-            true,
-        ),
+        get_deletion_vector_field(),
         Field::new("baseRowId", DataType::Int64, true),
         Field::new("defaultRowCommitVersion", DataType::Int64, true),
     ])
@@ -123,6 +115,9 @@ fn remove_fields() -> Vec<Field> {
             DataType::Map(Arc::new(get_map_field()), false),
             true,
         ),
+        get_deletion_vector_field(),
+        Field::new("baseRowId", DataType::Int64, true),
+        Field::new("defaultRowCommitVersion", DataType::Int64, true),
     ])
 }
 
@@ -207,6 +202,14 @@ fn watermark_fields() -> Vec<Field> {
     Vec::from_iter([Field::new("highWaterMark", DataType::Int64, true)])
 }
 
+fn sidecar_fields() -> Vec<Field> {
+    Vec::from_iter([
+        Field::new("path", DataType::Utf8, false),
+        Field::new("sizeInBytes", DataType::Int64, false),
+        Field::new("modificationTime", DataType::Int64, false),
+    ])
+}
+
 fn commit_info_fields() -> Vec<Field> {
     Vec::from_iter([
         Field::new("timestamp", DataType::Int64, true),
@@ -231,11 +234,7 @@ fn commit_info_fields() -> Vec<Field> {
 fn domain_metadata_fields() -> Vec<Field> {
     Vec::from_iter([
         Field::new("domain", DataType::Utf8, true),
-        Field::new(
-            "configuration",
-            DataType::Map(Arc::new(get_map_field()), false),
-            true,
-        ),
+        Field::new("configuration", DataType::Utf8, true),
         Field::new("removed", DataType::Boolean, true),
     ])
 }
@@ -250,3 +249,17 @@ fn get_map_field() -> Field {
         false,
     )
 }
+
+fn get_deletion_vector_field() -> Field {
+    Field::new(
+        "deletionVector",
+        DataType::Struct(Fields::from(vec![
+            Field::new("storageType", DataType::Utf8, false),
+            Field::new("pathOrInlineDv", DataType::Utf8, false),
+            Field::new("offset", DataType::Int32, true),
+            Field::new("sizeInBytes", DataType::Int32, false),
+            Field::new("cardinality", DataType::Int64, false),
+        ])),
+        true,
+    )
+}
@@ -4,16 +4,19 @@ use std::sync::Arc;
 
 use futures::future::{BoxFuture, FutureExt};
 use roaring::RoaringTreemap;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::schema::StructType;
 use crate::{DeltaResult, Error, FileSystemClient};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Format {
     /// Name of the encoding for files in this table
     pub provider: String,
     /// A map containing configuration options for the format
+    #[serde(default)]
     pub options: HashMap<String, String>,
 }
 
@@ -26,7 +29,8 @@ impl Default for Format {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Metadata {
     /// Unique identifier for this table
     pub id: String,
@@ -43,6 +47,7 @@ pub struct Metadata {
     /// The time when this metadata action is created, in milliseconds since the Unix epoch
     pub created_time: Option<i64>,
     /// Configuration options for the metadata action
+    #[serde(default)]
     pub configuration: HashMap<String, Option<String>>,
 }
 
@@ -84,15 +89,39 @@ impl Metadata {
     pub fn schema(&self) -> DeltaResult<StructType> {
         Ok(serde_json::from_str(&self.schema_string)?)
     }
+
+    /// The value of the table property `key`, if it is set.
+    pub fn config_value(&self, key: &str) -> Option<&str> {
+        self.configuration
+            .get(key)
+            .and_then(|value| value.as_deref())
+    }
+
+    /// The value of the boolean table property `key`, if it is set.
+    ///
+    /// Returns an error if the property is set to something other than `true` or `false`.
+    pub fn config_bool(&self, key: &str) -> DeltaResult<Option<bool>> {
+        self.config_value(key)
+            .map(|value| match value.to_ascii_lowercase().as_str() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(Error::Generic(format!(
+                    "Invalid value '{value}' for boolean table property {key}"
+                ))),
+            })
+            .transpose()
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Protocol {
     /// The minimum version of the Delta read protocol that a client must implement
     /// in order to correctly read this table
     pub min_reader_version: i32,
     /// The minimum version of the Delta write protocol that a client must implement
     /// in order to correctly write this table
+    #[serde(rename = "minWriterVersion")]
     pub min_wrriter_version: i32,
     /// A collection of features that a client must implement in order to correctly
     /// read this table (exist only when minReaderVersion is set to 3)
@@ -129,7 +158,8 @@ impl Protocol {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DeletionVectorDescriptor {
     /// A single character to indicate how to access the DV. Legal options are: ['u', 'i', 'p'].
     pub storage_type: String,
@@ -204,20 +234,40 @@ impl DeletionVectorDescriptor {
         }
     }
 
+    /// Decode a deletion vector stored inline in the log, without accessing any storage.
+    ///
+    /// Returns `None` if the deletion vector is stored in a file.
+    pub fn read_inline(&self) -> Option<DeltaResult<RoaringTreemap>> {
+        if self.storage_type != "i" {
+            return None;
+        }
+        let bytes = match z85::decode(&self.path_or_inline_dv) {
+            Ok(bytes) if bytes.len() >= 12 => bytes,
+            _ => {
+                return Some(Err(Error::DeletionVector(
+                    "Failed to decode DV".to_string(),
+                )))
+            }
+        };
+        Some(
+            RoaringTreemap::deserialize_from(&bytes[12..])
+                .map_err(|err| Error::DeletionVector(err.to_string())),
+        )
+    }
+
     // TODO read only required byte ranges
     pub fn read(
         &self,
         fs_client: Arc<dyn FileSystemClient>,
         parent: Url,
     ) -> DeltaResult<BoxFuture<'static, DeltaResult<RoaringTreemap>>> {
+        if let Some(rtm) = self.read_inline() {
+            return Ok(async { rtm }.boxed());
+        }
         match self.absolute_path(&parent)? {
-            None => {
-                let bytes = z85::decode(&self.path_or_inline_dv)
-                    .map_err(|_| Error::DeletionVector("Failed to decode DV".to_string()))?;
-                let rtm = RoaringTreemap::deserialize_from(&bytes[12..])
-                    .map_err(|err| Error::DeletionVector(err.to_string()));
-                Ok(async { rtm }.boxed())
-            }
+            None => Err(Error::DeletionVector(
+                "Missing path of deletion vector".to_string(),
+            )),
             Some(path) => {
                 let offset = self.offset;
                 let size_in_bytes = self.size_in_bytes;
@@ -264,7 +314,8 @@ impl DeletionVectorDescriptor {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Add {
     /// A relative path to a data file from the root of the table or an absolute path to a file
     /// that should be added to the table. The path is a URI as specified by
@@ -292,6 +343,7 @@ pub struct Add {
     pub stats: Option<String>,
 
     /// Map containing metadata about this logical file.
+    #[serde(default)]
     pub tags: HashMap<String, Option<String>>,
 
     /// Information about deletion vector (DV) associated with this add action
@@ -307,6 +359,44 @@ pub struct Add {
 }
 
 impl Add {
+    /// Path of the data file, relative to the table root or absolute.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Size of the data file in bytes.
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    /// Map from partition column to value for this logical file.
+    pub fn partition_values(&self) -> &HashMap<String, Option<String>> {
+        &self.partition_values
+    }
+
+    /// Map containing custom metadata about this logical file, e.g. clustering information.
+    pub fn tags(&self) -> &HashMap<String, Option<String>> {
+        &self.tags
+    }
+
+    /// The raw statistics JSON string, if statistics were collected for this file.
+    pub fn stats(&self) -> Option<&str> {
+        self.stats.as_deref()
+    }
+
+    /// Number of records in the data file, as recorded in the file statistics.
+    ///
+    /// Returns `None` if the file has no statistics or they do not contain a record count.
+    pub fn num_records(&self) -> Option<i64> {
+        let stats: serde_json::Value = serde_json::from_str(self.stats()?).ok()?;
+        stats.get("numRecords")?.as_i64()
+    }
+
+    /// Descriptor of the deletion vector associated with this file, if any.
+    pub fn deletion_vector(&self) -> Option<&DeletionVectorDescriptor> {
+        self.deletion_vector.as_ref()
+    }
+
     pub fn dv_unique_id(&self) -> Option<String> {
         self.deletion_vector.clone().map(|dv| dv.unique_id())
     }
@@ -317,7 +407,8 @@ impl Add {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Remove {
     /// A relative path to a data file from the root of the table or an absolute path to a file
     /// that should be added to the table. The path is a URI as specified by
@@ -363,6 +454,92 @@ impl Remove {
     }
 }
 
+/// A file containing only the data changed as part of a transaction, used by the change data feed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cdc {
+    /// A relative path to a change data file from the root of the table or an absolute path to a
+    /// change data file that should be added to the table. The path is a URI as specified by
+    /// [RFC 2396 URI Generic Syntax], which needs to be decoded to get the file path.
+    ///
+    /// [RFC 2396 URI Generic Syntax]: https://www.ietf.org/rfc/rfc2396.txt
+    pub path: String,
+
+    /// A map from partition column to value for this file.
+    pub partition_values: HashMap<String, Option<String>>,
+
+    /// The size of this file in bytes
+    pub size: i64,
+
+    /// Should always be set to `false` for `cdc` actions because they *do not* change the
+    /// underlying data of the table
+    pub data_change: bool,
+
+    /// Map containing metadata about this file.
+    pub tags: Option<HashMap<String, Option<String>>>,
+}
+
+/// The latest version of an application specific transaction committed to the table, used to
+/// make writes of e.g. streaming sources idempotent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Txn {
+    /// A unique identifier for the application performing the transaction
+    pub app_id: String,
+
+    /// An application-specific numeric identifier for this transaction
+    pub version: i64,
+
+    /// The time when this transaction action is created, in milliseconds since the Unix epoch
+    pub last_updated: Option<i64>,
+}
+
+/// The configuration of a named metadata domain, used by table features such as clustering
+/// to store their state in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainMetadata {
+    /// The name of the domain, domains starting with `delta.` are reserved for table features
+    pub domain: String,
+
+    /// The configuration of the domain, the format is defined by the owner of the domain
+    pub configuration: String,
+
+    /// Whether the domain was removed, the configuration of removed domains is not active
+    pub removed: bool,
+}
+
+/// Provenance information about the operation which created a commit.
+///
+/// Only the commonly used fields are represented, all of them are optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitInfo {
+    /// The time this commit was created, in milliseconds since the Unix epoch
+    pub timestamp: Option<i64>,
+
+    /// The name of the operation, e.g. `WRITE` or `MERGE`
+    pub operation: Option<String>,
+
+    /// Parameters of the operation, e.g. the write mode
+    pub operation_parameters: Option<HashMap<String, Option<String>>>,
+
+    /// Metrics of the operation, e.g. the number of files written
+    pub operation_metrics: Option<HashMap<String, Option<String>>>,
+
+    /// The isolation level the commit was performed with
+    pub isolation_level: Option<String>,
+
+    /// Whether the commit only added data, without reading the table first
+    pub is_blind_append: Option<bool>,
+
+    /// A unique identifier of the transaction
+    pub txn_id: Option<String>,
+
+    /// The version of the table the transaction read before committing
+    pub read_version: Option<i64>,
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -459,4 +636,22 @@ mod tests {
         let found = tree_map.iter().collect::<Vec<_>>();
         assert_eq!(found, expected)
     }
+
+    #[test]
+    fn test_deletion_vector_read_inline() {
+        let inline = DeletionVectorDescriptor {
+            storage_type: "i".to_string(),
+            path_or_inline_dv: "0000000000000000rr910000000000iXQKl0rr91000625c8Xg000310SSi2"
+                .to_string(),
+            offset: None,
+            size_in_bytes: 44,
+            cardinality: 3,
+        };
+        let tree_map = inline.read_inline().unwrap().unwrap();
+        let found = tree_map.iter().collect::<Vec<_>>();
+        assert_eq!(found, vec![0, 1, 2]);
+
+        assert!(dv_example().read_inline().is_none());
+        assert!(dv_absolute().read_inline().is_none());
+    }
 }
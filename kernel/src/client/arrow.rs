@@ -4,14 +4,10 @@ use arrow_schema::{
     ArrowError, DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema,
     SchemaRef as ArrowSchemaRef, TimeUnit,
 };
-use lazy_static::lazy_static;
-use regex::Regex;
 
-use crate::schema::{ArrayType, DataType, MapType, PrimitiveType, StructField, StructType};
-
-lazy_static! {
-    static ref DECIMAL_REGEX: Regex = Regex::new(r"\((\d{1,2}),(\d{1,2})\)").unwrap();
-}
+use crate::schema::{
+    ArrayType, DataType, MapType, MetadataValue, PrimitiveType, StructField, StructType,
+};
 
 impl TryFrom<&StructType> for ArrowSchema {
     type Error = ArrowError;
@@ -34,9 +30,14 @@ impl TryFrom<&StructField> for ArrowField {
         let metadata = f
             .metadata()
             .iter()
-            .map(|(key, val)| Ok((key.clone(), serde_json::to_string(val)?)))
-            .collect::<Result<_, serde_json::Error>>()
-            .map_err(|err| ArrowError::JsonError(err.to_string()))?;
+            .map(|(key, val)| {
+                let val = match val {
+                    MetadataValue::Number(num) => num.to_string(),
+                    MetadataValue::String(s) => s.clone(),
+                };
+                (key.clone(), val)
+            })
+            .collect();
 
         let field = ArrowField::new(
             f.name(),
@@ -99,21 +100,9 @@ impl TryFrom<&DataType> for ArrowDataType {
                     PrimitiveType::Double => Ok(ArrowDataType::Float64),
                     PrimitiveType::Boolean => Ok(ArrowDataType::Boolean),
                     PrimitiveType::Binary => Ok(ArrowDataType::Binary),
-                    PrimitiveType::Deciaml(decimal) if DECIMAL_REGEX.is_match(decimal) => {
-                        let extract = DECIMAL_REGEX.captures(decimal).ok_or_else(|| {
-                            ArrowError::SchemaError(format!(
-                                "Invalid decimal type for Arrow: {decimal}"
-                            ))
-                        })?;
-                        let precision = extract.get(1).and_then(|v| v.as_str().parse::<u8>().ok());
-                        let scale = extract.get(2).and_then(|v| v.as_str().parse::<i8>().ok());
-                        match (precision, scale) {
-                            // TODO how do we decide which variant (128 / 256) to use?
-                            (Some(p), Some(s)) => Ok(ArrowDataType::Decimal128(p, s)),
-                            _ => Err(ArrowError::SchemaError(format!(
-                                "Invalid precision or scale decimal type for Arrow: {decimal}"
-                            ))),
-                        }
+                    PrimitiveType::Decimal(precision, scale) => {
+                        // TODO how do we decide which variant (128 / 256) to use?
+                        Ok(ArrowDataType::Decimal128(*precision, *scale as i8))
                     }
                     PrimitiveType::Date => {
                         // A calendar date, represented as a year-month-day triple without a
@@ -122,11 +111,14 @@ impl TryFrom<&DataType> for ArrowDataType {
                     }
                     PrimitiveType::Timestamp => {
                         // Issue: https://github.com/delta-io/delta/issues/643
+                        Ok(ArrowDataType::Timestamp(
+                            TimeUnit::Microsecond,
+                            Some("UTC".into()),
+                        ))
+                    }
+                    PrimitiveType::TimestampNtz => {
                         Ok(ArrowDataType::Timestamp(TimeUnit::Microsecond, None))
                     }
-                    s => Err(ArrowError::SchemaError(format!(
-                        "Invalid data type for Arrow: {s:?}"
-                    ))),
                 }
             }
             DataType::Struct(s) => Ok(ArrowDataType::Struct(
@@ -140,25 +132,7 @@ impl TryFrom<&DataType> for ArrowDataType {
                 &ArrayType,
             >>::try_from(a)?))),
             DataType::Map(m) => Ok(ArrowDataType::Map(
-                Arc::new(ArrowField::new(
-                    "entries",
-                    ArrowDataType::Struct(
-                        vec![
-                            ArrowField::new(
-                                "key",
-                                <ArrowDataType as TryFrom<&DataType>>::try_from(m.key_type())?,
-                                false,
-                            ),
-                            ArrowField::new(
-                                "value",
-                                <ArrowDataType as TryFrom<&DataType>>::try_from(m.value_type())?,
-                                m.value_contains_null(),
-                            ),
-                        ]
-                        .into(),
-                    ),
-                    true,
-                )),
+                Arc::new(<ArrowField as TryFrom<&MapType>>::try_from(m.as_ref())?),
                 false,
             )),
         }
@@ -195,7 +169,13 @@ impl TryFrom<&ArrowField> for StructField {
             arrow_field.data_type().try_into()?,
             arrow_field.is_nullable(),
         )
-        .with_metadata(arrow_field.metadata().iter().map(|(k, v)| (k.clone(), v))))
+        .with_metadata(arrow_field.metadata().iter().map(|(k, v)| {
+            let v = match v.parse::<i32>() {
+                Ok(num) => MetadataValue::Number(num),
+                Err(_) => MetadataValue::String(v.clone()),
+            };
+            (k.clone(), v)
+        })))
     }
 }
 
@@ -213,27 +193,24 @@ impl TryFrom<&ArrowDataType> for DataType {
             ArrowDataType::UInt64 => Ok(DataType::Primitive(PrimitiveType::Long)), // undocumented type
             ArrowDataType::UInt32 => Ok(DataType::Primitive(PrimitiveType::Integer)),
             ArrowDataType::UInt16 => Ok(DataType::Primitive(PrimitiveType::Short)),
-            ArrowDataType::UInt8 => Ok(DataType::Primitive(PrimitiveType::Boolean)),
+            ArrowDataType::UInt8 => Ok(DataType::Primitive(PrimitiveType::Byte)),
             ArrowDataType::Float32 => Ok(DataType::Primitive(PrimitiveType::Float)),
             ArrowDataType::Float64 => Ok(DataType::Primitive(PrimitiveType::Double)),
             ArrowDataType::Boolean => Ok(DataType::Primitive(PrimitiveType::Boolean)),
             ArrowDataType::Binary => Ok(DataType::Primitive(PrimitiveType::Binary)),
             ArrowDataType::FixedSizeBinary(_) => Ok(DataType::Primitive(PrimitiveType::Binary)),
             ArrowDataType::LargeBinary => Ok(DataType::Primitive(PrimitiveType::Binary)),
-            ArrowDataType::Decimal128(p, s) => Ok(DataType::Primitive(PrimitiveType::Deciaml(
-                format!("decimal({p},{s})"),
-            ))),
-            ArrowDataType::Decimal256(p, s) => Ok(DataType::Primitive(PrimitiveType::Deciaml(
-                format!("decimal({p},{s})"),
-            ))),
+            ArrowDataType::Decimal128(p, s) | ArrowDataType::Decimal256(p, s)
+                if *p <= 38 && *s >= 0 && *s as u8 <= *p =>
+            {
+                Ok(DataType::Primitive(PrimitiveType::Decimal(*p, *s as u8)))
+            }
             ArrowDataType::Date32 => Ok(DataType::Primitive(PrimitiveType::Date)),
             ArrowDataType::Date64 => Ok(DataType::Primitive(PrimitiveType::Date)),
             ArrowDataType::Timestamp(TimeUnit::Microsecond, None) => {
-                Ok(DataType::Primitive(PrimitiveType::Timestamp))
+                Ok(DataType::Primitive(PrimitiveType::TimestampNtz))
             }
-            ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(tz))
-                if tz.eq_ignore_ascii_case("utc") =>
-            {
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, Some(_)) => {
                 Ok(DataType::Primitive(PrimitiveType::Timestamp))
             }
             ArrowDataType::Struct(fields) => {
@@ -267,7 +244,9 @@ impl TryFrom<&ArrowDataType> for DataType {
                         value_type_nullable,
                     ))))
                 } else {
-                    panic!("DataType::Map should contain a struct field child");
+                    Err(ArrowError::SchemaError(
+                        "DataType::Map should contain a struct field child".into(),
+                    ))
                 }
             }
             s => Err(ArrowError::SchemaError(format!(
@@ -276,3 +255,159 @@ impl TryFrom<&ArrowDataType> for DataType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn primitive(primitive: PrimitiveType) -> DataType {
+        DataType::Primitive(primitive)
+    }
+
+    fn round_trip(data_type: DataType, expected: ArrowDataType) {
+        let arrow_type = ArrowDataType::try_from(&data_type).unwrap();
+        assert_eq!(arrow_type, expected);
+        assert_eq!(DataType::try_from(&arrow_type).unwrap(), data_type);
+    }
+
+    #[test]
+    fn test_primitive_round_trip() {
+        for (data_type, expected) in [
+            (PrimitiveType::String, ArrowDataType::Utf8),
+            (PrimitiveType::Long, ArrowDataType::Int64),
+            (PrimitiveType::Integer, ArrowDataType::Int32),
+            (PrimitiveType::Short, ArrowDataType::Int16),
+            (PrimitiveType::Byte, ArrowDataType::Int8),
+            (PrimitiveType::Float, ArrowDataType::Float32),
+            (PrimitiveType::Double, ArrowDataType::Float64),
+            (PrimitiveType::Boolean, ArrowDataType::Boolean),
+            (PrimitiveType::Binary, ArrowDataType::Binary),
+            (PrimitiveType::Date, ArrowDataType::Date32),
+        ] {
+            round_trip(primitive(data_type), expected);
+        }
+    }
+
+    #[test]
+    fn test_decimal_round_trip() {
+        round_trip(
+            primitive(PrimitiveType::Decimal(10, 2)),
+            ArrowDataType::Decimal128(10, 2),
+        );
+        round_trip(
+            primitive(PrimitiveType::Decimal(38, 38)),
+            ArrowDataType::Decimal128(38, 38),
+        );
+        assert!(DataType::try_from(&ArrowDataType::Decimal128(10, -2)).is_err());
+        assert!(DataType::try_from(&ArrowDataType::Decimal256(76, 2)).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_round_trip() {
+        round_trip(
+            primitive(PrimitiveType::Timestamp),
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        );
+        round_trip(
+            primitive(PrimitiveType::TimestampNtz),
+            ArrowDataType::Timestamp(TimeUnit::Microsecond, None),
+        );
+        // any timezone denotes an instant in time
+        assert_eq!(
+            DataType::try_from(&ArrowDataType::Timestamp(
+                TimeUnit::Microsecond,
+                Some("+01:00".into())
+            ))
+            .unwrap(),
+            primitive(PrimitiveType::Timestamp)
+        );
+    }
+
+    #[test]
+    fn test_nested_round_trip() {
+        let array = DataType::Array(Box::new(ArrayType::new(
+            primitive(PrimitiveType::Integer),
+            false,
+        )));
+        round_trip(
+            array.clone(),
+            ArrowDataType::List(Arc::new(ArrowField::new(
+                "item",
+                ArrowDataType::Int32,
+                false,
+            ))),
+        );
+
+        let map = DataType::Map(Box::new(MapType::new(
+            primitive(PrimitiveType::String),
+            primitive(PrimitiveType::Long),
+            true,
+        )));
+        round_trip(
+            map.clone(),
+            ArrowDataType::Map(
+                Arc::new(ArrowField::new(
+                    "entries",
+                    ArrowDataType::Struct(
+                        vec![
+                            ArrowField::new("key", ArrowDataType::Utf8, false),
+                            ArrowField::new("value", ArrowDataType::Int64, true),
+                        ]
+                        .into(),
+                    ),
+                    false,
+                )),
+                false,
+            ),
+        );
+
+        let nested = DataType::Struct(Box::new(StructType::new(vec![
+            StructField::new("a", array, true),
+            StructField::new("b", map, false),
+            StructField::new(
+                "c",
+                DataType::Struct(Box::new(StructType::new(vec![StructField::new(
+                    "d",
+                    primitive(PrimitiveType::Timestamp),
+                    false,
+                )]))),
+                true,
+            ),
+        ])));
+        let arrow_type = ArrowDataType::try_from(&nested).unwrap();
+        assert_eq!(DataType::try_from(&arrow_type).unwrap(), nested);
+    }
+
+    #[test]
+    fn test_schema_round_trip_with_metadata() {
+        let schema = StructType::new(vec![
+            StructField::new("id", primitive(PrimitiveType::Long), false).with_metadata([
+                ("delta.columnMapping.id", MetadataValue::Number(4)),
+                (
+                    "delta.columnMapping.physicalName",
+                    MetadataValue::String("col-5f422f40".into()),
+                ),
+            ]),
+            StructField::new("value", primitive(PrimitiveType::Decimal(5, 1)), true),
+        ]);
+        let arrow_schema = ArrowSchema::try_from(&schema).unwrap();
+
+        let id = arrow_schema.field_with_name("id").unwrap();
+        assert!(!id.is_nullable());
+        assert_eq!(
+            id.metadata(),
+            &HashMap::from([
+                ("delta.columnMapping.id".to_string(), "4".to_string()),
+                (
+                    "delta.columnMapping.physicalName".to_string(),
+                    "col-5f422f40".to_string()
+                ),
+            ])
+        );
+        assert!(arrow_schema.field_with_name("value").unwrap().is_nullable());
+
+        assert_eq!(StructType::try_from(&arrow_schema).unwrap(), schema);
+    }
+}
@@ -0,0 +1,19 @@
+//! Executors running the background work of the default client
+
+use futures::executor::ThreadPool;
+use futures::future::BoxFuture;
+
+/// Runs tasks of the default client in the background, e.g. reading and decoding files.
+///
+/// This allows CPU bound work like decoding to run in parallel on the runtime of the engine,
+/// rather than on the task polling the returned streams.
+pub trait TaskExecutor: Send + Sync + std::fmt::Debug {
+    /// Run `task` to completion in the background.
+    fn spawn(&self, task: BoxFuture<'static, ()>);
+}
+
+impl TaskExecutor for ThreadPool {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        self.spawn_ok(task)
+    }
+}
@@ -0,0 +1,90 @@
+//! Default Expression handler.
+//!
+//! Expressions are evaluated with an [`ArrowExpressionCompiler`].
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use arrow_schema::{Field, Schema, SchemaRef as ArrowSchemaRef};
+
+use crate::expressions::{ArrowExpressionCompiler, CompiledExpression, Expression};
+use crate::{DeltaResult, Error, ExpressionEvaluator, ExpressionHandler};
+
+#[derive(Debug, Default)]
+pub struct DefaultExpressionHandler {}
+
+impl DefaultExpressionHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ExpressionHandler for DefaultExpressionHandler {
+    fn get_evaluator(
+        &self,
+        schema: ArrowSchemaRef,
+        expression: Expression,
+    ) -> Arc<dyn ExpressionEvaluator> {
+        let compiled = ArrowExpressionCompiler::new(schema)
+            .compile(&expression)
+            .map_err(|err| err.to_string());
+        Arc::new(DefaultExpressionEvaluator { compiled })
+    }
+}
+
+/// Evaluates a compiled expression, returning its result as the single `output` column.
+///
+/// Errors compiling the expression are reported when evaluating it.
+#[allow(missing_debug_implementations)]
+pub struct DefaultExpressionEvaluator {
+    compiled: Result<CompiledExpression, String>,
+}
+
+impl ExpressionEvaluator for DefaultExpressionEvaluator {
+    fn evaluate(&self, batch: &RecordBatch) -> DeltaResult<RecordBatch> {
+        let compiled = self
+            .compiled
+            .as_ref()
+            .map_err(|err| Error::Generic(err.clone()))?;
+        let output = compiled(batch)?;
+        let schema = Schema::new(vec![Field::new("output", output.data_type().clone(), true)]);
+        Ok(RecordBatch::try_new(Arc::new(schema), vec![output])?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Array, BooleanArray, Int32Array};
+    use arrow_schema::DataType;
+
+    use super::*;
+    use crate::expressions::Scalar;
+
+    #[test]
+    fn test_evaluate() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )
+        .unwrap();
+        let handler = DefaultExpressionHandler::new();
+
+        let predicate = Expression::GreaterThan(
+            Box::new(Expression::Column("a".to_string())),
+            Box::new(Expression::Literal(Scalar::from(1))),
+        );
+        let result = handler
+            .get_evaluator(schema.clone(), predicate)
+            .evaluate(&batch)
+            .unwrap();
+        let output = result.column(0).as_any().downcast_ref::<BooleanArray>();
+        assert_eq!(output, Some(&BooleanArray::from(vec![false, true, true])));
+
+        let missing = Expression::Column("b".to_string());
+        assert!(handler
+            .get_evaluator(schema, missing)
+            .evaluate(&batch)
+            .is_err());
+    }
+}
@@ -40,7 +40,7 @@ impl FileSystemClient for ObjectStoreFileSystemClient {
                 location.set_path(&format!("/{}", meta.location.as_ref()));
                 FileMeta {
                     location,
-                    last_modified: meta.last_modified.timestamp(),
+                    last_modified: meta.last_modified.timestamp_millis(),
                     size: meta.size,
                 }
             })
@@ -1,19 +1,24 @@
 //! Default Json handler implementation
 
 use std::io::{BufReader, Cursor};
+use std::num::NonZeroUsize;
 use std::ops::Range;
 use std::sync::Arc;
 use std::task::{ready, Poll};
 
-use arrow_array::{RecordBatch, StringArray};
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray, StructArray};
+use arrow_cast::{cast_with_options, CastOptions};
 use arrow_json::ReaderBuilder;
-use arrow_schema::SchemaRef as ArrowSchemaRef;
+use arrow_schema::{DataType, Field, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
 use arrow_select::concat::concat_batches;
 use bytes::{Buf, Bytes};
+use futures::channel::oneshot;
+use futures::future::{BoxFuture, Future, FutureExt};
 use futures::stream::{StreamExt, TryStreamExt};
 use object_store::path::Path;
 use object_store::{DynObjectStore, GetResult};
 
+use super::executor::TaskExecutor;
 use super::file_handler::{FileOpenFuture, FileOpener};
 use crate::file_handler::FileStream;
 use crate::schema::SchemaRef;
@@ -30,11 +35,59 @@ pub struct JsonReadContext {
 #[derive(Debug)]
 pub struct DefaultJsonHandler {
     store: Arc<DynObjectStore>,
+    readahead: usize,
+    batch_size: usize,
+    strict: bool,
+    executor: Option<Arc<dyn TaskExecutor>>,
 }
 
 impl DefaultJsonHandler {
     pub fn new(store: Arc<DynObjectStore>) -> Self {
-        Self { store }
+        Self {
+            store,
+            readahead: 1,
+            batch_size: 1024,
+            strict: false,
+            executor: None,
+        }
+    }
+
+    /// Set the maximum number of rows the JSON reader decodes into one batch, defaults to 1024.
+    ///
+    /// Applies to the batches yielded by [`JsonHandler::read_json_files`] and to the batches
+    /// decoded by [`JsonHandler::parse_json`] before they are combined.
+    pub fn with_batch_size(mut self, batch_size: NonZeroUsize) -> Self {
+        self.batch_size = batch_size.get();
+        self
+    }
+
+    /// Set whether [`JsonHandler::read_json_files`] fails on unknown top-level keys,
+    /// defaults to `false`.
+    ///
+    /// By default keys that are not part of the read schema are ignored, so commits written by
+    /// newer writers with additional action types can still be read. In strict mode every line
+    /// of a file must only contain keys of the read schema, e.g. known action types.
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Set the number of files to read and parse concurrently, defaults to 1.
+    ///
+    /// Batches are still yielded in the order of the files passed to
+    /// [`JsonHandler::read_json_files`], and a file failing to read does not prevent reading
+    /// the subsequent files. Files are only decoded in parallel if a [`TaskExecutor`] is set
+    /// with [`DefaultJsonHandler::with_task_executor`], otherwise only their reads overlap.
+    pub fn with_readahead(mut self, readahead: NonZeroUsize) -> Self {
+        self.readahead = readahead.get();
+        self
+    }
+
+    /// Read and decode files on `executor` when reading more than one file concurrently,
+    /// see [`DefaultJsonHandler::with_readahead`].
+    pub fn with_task_executor(mut self, executor: Arc<dyn TaskExecutor>) -> Self {
+        self.executor = Some(executor);
+        self
     }
 }
 
@@ -77,11 +130,9 @@ impl JsonHandler for DefaultJsonHandler {
             .flatten()
             .collect::<Vec<_>>();
 
-        let batches = ReaderBuilder::new(output_schema.clone())
-            .build(Cursor::new(data))?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(concat_batches(&output_schema, &batches)?)
+        // values may be encoded with a compatible but different type, e.g. a boolean as a
+        // string, so numeric and boolean columns are parsed as strings and cast to their type.
+        coerce_primitive_columns(&data, &output_schema, self.batch_size)
     }
 
     fn read_json_files(
@@ -95,20 +146,161 @@ impl JsonHandler for DefaultJsonHandler {
 
         let schema: ArrowSchemaRef = Arc::new(physical_schema.as_ref().try_into()?);
         let store = files.first().unwrap().store.clone();
-        let file_reader = JsonOpener::new(1024, schema.clone(), store);
+        let file_reader =
+            JsonOpener::new(self.batch_size, schema.clone(), store).with_strict_mode(self.strict);
 
         let files = files.into_iter().map(|f| f.meta).collect::<Vec<_>>();
-        let stream = FileStream::new(files, schema, file_reader)?;
+        if self.readahead == 1 {
+            let stream = FileStream::new(files, schema, file_reader)?;
+            return Ok(stream.boxed());
+        }
+
+        let executor = self.executor.clone();
+        let stream = futures::stream::iter(files)
+            .map(move |file| match file_reader.open(file, None) {
+                Ok(future) => {
+                    let read = async move { future.await?.try_collect::<Vec<_>>().await };
+                    match &executor {
+                        Some(executor) => spawn(executor.as_ref(), read),
+                        None => read.boxed(),
+                    }
+                }
+                Err(err) => futures::future::ready(Err(err)).boxed(),
+            })
+            .buffered(self.readahead)
+            .map_ok(|batches| futures::stream::iter(batches.into_iter().map(Ok)))
+            .try_flatten();
         Ok(stream.boxed())
     }
 }
 
+/// Run `task` on `executor`, resolving to its output once it completed.
+fn spawn<T: Send + 'static>(
+    executor: &dyn TaskExecutor,
+    task: impl Future<Output = DeltaResult<T>> + Send + 'static,
+) -> BoxFuture<'static, DeltaResult<T>> {
+    let (sender, receiver) = oneshot::channel();
+    executor.spawn(
+        async move {
+            // the receiver is gone if the stream was dropped
+            let _ = sender.send(task.await);
+        }
+        .boxed(),
+    );
+    receiver
+        .map(|result| match result {
+            Ok(result) => result,
+            Err(_) => Err(Error::Generic("task was cancelled by the executor".into())),
+        })
+        .boxed()
+}
+
+/// Decode `data` with `reader` into batches of at most its batch size rows.
+fn decode_batches(reader: ReaderBuilder, data: &[u8]) -> DeltaResult<Vec<RecordBatch>> {
+    Ok(reader
+        .build(Cursor::new(data))?
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Combine `batches` into one batch, without copying if there is only one.
+fn concat_if_needed(
+    schema: &ArrowSchemaRef,
+    mut batches: Vec<RecordBatch>,
+) -> DeltaResult<RecordBatch> {
+    match batches.len() {
+        1 => Ok(batches.pop().expect("one batch")),
+        _ => Ok(concat_batches(schema, &batches)?),
+    }
+}
+
+/// Decode `data` with all numeric and boolean columns, including the children of structs, read
+/// as strings, and cast them to the type declared in `schema`.
+///
+/// JSON numbers and booleans are read as their text, so such values are accepted whether they
+/// are encoded as JSON strings or not. Other columns, e.g. timestamps, are decoded as declared.
+fn coerce_primitive_columns(
+    data: &[u8],
+    schema: &ArrowSchemaRef,
+    batch_size: usize,
+) -> DeltaResult<RecordBatch> {
+    let string_schema = Arc::new(ArrowSchema::new(
+        schema
+            .fields()
+            .iter()
+            .map(|field| as_string_field(field))
+            .collect::<Vec<_>>(),
+    ));
+    let reader = ReaderBuilder::new(string_schema.clone())
+        .with_batch_size(batch_size)
+        .with_coerce_primitive(true);
+    let batch = concat_if_needed(&string_schema, decode_batches(reader, data)?)?;
+
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let columns = batch
+        .columns()
+        .iter()
+        .zip(schema.fields())
+        .map(|(column, field)| cast_from_strings(column, field.data_type(), &options))
+        .collect::<DeltaResult<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(schema.clone(), columns)?)
+}
+
+/// `field` with all numeric and boolean types, including those of struct children, replaced by
+/// strings.
+fn as_string_field(field: &Field) -> Field {
+    match field.data_type() {
+        DataType::Struct(fields) => field.clone().with_data_type(DataType::Struct(
+            fields.iter().map(|field| as_string_field(field)).collect(),
+        )),
+        data_type if is_coerced(data_type) => field.clone().with_data_type(DataType::Utf8),
+        _ => field.clone(),
+    }
+}
+
+/// Whether values of `data_type` are parsed as strings, see [`coerce_primitive_columns`].
+fn is_coerced(data_type: &DataType) -> bool {
+    data_type.is_numeric() || data_type == &DataType::Boolean
+}
+
+/// Cast a `column` decoded with [`as_string_field`] back to `data_type`.
+fn cast_from_strings(
+    column: &ArrayRef,
+    data_type: &DataType,
+    options: &CastOptions<'_>,
+) -> DeltaResult<ArrayRef> {
+    match data_type {
+        DataType::Struct(fields) => {
+            let array = column
+                .as_any()
+                .downcast_ref::<StructArray>()
+                .ok_or_else(|| Error::UnexpectedColumnType("expected a struct".into()))?;
+            let columns = array
+                .columns()
+                .iter()
+                .zip(fields)
+                .map(|(column, field)| cast_from_strings(column, field.data_type(), options))
+                .collect::<DeltaResult<Vec<_>>>()?;
+            Ok(Arc::new(StructArray::try_new(
+                fields.clone(),
+                columns,
+                array.nulls().cloned(),
+            )?))
+        }
+        data_type if is_coerced(data_type) => Ok(cast_with_options(column, data_type, options)?),
+        _ => Ok(column.clone()),
+    }
+}
+
 /// A [`FileOpener`] that opens a JSON file and yields a [`FileOpenFuture`]
 #[allow(missing_debug_implementations)]
 pub struct JsonOpener {
     batch_size: usize,
     projected_schema: ArrowSchemaRef,
     object_store: Arc<DynObjectStore>,
+    strict: bool,
 }
 
 impl JsonOpener {
@@ -124,8 +316,32 @@ impl JsonOpener {
             projected_schema,
             // file_compression_type,
             object_store,
+            strict: false,
         }
     }
+
+    /// Fail on top-level keys which are not part of the projected schema.
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
+
+/// Check that every line of `data` only contains top-level keys of `schema`.
+fn check_known_keys(data: &[u8], schema: &ArrowSchema, location: &url::Url) -> DeltaResult<()> {
+    for line in data.split(|b| *b == b'\n') {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let value: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(line)?;
+        if let Some(key) = value
+            .keys()
+            .find(|key| schema.field_with_name(key).is_err())
+        {
+            return Err(Error::Generic(format!("Unknown key '{key}' in {location}")));
+        }
+    }
+    Ok(())
 }
 
 impl FileOpener for JsonOpener {
@@ -133,9 +349,18 @@ impl FileOpener for JsonOpener {
         let store = self.object_store.clone();
         let schema = self.projected_schema.clone();
         let batch_size = self.batch_size;
+        let strict = self.strict;
 
         Ok(Box::pin(async move {
             let path = Path::from(file_meta.location.path());
+            if strict {
+                let data = store.get(&path).await?.bytes().await?;
+                check_known_keys(&data, &schema, &file_meta.location)?;
+                let reader = ReaderBuilder::new(schema)
+                    .with_batch_size(batch_size)
+                    .build(Cursor::new(data))?;
+                return Ok(futures::stream::iter(reader).map_err(Error::from).boxed());
+            }
             match store.get(&path).await? {
                 GetResult::File(file, _) => {
                     let reader = ReaderBuilder::new(schema)
@@ -186,10 +411,13 @@ impl FileOpener for JsonOpener {
 mod tests {
     use std::path::PathBuf;
 
-    use object_store::{local::LocalFileSystem, ObjectStore};
+    use arrow_array::{BooleanArray, Date32Array, Float64Array, Int32Array, Int64Array};
+    use object_store::{local::LocalFileSystem, memory::InMemory, ObjectStore};
+
+    use itertools::Itertools;
 
     use super::*;
-    use crate::actions::get_log_schema;
+    use crate::actions::{get_log_schema, ActionType};
 
     #[test]
     fn test_parse_json() {
@@ -209,6 +437,148 @@ mod tests {
         assert_eq!(batch.num_rows(), 4);
     }
 
+    #[test]
+    fn test_parse_json_batch_size() {
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "value",
+            DataType::Int32,
+            true,
+        )]));
+        let data = (0..10_000)
+            .map(|value| format!(r#"{{"value":{value}}}"#))
+            .join("\n");
+        let json_strings: StringArray = data.lines().map(Some).collect();
+
+        let reader = ReaderBuilder::new(schema.clone()).with_batch_size(3000);
+        let sizes = decode_batches(reader, data.as_bytes())
+            .unwrap()
+            .iter()
+            .map(|batch| batch.num_rows())
+            .collect::<Vec<_>>();
+        assert_eq!(sizes, vec![3000, 3000, 3000, 1000]);
+
+        for batch_size in [3000, 10_000] {
+            let handler = DefaultJsonHandler::new(Arc::new(LocalFileSystem::new()))
+                .with_batch_size(NonZeroUsize::new(batch_size).unwrap());
+            let batch = handler
+                .parse_json(json_strings.clone(), schema.clone())
+                .unwrap();
+            let values = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            assert_eq!(values, &Int32Array::from_iter_values(0..10_000));
+        }
+    }
+
+    #[test]
+    fn test_parse_json_empty() {
+        let handler = DefaultJsonHandler::new(Arc::new(LocalFileSystem::new()));
+        let output_schema = Arc::new(get_log_schema());
+
+        let json_strings = StringArray::from(Vec::<Option<&str>>::new());
+        let batch = handler
+            .parse_json(json_strings, output_schema.clone())
+            .unwrap();
+        assert_eq!(batch.num_rows(), 0);
+        assert_eq!(batch.schema(), output_schema);
+
+        // null strings are skipped
+        let json_strings = StringArray::from(vec![None::<&str>, None]);
+        let batch = handler.parse_json(json_strings, output_schema).unwrap();
+        assert_eq!(batch.num_rows(), 0);
+    }
+
+    #[test]
+    fn test_parse_json_coerce_primitives() {
+        let handler = DefaultJsonHandler::new(Arc::new(LocalFileSystem::new()));
+        let output_schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("int", DataType::Int32, true),
+            Field::new("bool", DataType::Boolean, true),
+            Field::new("string", DataType::Utf8, true),
+            Field::new("double", DataType::Float64, true),
+            Field::new("date", DataType::Date32, true),
+            Field::new(
+                "struct",
+                DataType::Struct(
+                    vec![
+                        Field::new("bool", DataType::Boolean, true),
+                        Field::new(
+                            "list",
+                            DataType::List(Arc::new(Field::new("item", DataType::Int32, true))),
+                            true,
+                        ),
+                    ]
+                    .into(),
+                ),
+                true,
+            ),
+        ]));
+        let json_strings: StringArray = vec![
+            r#"{"int":"5","bool":"true","string":5,"double":"1.5","date":"2021-03-01","struct":{"bool":"false","list":[1]}}"#,
+            r#"{"int":6,"bool":false,"string":true,"double":2e3,"date":18687}"#,
+        ]
+        .into();
+
+        let batch = handler.parse_json(json_strings, output_schema).unwrap();
+        let column = |idx: usize| batch.column(idx).as_any();
+        assert_eq!(
+            column(0).downcast_ref::<Int32Array>().unwrap(),
+            &Int32Array::from(vec![5, 6])
+        );
+        assert_eq!(
+            column(1).downcast_ref::<BooleanArray>().unwrap(),
+            &BooleanArray::from(vec![true, false])
+        );
+        assert_eq!(
+            column(2).downcast_ref::<StringArray>().unwrap(),
+            &StringArray::from(vec!["5", "true"])
+        );
+        assert_eq!(
+            column(3).downcast_ref::<Float64Array>().unwrap(),
+            &Float64Array::from(vec![1.5, 2000.0])
+        );
+        // other types are decoded as declared
+        assert_eq!(
+            column(4).downcast_ref::<Date32Array>().unwrap(),
+            &Date32Array::from(vec![18687, 18687])
+        );
+        let column = column(5).downcast_ref::<StructArray>().unwrap();
+        assert!(column.is_null(1));
+        assert_eq!(
+            column.column(0).as_any().downcast_ref::<BooleanArray>(),
+            Some(&BooleanArray::from(vec![Some(false), None]))
+        );
+        assert_eq!(column.column(1).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_json_incompatible_type() {
+        let handler = DefaultJsonHandler::new(Arc::new(LocalFileSystem::new()));
+        let output_schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "int",
+            DataType::Int32,
+            true,
+        )]));
+
+        let json_strings: StringArray = vec![r#"{"int":{"nested":5}}"#].into();
+        let err = handler
+            .parse_json(json_strings, output_schema.clone())
+            .unwrap_err();
+        assert!(matches!(err, Error::Arrow(_)), "{err}");
+
+        let json_strings: StringArray = vec![r#"{"int":"five"}"#].into();
+        let err = handler
+            .parse_json(json_strings, output_schema.clone())
+            .unwrap_err();
+        assert!(err.to_string().contains("five"), "{err}");
+
+        let json_strings: StringArray = vec![r#"{"int":5"#].into();
+        let err = handler.parse_json(json_strings, output_schema).unwrap_err();
+        assert!(matches!(err, Error::Arrow(_)), "{err}");
+    }
+
     #[tokio::test]
     async fn test_read_json_files() {
         let store = Arc::new(LocalFileSystem::new());
@@ -223,7 +593,7 @@ mod tests {
 
         let files = vec![FileMeta {
             location: url.clone(),
-            last_modified: meta.last_modified.timestamp(),
+            last_modified: meta.last_modified.timestamp_millis(),
             size: meta.size,
         }];
 
@@ -240,4 +610,212 @@ mod tests {
         assert_eq!(data.len(), 1);
         assert_eq!(data[0].num_rows(), 4);
     }
+
+    #[tokio::test]
+    async fn test_read_json_files_strict_mode() {
+        let store = Arc::new(InMemory::new());
+        let path = Path::from("_delta_log/00000000000000000000.json");
+        let data = [
+            r#"{"commitInfo":{"timestamp":1587968586154}}"#,
+            r#"{"newAction":{"timestamp":1587968586154}}"#,
+        ]
+        .join("\n");
+        store.put(&path, data.into()).await.unwrap();
+        let files = vec![FileMeta {
+            location: url::Url::parse(&format!("memory:///{path}")).unwrap(),
+            last_modified: 0,
+            size: 0,
+        }];
+        let schema = Arc::new(ArrowSchema::new(vec![ActionType::CommitInfo.field()]));
+        let read = |handler: DefaultJsonHandler| {
+            let context = handler
+                .contextualize_file_reads(files.clone(), None)
+                .unwrap();
+            handler
+                .read_json_files(context, Arc::new(schema.as_ref().try_into().unwrap()))
+                .unwrap()
+                .try_collect::<Vec<_>>()
+        };
+
+        // unknown actions are read as null rows
+        let batches = read(DefaultJsonHandler::new(store.clone())).await.unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 2);
+        assert_eq!(batches[0].column(0).null_count(), 1);
+
+        let err = read(DefaultJsonHandler::new(store).with_strict_mode(true))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("newAction"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_read_json_files_concurrently() {
+        let store = Arc::new(InMemory::new());
+        let mut files = Vec::new();
+        for version in 0..20 {
+            let path = Path::from(format!("_delta_log/{:020}.json", version));
+            let data = (0..version)
+                .map(|i| format!(r#"{{"commitInfo":{{"timestamp":{}}}}}"#, version * 100 + i))
+                .collect::<Vec<_>>()
+                .join("\n");
+            store.put(&path, data.into()).await.unwrap();
+            files.push(FileMeta {
+                location: url::Url::parse(&format!("memory:///{path}")).unwrap(),
+                last_modified: 0,
+                size: 0,
+            });
+        }
+        let schema = Arc::new(ArrowSchema::new(vec![ActionType::CommitInfo.field()]));
+        let read = |handler: DefaultJsonHandler, files: Vec<FileMeta>| {
+            let schema = schema.clone();
+            async move {
+                let context = handler.contextualize_file_reads(files, None).unwrap();
+                handler
+                    .read_json_files(context, Arc::new(schema.as_ref().try_into().unwrap()))
+                    .unwrap()
+                    .collect::<Vec<_>>()
+                    .await
+            }
+        };
+
+        let serial = read(DefaultJsonHandler::new(store.clone()), files.clone()).await;
+        let concurrent = read(
+            DefaultJsonHandler::new(store.clone()).with_readahead(NonZeroUsize::new(8).unwrap()),
+            files.clone(),
+        )
+        .await;
+        let serial = serial.into_iter().collect::<DeltaResult<Vec<_>>>().unwrap();
+        let concurrent = concurrent
+            .into_iter()
+            .collect::<DeltaResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(serial, concurrent);
+        assert_eq!(
+            concurrent.iter().map(|b| b.num_rows()).sum::<usize>(),
+            (0..20).sum::<usize>()
+        );
+
+        // failing files are reported in order, without stopping the other reads
+        let missing = |name: &str| FileMeta {
+            location: url::Url::parse(&format!("memory:///{name}")).unwrap(),
+            last_modified: 0,
+            size: 0,
+        };
+        let files = vec![
+            missing("missing-1.json"),
+            files[5].clone(),
+            missing("missing-2.json"),
+            files[6].clone(),
+        ];
+        let results = read(
+            DefaultJsonHandler::new(store).with_readahead(NonZeroUsize::new(4).unwrap()),
+            files,
+        )
+        .await;
+        let results = results
+            .iter()
+            .map(|result| match result {
+                Ok(batch) => Ok(batch.num_rows()),
+                Err(Error::FileNotFound(path)) => Err(path.clone()),
+                Err(err) => panic!("unexpected error {err}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results,
+            vec![
+                Err("missing-1.json".to_string()),
+                Ok(5),
+                Err("missing-2.json".to_string()),
+                Ok(6)
+            ]
+        );
+    }
+
+    /// The number of tasks spawned on a [`GatedExecutor`] which did not complete yet.
+    #[derive(Debug, Default)]
+    struct InFlight {
+        current: usize,
+        max: usize,
+    }
+
+    /// Runs every task on its own thread, once at least `parallelism` tasks are in flight.
+    #[derive(Debug, Default)]
+    struct GatedExecutor {
+        parallelism: usize,
+        in_flight: Arc<(std::sync::Mutex<InFlight>, std::sync::Condvar)>,
+    }
+
+    impl GatedExecutor {
+        /// The largest number of tasks in flight at the same time.
+        fn max_in_flight(&self) -> usize {
+            self.in_flight.0.lock().unwrap().max
+        }
+    }
+
+    impl TaskExecutor for GatedExecutor {
+        fn spawn(&self, task: BoxFuture<'static, ()>) {
+            let in_flight = self.in_flight.clone();
+            let parallelism = self.parallelism;
+            std::thread::spawn(move || {
+                let (lock, spawned) = in_flight.as_ref();
+                let mut counts = lock.lock().unwrap();
+                counts.current += 1;
+                counts.max = counts.max.max(counts.current);
+                spawned.notify_all();
+                // tasks spawned one after another time out and run one at a time
+                let timeout = std::time::Duration::from_secs(10);
+                let (counts, _) = spawned
+                    .wait_timeout_while(counts, timeout, |counts| counts.max < parallelism)
+                    .unwrap();
+                drop(counts);
+                futures::executor::block_on(task);
+                lock.lock().unwrap().current -= 1;
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_json_files_on_executor() {
+        let store = Arc::new(InMemory::new());
+        let mut files = Vec::new();
+        for version in 0..4 {
+            let path = Path::from(format!("_delta_log/{:020}.json", version));
+            let data = format!(r#"{{"commitInfo":{{"timestamp":{version}}}}}"#);
+            store.put(&path, data.into()).await.unwrap();
+            files.push(FileMeta {
+                location: url::Url::parse(&format!("memory:///{path}")).unwrap(),
+                last_modified: 0,
+                size: 0,
+            });
+        }
+        let executor = Arc::new(GatedExecutor {
+            parallelism: 4,
+            ..Default::default()
+        });
+        let handler = DefaultJsonHandler::new(store)
+            .with_readahead(NonZeroUsize::new(4).unwrap())
+            .with_task_executor(executor.clone());
+        let schema = ArrowSchema::new(vec![ActionType::CommitInfo.field()]);
+        let context = handler.contextualize_file_reads(files, None).unwrap();
+        let batches = handler
+            .read_json_files(context, Arc::new((&schema).try_into().unwrap()))
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        // all files were read at the same time, but are yielded in order
+        assert_eq!(executor.max_in_flight(), 4);
+        let timestamps = batches
+            .iter()
+            .map(|batch| {
+                let commit_info = batch.column(0).as_any().downcast_ref::<StructArray>();
+                let timestamp = commit_info.unwrap().column_by_name("timestamp").unwrap();
+                let timestamp = timestamp.as_any().downcast_ref::<Int64Array>().unwrap();
+                timestamp.value(0)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![0, 1, 2, 3]);
+    }
 }
@@ -5,14 +5,18 @@ use std::sync::Arc;
 use object_store::{parse_url_opts, path::Path, DynObjectStore};
 use url::Url;
 
+use self::expression::DefaultExpressionHandler;
 use self::filesystem::ObjectStoreFileSystemClient;
 use self::json::{DefaultJsonHandler, JsonReadContext};
 use self::parquet::{DefaultParquetHandler, ParquetReadContext};
+use crate::snapshot::LogCache;
 use crate::{
     DeltaResult, ExpressionHandler, FileSystemClient, JsonHandler, ParquetHandler, TableClient,
 };
 
 pub mod arrow;
+pub mod executor;
+pub mod expression;
 pub mod file_handler;
 pub mod filesystem;
 pub mod json;
@@ -24,6 +28,7 @@ pub struct DefaultTableClient {
     file_system: Arc<ObjectStoreFileSystemClient>,
     json: Arc<DefaultJsonHandler>,
     parquet: Arc<DefaultParquetHandler>,
+    log_cache: Option<Arc<LogCache>>,
 }
 
 impl DefaultTableClient {
@@ -41,6 +46,7 @@ impl DefaultTableClient {
             json: Arc::new(DefaultJsonHandler::new(store.clone())),
             parquet: Arc::new(DefaultParquetHandler::new(store.clone())),
             store,
+            log_cache: None,
         })
     }
 
@@ -50,8 +56,25 @@ impl DefaultTableClient {
             json: Arc::new(DefaultJsonHandler::new(store.clone())),
             parquet: Arc::new(DefaultParquetHandler::new(store.clone())),
             store,
+            log_cache: None,
         }
     }
+
+    /// Share the table metadata parsed from the log between snapshots using `cache`.
+    ///
+    /// The cache can also be shared between multiple clients.
+    pub fn with_log_cache(mut self, cache: Arc<LogCache>) -> Self {
+        self.log_cache = Some(cache);
+        self
+    }
+
+    /// Read JSON files, e.g. commits, with `json` instead of the default handler.
+    ///
+    /// This allows configuring the handler, e.g. with [`DefaultJsonHandler::with_strict_mode`].
+    pub fn with_json_handler(mut self, json: DefaultJsonHandler) -> Self {
+        self.json = Arc::new(json);
+        self
+    }
 }
 
 impl DefaultTableClient {
@@ -65,7 +88,7 @@ impl TableClient for DefaultTableClient {
     type ParquetReadContext = ParquetReadContext;
 
     fn get_expression_handler(&self) -> Arc<dyn ExpressionHandler> {
-        todo!()
+        Arc::new(DefaultExpressionHandler::new())
     }
 
     fn get_file_system_client(&self) -> Arc<dyn FileSystemClient> {
@@ -81,4 +104,8 @@ impl TableClient for DefaultTableClient {
     ) -> Arc<dyn ParquetHandler<FileReadContext = Self::ParquetReadContext>> {
         self.parquet.clone()
     }
+
+    fn get_log_cache(&self) -> Option<Arc<LogCache>> {
+        self.log_cache.clone()
+    }
 }
@@ -3,16 +3,22 @@
 use std::ops::Range;
 use std::sync::Arc;
 
-use arrow_schema::SchemaRef as ArrowSchemaRef;
+use arrow_array::{new_null_array, Array, ArrayRef, RecordBatch, RecordBatchOptions, StructArray};
+use arrow_schema::{
+    ArrowError, DataType as ArrowDataType, Field as ArrowField, FieldRef as ArrowFieldRef, Fields,
+    Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
+};
 use futures::stream::{StreamExt, TryStreamExt};
 use object_store::path::Path;
 use object_store::DynObjectStore;
 use parquet::arrow::arrow_reader::ArrowReaderOptions;
 use parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
+use parquet::arrow::ProjectionMask;
+use parquet::schema::types::TypePtr;
 
 use super::file_handler::{FileOpenFuture, FileOpener};
 use crate::file_handler::FileStream;
-use crate::schema::SchemaRef;
+use crate::schema::{ColumnMetadataKey, SchemaRef};
 use crate::{
     DeltaResult, Error, Expression, FileDataReadResultStream, FileHandler, FileMeta, ParquetHandler,
 };
@@ -56,7 +62,6 @@ impl ParquetHandler for DefaultParquetHandler {
         files: Vec<<Self as FileHandler>::FileReadContext>,
         physical_schema: SchemaRef,
     ) -> DeltaResult<FileDataReadResultStream> {
-        // TODO at the very least load only required columns ...
         if files.is_empty() {
             return Ok(futures::stream::empty().boxed());
         }
@@ -71,8 +76,16 @@ impl ParquetHandler for DefaultParquetHandler {
 }
 
 /// Implements [`FileOpener`] for a parquet file
+///
+/// Only the columns of the table schema are read from the file, and they are returned in the
+/// order and with the names of the table schema. Columns missing from the file (e.g. partition
+/// columns) are omitted.
+///
+/// Columns with a column mapping id, including nested fields of structs, are matched by the
+/// parquet field id of the file columns, as is required for tables in column mapping mode `id`.
+/// Files written without field ids, e.g. before column mapping was enabled, are matched by name,
+/// so tables in column mapping mode `name` have to be read with their physical names.
 struct ParquetOpener {
-    // projection: Arc<[usize]>,
     batch_size: usize,
     limit: Option<usize>,
     table_schema: ArrowSchemaRef,
@@ -100,8 +113,7 @@ impl FileOpener for ParquetOpener {
         let store = self.store.clone();
 
         let batch_size = self.batch_size;
-        // let projection = self.projection.clone();
-        let _table_schema = self.table_schema.clone();
+        let table_schema = self.table_schema.clone();
         let limit = self.limit;
 
         Ok(Box::pin(async move {
@@ -112,32 +124,160 @@ impl FileOpener for ParquetOpener {
             let mut builder =
                 ParquetRecordBatchStreamBuilder::new_with_options(reader, options).await?;
 
-            // let mask = ProjectionMask::roots(builder.parquet_schema(), projection.iter().cloned());
+            // the indices of the requested columns in the file, in the requested order
+            let file_schema = builder.schema().clone();
+            let file_types = builder.parquet_schema().root_schema().get_fields().to_vec();
+            let by_id = file_types.iter().any(|t| t.get_basic_info().has_id());
+            let (requested, fields): (Vec<_>, Vec<_>) = table_schema
+                .fields()
+                .iter()
+                .filter_map(|field| {
+                    let idx = find_column(field, &file_types, file_schema.fields(), by_id)?;
+                    Some((idx, (field.clone(), file_types[idx].clone())))
+                })
+                .unzip();
+            // the projected batches hold the columns in file order, so we need to map them
+            // back into the requested order.
+            let mut sorted = requested.clone();
+            sorted.sort_unstable();
+            let order = requested
+                .iter()
+                .map(|idx| sorted.binary_search(idx).expect("index is in projection"))
+                .collect::<Vec<_>>();
+
+            let mask = ProjectionMask::roots(builder.parquet_schema(), sorted);
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
 
             let stream = builder
-                // .with_projection(mask)
+                .with_projection(mask)
                 .with_batch_size(batch_size)
                 .build()?;
 
-            let adapted = stream.map_err(|e| Error::GenericError {
-                source: Box::new(e),
-            });
+            let adapted = stream
+                .map_err(|e| Error::GenericError {
+                    source: Box::new(e),
+                })
+                .and_then(move |batch| {
+                    let projected = batch
+                        .project(&order)
+                        .and_then(|batch| match_columns(batch, &fields, by_id))
+                        .map_err(Error::from);
+                    futures::future::ready(projected)
+                });
 
             Ok(adapted.boxed())
         }))
     }
 }
 
+/// The index of the file column matching the `requested` field, by its column mapping id if
+/// `by_id` is set and the field has one, and by name otherwise.
+fn find_column(
+    requested: &ArrowField,
+    file_types: &[TypePtr],
+    file_fields: &Fields,
+    by_id: bool,
+) -> Option<usize> {
+    let id = requested
+        .metadata()
+        .get(ColumnMetadataKey::ColumnMappingId.as_ref())
+        .and_then(|id| id.parse::<i32>().ok());
+    match id {
+        Some(id) if by_id => file_types.iter().position(|file_type| {
+            let info = file_type.get_basic_info();
+            info.has_id() && info.id() == id
+        }),
+        _ => file_fields
+            .iter()
+            .position(|field| field.name() == requested.name()),
+    }
+}
+
+/// Give the columns of `batch` the names of the `requested` fields, see [`match_column`].
+fn match_columns(
+    batch: RecordBatch,
+    requested: &[(ArrowFieldRef, TypePtr)],
+    by_id: bool,
+) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    let (fields, columns): (Vec<_>, Vec<_>) = requested
+        .iter()
+        .zip(schema.fields().iter().zip(batch.columns()))
+        .map(|((requested, file_type), (field, column))| {
+            match_column(column, field, file_type, requested, by_id)
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+    RecordBatch::try_new_with_options(Arc::new(ArrowSchema::new(fields)), columns, &options)
+}
+
+/// Give `column` the name of the `requested` field, keeping all other properties of its field.
+///
+/// If the file is matched by field ids, the children of struct columns are matched by their
+/// field ids as well and returned in the requested order and with the requested names. Missing
+/// children are filled with nulls. Otherwise the column is returned as read.
+fn match_column(
+    column: &ArrayRef,
+    field: &ArrowField,
+    file_type: &TypePtr,
+    requested: &ArrowField,
+    by_id: bool,
+) -> Result<(ArrowField, ArrayRef), ArrowError> {
+    let renamed = || (field.clone().with_name(requested.name()), column.clone());
+    let ArrowDataType::Struct(requested_children) = requested.data_type() else {
+        return Ok(renamed());
+    };
+    let Some(array) = column.as_any().downcast_ref::<StructArray>() else {
+        return Ok(renamed());
+    };
+    if !by_id || !file_type.is_group() {
+        return Ok(renamed());
+    }
+    let file_types = file_type.get_fields();
+    let (fields, columns): (Vec<_>, Vec<_>) = requested_children
+        .iter()
+        .map(
+            |child| match find_column(child, file_types, array.fields(), by_id) {
+                Some(idx) => match_column(
+                    array.column(idx),
+                    &array.fields()[idx],
+                    &file_types[idx],
+                    child,
+                    by_id,
+                ),
+                None => Ok((
+                    child.as_ref().clone().with_nullable(true),
+                    new_null_array(child.data_type(), array.len()),
+                )),
+            },
+        )
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    let array = StructArray::try_new(fields.into(), columns, array.nulls().cloned())?;
+    let field = ArrowField::new(
+        requested.name(),
+        array.data_type().clone(),
+        field.is_nullable(),
+    )
+    .with_metadata(field.metadata().clone());
+    Ok((field, Arc::new(array)))
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use object_store::{local::LocalFileSystem, ObjectStore};
+    use arrow_array::{ArrayRef, Int32Array, Int64Array, RecordBatch, StringArray};
+    use object_store::{local::LocalFileSystem, memory::InMemory, ObjectStore};
+    use parquet::arrow::ArrowWriter;
 
     use super::*;
+    use crate::schema::{DataType, PrimitiveType, StructField, StructType};
 
     #[tokio::test]
     async fn test_read_parquet_files() {
@@ -159,7 +299,7 @@ mod tests {
 
         let files = vec![FileMeta {
             location: url.clone(),
-            last_modified: meta.last_modified.timestamp(),
+            last_modified: meta.last_modified.timestamp_millis(),
             size: meta.size,
         }];
 
@@ -176,4 +316,227 @@ mod tests {
         assert_eq!(data.len(), 1);
         assert_eq!(data[0].num_rows(), 10);
     }
+
+    #[tokio::test]
+    async fn test_read_parquet_files_with_projection() {
+        let store = Arc::new(InMemory::new());
+        let columns: Vec<(&str, ArrayRef)> = vec![
+            ("a", Arc::new(Int32Array::from(vec![1, 2]))),
+            ("b", Arc::new(StringArray::from(vec!["x", "y"]))),
+            ("c", Arc::new(Int64Array::from(vec![10, 20]))),
+            ("d", Arc::new(StringArray::from(vec!["u", "v"]))),
+        ];
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let mut data = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        store
+            .put(&Path::from("data.parquet"), data.into())
+            .await
+            .unwrap();
+
+        let files = vec![FileMeta {
+            location: url::Url::parse("memory:///data.parquet").unwrap(),
+            last_modified: 0,
+            size: 0,
+        }];
+        let handler = DefaultParquetHandler::new(store);
+        let context = handler.contextualize_file_reads(files, None).unwrap();
+
+        // read two of the four columns in swapped order
+        let schema = StructType::new(vec![
+            StructField::new("c", DataType::Primitive(PrimitiveType::Long), true),
+            StructField::new("a", DataType::Primitive(PrimitiveType::Integer), true),
+        ]);
+        let data = handler
+            .read_parquet_files(context, Arc::new(schema))
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert_eq!(data.len(), 1);
+        let columns: Vec<(&str, ArrayRef)> = vec![
+            ("c", Arc::new(Int64Array::from(vec![10, 20]))),
+            ("a", Arc::new(Int32Array::from(vec![1, 2]))),
+        ];
+        let expected = RecordBatch::try_from_iter(columns).unwrap();
+        assert_eq!(data[0], expected);
+    }
+
+    #[tokio::test]
+    async fn test_read_parquet_files_multiple_batches() {
+        let store = Arc::new(InMemory::new());
+        let values: ArrayRef = Arc::new(Int32Array::from_iter_values(0..2500));
+        let batch = RecordBatch::try_from_iter(vec![("a", values)]).unwrap();
+        let mut data = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        store
+            .put(&Path::from("data.parquet"), data.into())
+            .await
+            .unwrap();
+
+        let files = vec![FileMeta {
+            location: url::Url::parse("memory:///data.parquet").unwrap(),
+            last_modified: 0,
+            size: 0,
+        }];
+        let handler = DefaultParquetHandler::new(store);
+        let context = handler.contextualize_file_reads(files, None).unwrap();
+        let schema = StructType::new(vec![StructField::new(
+            "a",
+            DataType::Primitive(PrimitiveType::Integer),
+            true,
+        )]);
+        let data = handler
+            .read_parquet_files(context, Arc::new(schema))
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            data.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![1024, 1024, 452]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_parquet_files_by_field_id() {
+        use parquet::basic::{LogicalType, Repetition, Type as PhysicalType};
+        use parquet::data_type::{ByteArray, ByteArrayType, Int32Type};
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::types::Type;
+
+        // the file columns have physical names and field ids
+        let write = |with_ids: bool| {
+            let column = |name: &str, physical: PhysicalType, id: i32| {
+                let logical = (physical == PhysicalType::BYTE_ARRAY).then_some(LogicalType::String);
+                let builder = Type::primitive_type_builder(name, physical)
+                    .with_repetition(Repetition::REQUIRED)
+                    .with_logical_type(logical);
+                let builder = match with_ids {
+                    true => builder.with_id(id),
+                    false => builder,
+                };
+                Arc::new(builder.build().unwrap())
+            };
+            let group = Type::group_type_builder("col-3")
+                .with_repetition(Repetition::REQUIRED)
+                .with_fields(&mut vec![
+                    column("col-4", PhysicalType::INT32, 4),
+                    column("col-5", PhysicalType::INT32, 5),
+                ]);
+            let group = match with_ids {
+                true => group.with_id(3),
+                false => group,
+            };
+            let schema = Type::group_type_builder("schema")
+                .with_fields(&mut vec![
+                    column("col-1", PhysicalType::INT32, 1),
+                    column("col-2", PhysicalType::BYTE_ARRAY, 2),
+                    Arc::new(group.build().unwrap()),
+                ])
+                .build()
+                .unwrap();
+            let mut data = Vec::new();
+            let mut writer =
+                SerializedFileWriter::new(&mut data, Arc::new(schema), Default::default()).unwrap();
+            let mut row_group = writer.next_row_group().unwrap();
+            let mut column = row_group.next_column().unwrap().unwrap();
+            column
+                .typed::<Int32Type>()
+                .write_batch(&[1, 2], None, None)
+                .unwrap();
+            column.close().unwrap();
+            let mut column = row_group.next_column().unwrap().unwrap();
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(&[ByteArray::from("x"), ByteArray::from("y")], None, None)
+                .unwrap();
+            column.close().unwrap();
+            for values in [[3, 4], [5, 6]] {
+                let mut column = row_group.next_column().unwrap().unwrap();
+                column
+                    .typed::<Int32Type>()
+                    .write_batch(&values, None, None)
+                    .unwrap();
+                column.close().unwrap();
+            }
+            row_group.close().unwrap();
+            writer.close().unwrap();
+            data
+        };
+        let store = Arc::new(InMemory::new());
+        for (path, with_ids) in [("ids.parquet", true), ("no_ids.parquet", false)] {
+            store
+                .put(&Path::from(path), write(with_ids).into())
+                .await
+                .unwrap();
+        }
+
+        let handler = DefaultParquetHandler::new(store);
+        let read = |path: &str, schema: StructType| {
+            let files = vec![FileMeta {
+                location: url::Url::parse("memory:///").unwrap().join(path).unwrap(),
+                last_modified: 0,
+                size: 0,
+            }];
+            let context = handler.contextualize_file_reads(files, None).unwrap();
+            handler
+                .read_parquet_files(context, Arc::new(schema))
+                .unwrap()
+                .try_collect::<Vec<_>>()
+        };
+        let field = |name: &str, primitive: PrimitiveType, id: i32| {
+            StructField::new(name, DataType::Primitive(primitive), true)
+                .with_metadata([(ColumnMetadataKey::ColumnMappingId.as_ref(), id)])
+        };
+        // the logical names match neither the physical names nor the order of the file
+        let nested = StructType::new(vec![
+            field("b", PrimitiveType::Integer, 5),
+            field("a", PrimitiveType::Integer, 4),
+            field("c", PrimitiveType::Integer, 6),
+        ]);
+        let schema = StructType::new(vec![
+            field("name", PrimitiveType::String, 2),
+            field("id", PrimitiveType::Integer, 1),
+            StructField::new("info", DataType::Struct(Box::new(nested)), true)
+                .with_metadata([(ColumnMetadataKey::ColumnMappingId.as_ref(), 3)]),
+        ]);
+        let data = read("ids.parquet", schema).await.unwrap();
+        assert_eq!(data.len(), 1);
+        let schema = data[0].schema();
+        let names = schema.fields().iter().map(|f| f.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["name", "id", "info"]);
+        let name: ArrayRef = Arc::new(StringArray::from(vec!["x", "y"]));
+        let id: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+        assert_eq!(data[0].column(0), &name);
+        assert_eq!(data[0].column(1), &id);
+        // nested fields are matched by id as well, missing ones are null
+        let info = data[0]
+            .column(2)
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .unwrap();
+        let names = info.fields().iter().map(|f| f.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["b", "a", "c"]);
+        let b: ArrayRef = Arc::new(Int32Array::from(vec![5, 6]));
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![3, 4]));
+        assert_eq!(info.column(0), &b);
+        assert_eq!(info.column(1), &a);
+        assert_eq!(info.column(2).null_count(), 2);
+
+        // files without field ids are matched by name
+        let schema = StructType::new(vec![
+            field("col-2", PrimitiveType::String, 2),
+            field("col-9", PrimitiveType::Integer, 1),
+        ]);
+        let data = read("no_ids.parquet", schema).await.unwrap();
+        assert_eq!(data[0].num_columns(), 1);
+        assert_eq!(data[0].column(0), &name);
+    }
 }
@@ -14,11 +14,14 @@ pub enum Error {
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
     },
 
-    #[error("Arrow error: {0}")]
+    #[error("Parquet error: {0}")]
     Parquet(#[from] parquet::errors::ParquetError),
 
     #[error("Error interacting with object store: {0}")]
-    ObjectStore(object_store::Error),
+    ObjectStore(#[source] object_store::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 
     #[error("File not found: {0}")]
     FileNotFound(String),
@@ -41,11 +44,34 @@ pub enum Error {
     #[error("Invalid url: {0}")]
     InvalidUrl(#[from] url::ParseError),
 
-    #[error("Invalid url: {0}")]
+    #[error("Invalid json: {0}")]
     MalformedJson(#[from] serde_json::Error),
 
     #[error("No table metadata found in delta log.")]
     MissingMetadata,
+
+    #[error("Change data feed is not enabled for table version {0}.")]
+    ChangeDataFeedNotEnabled(crate::Version),
+
+    #[error("Unsupported table feature: {0}")]
+    UnsupportedFeature(String),
+
+    #[error("Commit file of version {version} is corrupt: {source}")]
+    CorruptCommit {
+        /// Version of the corrupt commit
+        version: crate::Version,
+        /// Error raised while reading the commit
+        source: Box<Error>,
+    },
+
+    #[error("Checkpoint does not match the _last_checkpoint file: {0}")]
+    CorruptCheckpoint(String),
+
+    #[error("No table version found at timestamp {0}.")]
+    MissingVersionAtTimestamp(i64),
+
+    #[error("Missing statistics required for data skipping of file {0}")]
+    MissingStatistics(String),
 }
 
 impl From<object_store::Error> for Error {
@@ -56,3 +82,41 @@ impl From<object_store::Error> for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+    use std::sync::Arc;
+
+    use arrow_array::{Int32Array, RecordBatch};
+    use arrow_schema::{ArrowError, DataType, Field, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_source_chain() {
+        let batch = || -> DeltaResult<RecordBatch> {
+            let schema = Schema::new(vec![Field::new("a", DataType::Utf8, false)]);
+            Ok(RecordBatch::try_new(
+                Arc::new(schema),
+                vec![Arc::new(Int32Array::from(vec![1]))],
+            )?)
+        };
+        let err = batch().unwrap_err();
+        let source = err.source().expect("arrow errors are chained");
+        assert!(matches!(
+            source.downcast_ref::<ArrowError>(),
+            Some(ArrowError::InvalidArgumentError(_))
+        ));
+
+        let err = Error::from(serde_json::from_str::<i32>("{").unwrap_err());
+        assert!(err.source().unwrap().is::<serde_json::Error>());
+
+        let err = Error::from(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+        let source = err.source().unwrap().downcast_ref::<std::io::Error>();
+        assert_eq!(source.unwrap().kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let err = Error::from(object_store::Error::NotImplemented);
+        assert!(err.source().unwrap().is::<object_store::Error>());
+    }
+}
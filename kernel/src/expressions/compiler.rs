@@ -0,0 +1,572 @@
+//! Compilation of [`Expression`]s into functions evaluating them on Arrow [`RecordBatch`]es.
+
+use std::sync::Arc;
+
+use arrow_arith::boolean::{and_kleene, or_kleene};
+use arrow_array::{Array, ArrayRef, BooleanArray, RecordBatch, StringArray, StructArray};
+use arrow_cast::cast;
+use arrow_ord::comparison::{eq_dyn, gt_dyn, lt_dyn, neq_dyn};
+use arrow_schema::{
+    DataType as ArrowDataType, Fields, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
+};
+
+use super::{
+    coerce_arrays, in_list, preserves_order, BinaryOperator, Expression, ExpressionVisitor, Scalar,
+};
+use crate::schema::DataType;
+use crate::{DeltaResult, Error};
+
+/// An [`Expression`] compiled against a schema, evaluating to one value per row of a batch.
+pub type CompiledExpression = Arc<dyn Fn(&RecordBatch) -> DeltaResult<ArrayRef> + Send + Sync>;
+
+/// Compiles [`Expression`]s into [`CompiledExpression`]s for batches of a given schema.
+///
+/// Columns are resolved when compiling, so expressions referencing columns that are not part
+/// of the schema are rejected up front. Nested columns are referenced by dot separated paths.
+/// Operands of comparisons are coerced to a common type like for data skipping, e.g. integers
+/// of different widths are compared as the wider type.
+#[derive(Debug, Clone)]
+pub struct ArrowExpressionCompiler {
+    schema: ArrowSchemaRef,
+}
+
+impl ArrowExpressionCompiler {
+    pub fn new(schema: ArrowSchemaRef) -> Self {
+        Self { schema }
+    }
+
+    /// Compile `expression` into a function evaluating it on batches of the compiler's schema.
+    pub fn compile(&self, expression: &Expression) -> DeltaResult<CompiledExpression> {
+        expression.visit(&mut Compiler {
+            schema: &self.schema,
+        })
+    }
+}
+
+struct Compiler<'a> {
+    schema: &'a ArrowSchemaRef,
+}
+
+impl<'a> ExpressionVisitor for Compiler<'a> {
+    type Output = DeltaResult<CompiledExpression>;
+
+    fn visit_literal(&mut self, value: &Scalar) -> Self::Output {
+        let value = value.clone();
+        Ok(Arc::new(move |batch| Ok(value.to_array(batch.num_rows())?)))
+    }
+
+    fn visit_column(&mut self, name: &str) -> Self::Output {
+        let path = resolve_path(self.schema.fields(), name)?;
+        Ok(Arc::new(move |batch| {
+            let (first, rest) = path.split_first().expect("paths are never empty");
+            rest.iter()
+                .try_fold(batch.column(*first).clone(), |column, idx| {
+                    let column = column
+                        .as_any()
+                        .downcast_ref::<StructArray>()
+                        .ok_or_else(|| Error::UnexpectedColumnType("Expected a struct".into()))?;
+                    Ok(column.column(*idx).clone())
+                })
+        }))
+    }
+
+    fn visit_binary(
+        &mut self,
+        op: BinaryOperator,
+        left: Self::Output,
+        right: Self::Output,
+    ) -> Self::Output {
+        let (left, right) = (left?, right?);
+        Ok(Arc::new(move |batch| {
+            let left = left(batch)?;
+            // a conjunction whose left side is false for all rows is false regardless of its
+            // right side, so the right side is not evaluated
+            if op == BinaryOperator::And && as_boolean(&left)?.false_count() == left.len() {
+                return Ok(left);
+            }
+            let right = right(batch)?;
+            let result = match op {
+                BinaryOperator::And => and_kleene(as_boolean(&left)?, as_boolean(&right)?)?,
+                BinaryOperator::Or => or_kleene(as_boolean(&left)?, as_boolean(&right)?)?,
+                op => {
+                    let (left, right) = coerce_arrays(&left, &right)?;
+                    match op {
+                        BinaryOperator::LessThan => lt_dyn(&left, &right)?,
+                        BinaryOperator::GreaterThan => gt_dyn(&left, &right)?,
+                        BinaryOperator::Equal => eq_dyn(&left, &right)?,
+                        BinaryOperator::NotEqual => neq_dyn(&left, &right)?,
+                        BinaryOperator::And | BinaryOperator::Or => unreachable!(),
+                    }
+                }
+            };
+            let result: ArrayRef = Arc::new(result);
+            Ok(result)
+        }))
+    }
+
+    fn visit_cast(&mut self, expr: Self::Output, to: &DataType) -> Self::Output {
+        let (expr, to) = (expr?, ArrowDataType::try_from(to)?);
+        Ok(Arc::new(move |batch| Ok(cast(&expr(batch)?, &to)?)))
+    }
+}
+
+/// Resolve the dot separated column `name` to the indices of the fields along its path.
+fn resolve_path(fields: &Fields, name: &str) -> DeltaResult<Vec<usize>> {
+    let missing = || Error::MissingColumn(format!("Column '{}' not found", name));
+    let mut fields = Some(fields);
+    let mut path = Vec::new();
+    for part in name.split('.') {
+        let (idx, field) = fields.and_then(|f| f.find(part)).ok_or_else(missing)?;
+        path.push(idx);
+        fields = match field.data_type() {
+            ArrowDataType::Struct(children) => Some(children),
+            _ => None,
+        };
+    }
+    Ok(path)
+}
+
+fn as_boolean(array: &ArrayRef) -> DeltaResult<&BooleanArray> {
+    array
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| Error::UnexpectedColumnType("Expected a boolean".into()))
+}
+
+impl Expression {
+    /// Rewrite this predicate on the data of a file into a predicate on its statistics.
+    ///
+    /// The rewritten predicate holds for a file if the file may contain rows satisfying this
+    /// predicate. Comparisons are rewritten against the `minValues` and `maxValues` of the
+    /// referenced columns in `stats_schema`, e.g. `a < 5` becomes `minValues.a < 5`.
+    /// Conjunctions only keep their usable legs, since dropping a leg only keeps more files,
+    /// and `None` is returned if the predicate can not be used at all. The result is meant to be
+    /// compiled with an [`ArrowExpressionCompiler`] for the stats schema and evaluated on stats
+    /// whose string maxima were widened with [`widen_string_maxima`].
+    ///
+    /// Casts of literals are always evaluated, but casts of columns are only rewritten if they
+    /// preserve the order of the values, see [`preserves_order`].
+    pub fn stats_predicate(&self, stats_schema: &ArrowSchema) -> Option<Expression> {
+        stats_predicate(&self.clone().normalize(), stats_schema)
+    }
+}
+
+#[cfg(test)]
+impl Expression {
+    /// Whether any row of `batch` satisfies this predicate, to check skipping against the data.
+    pub(crate) fn matches_any(&self, batch: &RecordBatch) -> DeltaResult<bool> {
+        let compiled = ArrowExpressionCompiler::new(batch.schema()).compile(self)?;
+        Ok(as_boolean(&compiled(batch)?)?.true_count() > 0)
+    }
+
+    /// Which of the files with the statistics in `stats` may contain rows satisfying this
+    /// predicate, or `None` if the predicate can not be used for skipping.
+    pub(crate) fn skipping_filter(&self, stats: &RecordBatch) -> DeltaResult<Option<BooleanArray>> {
+        let Some(predicate) = self.stats_predicate(&stats.schema()) else {
+            return Ok(None);
+        };
+        let stats = widen_string_maxima(stats)?;
+        let compiled = ArrowExpressionCompiler::new(stats.schema()).compile(&predicate)?;
+        Ok(Some(as_boolean(&compiled(&stats)?)?.clone()))
+    }
+}
+
+/// Length in characters to which writers may truncate string statistics.
+pub(crate) const STRING_PREFIX_LENGTH: usize = 32;
+
+/// Extend the string `maxValues` of `stats` that may have been truncated.
+///
+/// String statistics may be truncated to a prefix of [`STRING_PREFIX_LENGTH`] characters. A
+/// truncated min is still a lower bound of the column values, but the true max may be larger
+/// than a truncated max. Max values that may have been truncated are therefore extended with the
+/// largest possible character, so only files that can not contain matching values are skipped.
+pub(crate) fn widen_string_maxima(stats: &RecordBatch) -> DeltaResult<RecordBatch> {
+    let Ok(idx) = stats.schema().index_of("maxValues") else {
+        return Ok(stats.clone());
+    };
+    let mut columns = stats.columns().to_vec();
+    columns[idx] = widen_strings(&columns[idx])?;
+    Ok(RecordBatch::try_new(stats.schema(), columns)?)
+}
+
+fn widen_strings(array: &ArrayRef) -> DeltaResult<ArrayRef> {
+    if let Some(values) = array.as_any().downcast_ref::<StringArray>() {
+        let widened = values
+            .iter()
+            .map(|value| {
+                value.map(
+                    |value| match value.chars().count() >= STRING_PREFIX_LENGTH {
+                        true => format!("{value}{}", char::MAX),
+                        false => value.to_string(),
+                    },
+                )
+            })
+            .collect::<StringArray>();
+        return Ok(Arc::new(widened));
+    }
+    let Some(values) = array.as_any().downcast_ref::<StructArray>() else {
+        return Ok(array.clone());
+    };
+    let columns = values
+        .columns()
+        .iter()
+        .map(widen_strings)
+        .collect::<DeltaResult<Vec<_>>>()?;
+    Ok(Arc::new(StructArray::try_new(
+        values.fields().clone(),
+        columns,
+        values.nulls().cloned(),
+    )?))
+}
+
+/// The type of the statistics of the column `name`, if there are any.
+pub(crate) fn stats_type<'a>(
+    stats_schema: &'a ArrowSchema,
+    name: &str,
+) -> Option<&'a ArrowDataType> {
+    let field = stats_schema.field_with_name("minValues").ok()?;
+    name.split('.')
+        .try_fold(field, |field, name| match field.data_type() {
+            ArrowDataType::Struct(fields) => {
+                fields.iter().find(|f| f.name() == name).map(|f| f.as_ref())
+            }
+            _ => None,
+        })
+        .map(|field| field.data_type())
+        .filter(|data_type| !data_type.is_nested())
+}
+
+// expects a normalized predicate, with literals on the right of comparisons
+fn stats_predicate(predicate: &Expression, stats_schema: &ArrowSchema) -> Option<Expression> {
+    use Expression::*;
+    let b = Box::new;
+    let recurse = |expression: &Expression| stats_predicate(expression, stats_schema);
+    let comparison = |left: &Expression, right: &Expression| {
+        // comparisons of constants can not be used to skip files
+        if left.columns().is_empty() && right.columns().is_empty() {
+            return None;
+        }
+        Some((
+            operand_bounds(left, stats_schema)?,
+            operand_bounds(right, stats_schema)?,
+        ))
+    };
+    let predicate = match predicate {
+        And(left, right) => match (recurse(left), recurse(right)) {
+            (Some(left), Some(right)) => And(b(left), b(right)),
+            (Some(leg), None) | (None, Some(leg)) => leg,
+            (None, None) => return None,
+        },
+        Or(left, right) => {
+            // long lists of values are checked against the range of the list first
+            if let Some(predicate) = in_list_predicate(predicate, stats_schema) {
+                return Some(predicate);
+            }
+            // a file matching an unusable leg may not be skipped
+            Or(b(recurse(left)?), b(recurse(right)?))
+        }
+        // min(left) < max(right)
+        LessThan(left, right) => {
+            let ((left_min, _), (_, right_max)) = comparison(left, right)?;
+            LessThan(b(left_min), b(right_max))
+        }
+        // max(left) > min(right)
+        GreaterThan(left, right) => {
+            let ((_, left_max), (right_min, _)) = comparison(left, right)?;
+            GreaterThan(b(left_max), b(right_min))
+        }
+        Equal(left, right) => {
+            let ((left_min, left_max), (right_min, right_max)) = comparison(left, right)?;
+            overlaps(left_min, left_max, right_min, right_max)
+        }
+        // NOT (both sides are the same single value)
+        NotEqual(left, right) => {
+            let ((left_min, left_max), (right_min, right_max)) = comparison(left, right)?;
+            Or(
+                b(Or(
+                    b(NotEqual(b(left_min.clone()), b(left_max))),
+                    b(NotEqual(b(right_min.clone()), b(right_max))),
+                )),
+                b(NotEqual(b(left_min), b(right_min))),
+            )
+        }
+        Literal(_) | Column(_) | Cast { .. } => return None,
+    };
+    Some(predicate)
+}
+
+/// Whether the ranges of two sides of a comparison overlap, i.e.
+/// `min(left) <= max(right) AND max(left) >= min(right)`.
+fn overlaps(
+    left_min: Expression,
+    left_max: Expression,
+    right_min: Expression,
+    right_max: Expression,
+) -> Expression {
+    use Expression::*;
+    let b = Box::new;
+    And(
+        b(Or(
+            b(LessThan(b(left_min.clone()), b(right_max.clone()))),
+            b(Equal(b(left_min), b(right_max))),
+        )),
+        b(Or(
+            b(GreaterThan(b(left_max.clone()), b(right_min.clone()))),
+            b(Equal(b(left_max), b(right_min))),
+        )),
+    )
+}
+
+/// The min and max values of an operand of a comparison, if they can be derived from the
+/// statistics.
+///
+/// A constant is its own min and max. Casts of constants are always evaluated, but the min and
+/// max of a column are only cast if the cast preserves the order of the values.
+fn operand_bounds(
+    operand: &Expression,
+    stats_schema: &ArrowSchema,
+) -> Option<(Expression, Expression)> {
+    if operand.columns().is_empty() {
+        return Some((operand.clone(), operand.clone()));
+    }
+    match operand {
+        Expression::Column(name) => {
+            stats_type(stats_schema, name)?;
+            Some((
+                Expression::Column(format!("minValues.{name}")),
+                Expression::Column(format!("maxValues.{name}")),
+            ))
+        }
+        Expression::Cast { expr, to } => {
+            let from = match expr.as_ref() {
+                Expression::Column(name) => stats_type(stats_schema, name)?.clone(),
+                Expression::Cast { to, .. } => ArrowDataType::try_from(to).ok()?,
+                _ => return None,
+            };
+            if !preserves_order(&from, &ArrowDataType::try_from(to).ok()?) {
+                return None;
+            }
+            let (min, max) = operand_bounds(expr, stats_schema)?;
+            let cast = |expr| Expression::Cast {
+                expr: Box::new(expr),
+                to: to.clone(),
+            };
+            Some((cast(min), cast(max)))
+        }
+        _ => None,
+    }
+}
+
+/// The stats predicate of an `IN` list of the values of a column, see [`in_list`].
+///
+/// Files whose range does not overlap the range of the sorted values are skipped without looking
+/// at the individual values, as the compiled conjunction does not evaluate its right side if its
+/// left side is false for all files. Only then are the files checked against each distinct
+/// value like for `name = value`.
+fn in_list_predicate(predicate: &Expression, stats_schema: &ArrowSchema) -> Option<Expression> {
+    let (name, values) = in_list(predicate)?;
+    stats_type(stats_schema, name)?;
+    let min = || Expression::Column(format!("minValues.{name}"));
+    let max = || Expression::Column(format!("maxValues.{name}"));
+    let literal = |value: &Scalar| Expression::Literal(value.clone());
+    let (first, last) = (values.first()?, values.last()?);
+    let range = overlaps(min(), max(), literal(first), literal(last));
+    let matches = Expression::or_all(
+        values
+            .iter()
+            .map(|value| overlaps(min(), max(), literal(value), literal(value))),
+    )?;
+    Some(Expression::And(Box::new(range), Box::new(matches)))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{Int32Array, Int64Array, StringArray};
+    use arrow_schema::{Field, Schema};
+
+    use super::*;
+    use crate::schema::PrimitiveType;
+
+    fn column(name: &str) -> Box<Expression> {
+        Box::new(Expression::Column(name.to_string()))
+    }
+
+    fn literal(value: impl Into<Scalar>) -> Box<Expression> {
+        Box::new(Expression::Literal(value.into()))
+    }
+
+    #[test]
+    fn test_compile_row_filter() {
+        let nested = Fields::from(vec![Field::new("b", ArrowDataType::Int64, true)]);
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", ArrowDataType::Int32, true),
+            Field::new("s", ArrowDataType::Struct(nested.clone()), true),
+            Field::new("c", ArrowDataType::Utf8, true),
+        ]));
+        let b: ArrayRef = Arc::new(Int64Array::from(vec![Some(10), Some(2), None]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(StructArray::new(nested, vec![b], None)),
+                Arc::new(StringArray::from(vec!["x", "y", "z"])),
+            ],
+        )
+        .unwrap();
+        let compiler = ArrowExpressionCompiler::new(schema);
+        let evaluate = |expression: Expression| -> BooleanArray {
+            let result = compiler.compile(&expression).unwrap()(&batch).unwrap();
+            as_boolean(&result).unwrap().clone()
+        };
+        let expected = BooleanArray::from;
+
+        assert_eq!(
+            evaluate(Expression::LessThan(column("a"), column("s.b"))),
+            expected(vec![Some(true), Some(false), None])
+        );
+        assert_eq!(
+            evaluate(Expression::Or(
+                Box::new(Expression::Equal(column("c"), literal("y"))),
+                Box::new(Expression::GreaterThan(column("a"), literal(2i64))),
+            )),
+            expected(vec![Some(false), Some(true), Some(true)])
+        );
+        assert_eq!(
+            evaluate(Expression::And(
+                Box::new(Expression::NotEqual(column("a"), literal(1))),
+                Box::new(Expression::GreaterThan(column("s.b"), literal(5i64))),
+            )),
+            expected(vec![Some(false), Some(false), None])
+        );
+
+        let cast = |expr: Box<Expression>, to: PrimitiveType| {
+            Box::new(Expression::Cast {
+                expr,
+                to: DataType::Primitive(to),
+            })
+        };
+        assert_eq!(
+            evaluate(Expression::Equal(
+                column("a"),
+                cast(literal("2"), PrimitiveType::Integer)
+            )),
+            expected(vec![Some(false), Some(true), Some(false)])
+        );
+        // strings compare lexicographically
+        assert_eq!(
+            evaluate(Expression::LessThan(
+                cast(column("s.b"), PrimitiveType::String),
+                literal("3")
+            )),
+            expected(vec![Some(true), Some(true), None])
+        );
+
+        assert!(matches!(
+            compiler.compile(&Expression::Equal(column("s.x"), literal(1))),
+            Err(Error::MissingColumn(_))
+        ));
+        assert!(compiler
+            .compile(&Expression::Equal(column("a.b"), literal(1)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_stats_predicate() {
+        let fields = Fields::from(vec![
+            Field::new("a", ArrowDataType::Int32, true),
+            Field::new("b", ArrowDataType::Int64, true),
+        ]);
+        let stats_struct = |a: Vec<Option<i32>>, b: Vec<Option<i64>>| -> ArrayRef {
+            let a: ArrayRef = Arc::new(Int32Array::from(a));
+            let b: ArrayRef = Arc::new(Int64Array::from(b));
+            Arc::new(StructArray::new(fields.clone(), vec![a, b], None))
+        };
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("minValues", ArrowDataType::Struct(fields.clone()), true),
+            Field::new("maxValues", ArrowDataType::Struct(fields.clone()), true),
+        ]));
+        // a: [10, 20], [0, 5], [3, 3], unknown
+        // b: [0, 5], [1, 10], [3, 3], [4, 4]
+        let stats = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                stats_struct(
+                    vec![Some(10), Some(0), Some(3), None],
+                    vec![Some(0), Some(1), Some(3), Some(4)],
+                ),
+                stats_struct(
+                    vec![Some(20), Some(5), Some(3), None],
+                    vec![Some(5), Some(10), Some(3), Some(4)],
+                ),
+            ],
+        )
+        .unwrap();
+        let compiler = ArrowExpressionCompiler::new(schema);
+
+        let predicates = [
+            (
+                Expression::LessThan(column("a"), literal(5)),
+                vec![Some(false), Some(true), Some(true), None],
+            ),
+            (
+                Expression::GreaterThan(column("a"), literal(5i64)),
+                vec![Some(true), Some(false), Some(false), None],
+            ),
+            (
+                Expression::Equal(column("a"), literal(3)),
+                vec![Some(false), Some(true), Some(true), None],
+            ),
+            (
+                Expression::NotEqual(column("a"), literal(3)),
+                vec![Some(true), Some(true), Some(false), None],
+            ),
+            (
+                Expression::LessThan(literal(4), column("b")),
+                vec![Some(true), Some(true), Some(false), Some(false)],
+            ),
+            (
+                Expression::LessThan(column("a"), column("b")),
+                vec![Some(false), Some(true), Some(false), None],
+            ),
+            (
+                Expression::Equal(column("a"), column("b")),
+                vec![Some(false), Some(true), Some(true), None],
+            ),
+            (
+                Expression::NotEqual(column("a"), column("b")),
+                vec![Some(true), Some(true), Some(false), None],
+            ),
+            // only the usable leg of a conjunction is kept
+            (
+                Expression::And(
+                    Box::new(Expression::GreaterThan(column("b"), literal(2i64))),
+                    Box::new(Expression::Equal(literal(1), literal(1))),
+                ),
+                vec![Some(true), Some(true), Some(true), Some(true)],
+            ),
+            (
+                Expression::Or(
+                    Box::new(Expression::LessThan(column("a"), literal(1))),
+                    Box::new(Expression::Equal(column("b"), literal(4i64))),
+                ),
+                vec![Some(true), Some(true), Some(false), Some(true)],
+            ),
+        ];
+        for (predicate, expected) in predicates {
+            let stats_predicate = predicate.stats_predicate(&stats.schema()).unwrap();
+            let compiled = compiler.compile(&stats_predicate).unwrap()(&stats).unwrap();
+            let expected = BooleanArray::from(expected);
+            assert_eq!(as_boolean(&compiled).unwrap(), &expected, "{predicate}");
+        }
+
+        // predicates without usable legs can not be used for skipping
+        let predicate = Expression::Or(
+            Box::new(Expression::LessThan(column("a"), literal(1))),
+            Box::new(Expression::Equal(literal(1), literal(1))),
+        );
+        assert!(predicate.stats_predicate(&stats.schema()).is_none());
+        // as are comparisons of columns without statistics
+        let predicate = Expression::LessThan(column("c"), literal(1));
+        assert!(predicate.stats_predicate(&stats.schema()).is_none());
+    }
+}
@@ -0,0 +1,917 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use arrow_array::{Array, ArrayRef};
+use arrow_cast::cast;
+use arrow_schema::{ArrowError, DataType as ArrowDataType};
+
+pub(crate) use self::compiler::{stats_type, widen_string_maxima};
+pub use self::compiler::{ArrowExpressionCompiler, CompiledExpression};
+pub use self::scalars::Scalar;
+use crate::schema::DataType;
+
+mod compiler;
+mod scalars;
+
+#[derive(Debug, Clone)]
+pub enum Expression {
+    Literal(Scalar),
+    Column(String), // TODO make path to column (stats.min)
+    LessThan(Box<Expression>, Box<Expression>),
+    GreaterThan(Box<Expression>, Box<Expression>),
+    Equal(Box<Expression>, Box<Expression>),
+    NotEqual(Box<Expression>, Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    /// Cast the value of `expr` to the type `to`.
+    Cast {
+        expr: Box<Expression>,
+        to: DataType,
+    },
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (op, left, right) = match self {
+            Self::Literal(value) => return write!(f, "{value}"),
+            Self::Column(name) => return write!(f, "{name}"),
+            Self::Cast { expr, to } => {
+                return match to {
+                    DataType::Primitive(to) => {
+                        write!(
+                            f,
+                            "CAST({expr} AS {})",
+                            String::from(to.clone()).to_uppercase()
+                        )
+                    }
+                    to => write!(f, "CAST({expr} AS {to:?})"),
+                }
+            }
+            Self::LessThan(left, right) => ("<", left, right),
+            Self::GreaterThan(left, right) => (">", left, right),
+            Self::Equal(left, right) => ("=", left, right),
+            Self::NotEqual(left, right) => ("!=", left, right),
+            Self::And(left, right) => ("AND", left, right),
+            Self::Or(left, right) => ("OR", left, right),
+        };
+        write!(f, "({left} {op} {right})")
+    }
+}
+
+/// The operator of a binary [`Expression`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    LessThan,
+    GreaterThan,
+    Equal,
+    NotEqual,
+    And,
+    Or,
+}
+
+impl BinaryOperator {
+    fn expression(self, left: Expression, right: Expression) -> Expression {
+        let (left, right) = (Box::new(left), Box::new(right));
+        match self {
+            Self::LessThan => Expression::LessThan(left, right),
+            Self::GreaterThan => Expression::GreaterThan(left, right),
+            Self::Equal => Expression::Equal(left, right),
+            Self::NotEqual => Expression::NotEqual(left, right),
+            Self::And => Expression::And(left, right),
+            Self::Or => Expression::Or(left, right),
+        }
+    }
+
+    /// The operator comparing the operands in swapped order the same way, `None` for the
+    /// boolean connectives.
+    fn flipped(self) -> Option<Self> {
+        match self {
+            Self::LessThan => Some(Self::GreaterThan),
+            Self::GreaterThan => Some(Self::LessThan),
+            Self::Equal | Self::NotEqual => Some(self),
+            Self::And | Self::Or => None,
+        }
+    }
+}
+
+/// Visitor folding an [`Expression`] tree bottom-up, see [`Expression::visit`].
+///
+/// The children of a node are visited before the node itself, and their results are passed
+/// to the method visiting the node.
+pub trait ExpressionVisitor {
+    type Output;
+
+    fn visit_literal(&mut self, value: &Scalar) -> Self::Output;
+
+    fn visit_column(&mut self, name: &str) -> Self::Output;
+
+    fn visit_binary(
+        &mut self,
+        op: BinaryOperator,
+        left: Self::Output,
+        right: Self::Output,
+    ) -> Self::Output;
+
+    fn visit_cast(&mut self, expr: Self::Output, to: &DataType) -> Self::Output;
+}
+
+impl Expression {
+    /// Rewrite the expression bottom-up, replacing every node with the result of `f`.
+    ///
+    /// The children of a node are transformed before `f` is called on the node itself.
+    pub fn transform<F: FnMut(Expression) -> Expression>(self, mut f: F) -> Expression {
+        self.transform_with(&mut f)
+    }
+
+    fn transform_with<F: FnMut(Expression) -> Expression>(self, f: &mut F) -> Expression {
+        let (op, left, right) = match self {
+            Expression::LessThan(left, right) => (BinaryOperator::LessThan, left, right),
+            Expression::GreaterThan(left, right) => (BinaryOperator::GreaterThan, left, right),
+            Expression::Equal(left, right) => (BinaryOperator::Equal, left, right),
+            Expression::NotEqual(left, right) => (BinaryOperator::NotEqual, left, right),
+            Expression::And(left, right) => (BinaryOperator::And, left, right),
+            Expression::Or(left, right) => (BinaryOperator::Or, left, right),
+            Expression::Cast { expr, to } => {
+                let expr = Box::new(expr.transform_with(f));
+                return f(Expression::Cast { expr, to });
+            }
+            Expression::Literal(_) | Expression::Column(_) => return f(self),
+        };
+        let left = left.transform_with(f);
+        let right = right.transform_with(f);
+        f(op.expression(left, right))
+    }
+
+    /// Fold the expression bottom-up with `visitor`.
+    pub fn visit<V: ExpressionVisitor>(&self, visitor: &mut V) -> V::Output {
+        let (op, left, right) = match self {
+            Expression::Literal(value) => return visitor.visit_literal(value),
+            Expression::Column(name) => return visitor.visit_column(name),
+            Expression::Cast { expr, to } => {
+                let expr = expr.visit(visitor);
+                return visitor.visit_cast(expr, to);
+            }
+            Expression::LessThan(left, right) => (BinaryOperator::LessThan, left, right),
+            Expression::GreaterThan(left, right) => (BinaryOperator::GreaterThan, left, right),
+            Expression::Equal(left, right) => (BinaryOperator::Equal, left, right),
+            Expression::NotEqual(left, right) => (BinaryOperator::NotEqual, left, right),
+            Expression::And(left, right) => (BinaryOperator::And, left, right),
+            Expression::Or(left, right) => (BinaryOperator::Or, left, right),
+        };
+        let left = left.visit(visitor);
+        let right = right.visit(visitor);
+        visitor.visit_binary(op, left, right)
+    }
+
+    /// Rewrite comparisons into a canonical form with literals on the right.
+    ///
+    /// `literal <op> column` becomes `column <flipped op> literal`, e.g. `10 > x` becomes
+    /// `x < 10`, and comparisons of two columns are ordered by column name. Conjunctions and
+    /// disjunctions keep the order of their operands.
+    pub fn normalize(self) -> Expression {
+        self.transform(|expression| {
+            let (op, left, right) = match expression {
+                Expression::LessThan(left, right) => (BinaryOperator::LessThan, left, right),
+                Expression::GreaterThan(left, right) => (BinaryOperator::GreaterThan, left, right),
+                Expression::Equal(left, right) => (BinaryOperator::Equal, left, right),
+                Expression::NotEqual(left, right) => (BinaryOperator::NotEqual, left, right),
+                _ => return expression,
+            };
+            let swap = match (left.as_ref(), right.as_ref()) {
+                (Expression::Literal(_), Expression::Column(_)) => true,
+                (Expression::Column(left), Expression::Column(right)) => left > right,
+                _ => false,
+            };
+            match op.flipped() {
+                Some(flipped) if swap => flipped.expression(*right, *left),
+                _ => op.expression(*left, *right),
+            }
+        })
+    }
+
+    /// Combine `expressions` into a conjunction, or `None` if there are no expressions.
+    ///
+    /// The conjunction is built as a balanced tree, so its depth only grows logarithmically
+    /// with the number of expressions.
+    pub fn and_all(expressions: impl IntoIterator<Item = Expression>) -> Option<Expression> {
+        balanced(expressions.into_iter().collect(), Expression::And)
+    }
+
+    /// Combine `expressions` into a disjunction, or `None` if there are no expressions.
+    ///
+    /// See [`Expression::and_all`].
+    pub fn or_all(expressions: impl IntoIterator<Item = Expression>) -> Option<Expression> {
+        balanced(expressions.into_iter().collect(), Expression::Or)
+    }
+
+    pub(crate) fn columns(&self) -> Vec<String> {
+        struct Columns;
+        impl ExpressionVisitor for Columns {
+            type Output = Vec<String>;
+            fn visit_literal(&mut self, _: &Scalar) -> Vec<String> {
+                vec![]
+            }
+            fn visit_column(&mut self, name: &str) -> Vec<String> {
+                vec![name.to_string()]
+            }
+            fn visit_binary(
+                &mut self,
+                _: BinaryOperator,
+                mut left: Vec<String>,
+                mut right: Vec<String>,
+            ) -> Vec<String> {
+                left.append(&mut right);
+                left
+            }
+            fn visit_cast(&mut self, expr: Vec<String>, _: &DataType) -> Vec<String> {
+                expr
+            }
+        }
+        self.visit(&mut Columns)
+    }
+
+    /// The paths of all columns referenced in the expression.
+    ///
+    /// Nested columns are referenced by dot separated names, which are split into the names of
+    /// the fields along the path, e.g. `a.b.c` becomes `["a", "b", "c"]`.
+    pub fn references_paths(&self) -> HashSet<Vec<String>> {
+        self.columns()
+            .into_iter()
+            .map(|name| name.split('.').map(String::from).collect())
+            .collect()
+    }
+}
+
+fn balanced(
+    mut expressions: Vec<Expression>,
+    combine: fn(Box<Expression>, Box<Expression>) -> Expression,
+) -> Option<Expression> {
+    match expressions.len() {
+        0 => None,
+        1 => expressions.pop(),
+        len => {
+            let right = expressions.split_off(len / 2);
+            let left = balanced(expressions, combine)?;
+            Some(combine(Box::new(left), Box::new(balanced(right, combine)?)))
+        }
+    }
+}
+
+/// Whether casting values from `from` to `to` keeps their order, so the min and max of the cast
+/// values are the cast min and max.
+///
+/// Narrowing casts may overflow, and casts to strings compare numbers lexicographically, so only
+/// casts to the same type or widening numeric casts preserve the order.
+pub(crate) fn preserves_order(from: &ArrowDataType, to: &ArrowDataType) -> bool {
+    match (from, to) {
+        (from, to) if from == to => true,
+        (from, to) if from.is_signed_integer() && to.is_signed_integer() => {
+            integer_width(from) <= integer_width(to)
+        }
+        (from, ArrowDataType::Float32) if from.is_signed_integer() => integer_width(from) <= 2,
+        (from, ArrowDataType::Float64) if from.is_signed_integer() => integer_width(from) <= 4,
+        (ArrowDataType::Float32, ArrowDataType::Float64) => true,
+        _ => false,
+    }
+}
+
+/// The column and the sorted, distinct values of a disjunction of equalities of that column
+/// with literals, i.e. an `IN` list like `a = 3 OR a = 1 OR a = 3`.
+///
+/// Returns `None` for other expressions, and for null values or values that can not be
+/// ordered, e.g. because they are of incompatible types.
+fn in_list(expression: &Expression) -> Option<(&str, Vec<Scalar>)> {
+    fn collect<'a>(
+        expression: &'a Expression,
+        column: &mut Option<&'a str>,
+        values: &mut Vec<Scalar>,
+    ) -> Option<()> {
+        match expression {
+            Expression::Or(left, right) => {
+                collect(left, column, values)?;
+                collect(right, column, values)
+            }
+            Expression::Equal(left, right) => match (left.as_ref(), right.as_ref()) {
+                (Expression::Column(name), Expression::Literal(value))
+                    if !value.is_null() && column.is_none_or(|column| column == name) =>
+                {
+                    *column = Some(name);
+                    values.push(value.clone());
+                    Some(())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    let mut column = None;
+    let mut values = Vec::new();
+    collect(expression, &mut column, &mut values)?;
+    if values
+        .iter()
+        .any(|value| value.partial_cmp(&values[0]).is_none())
+    {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).expect("values are comparable"));
+    values.dedup();
+    Some((column?, values))
+}
+
+/// Convert the arrays to a common type so they can be compared.
+///
+/// Integers of a different width (e.g. a long literal against an integer column) are both
+/// widened to the larger type. Comparisons involving a floating point type are done as doubles.
+fn coerce_arrays(left: &ArrayRef, right: &ArrayRef) -> Result<(ArrayRef, ArrayRef), ArrowError> {
+    let target = match (left.data_type(), right.data_type()) {
+        (left_type, right_type) if left_type == right_type => {
+            return Ok((left.clone(), right.clone()))
+        }
+        (left_type, right_type) if left_type.is_integer() && right_type.is_integer() => {
+            if integer_width(left_type) >= integer_width(right_type) {
+                left_type.clone()
+            } else {
+                right_type.clone()
+            }
+        }
+        (left_type, right_type) if left_type.is_numeric() && right_type.is_numeric() => {
+            ArrowDataType::Float64
+        }
+        // let the comparison kernel report the mismatch
+        _ => return Ok((left.clone(), right.clone())),
+    };
+    Ok((cast(left, &target)?, cast(right, &target)?))
+}
+
+fn integer_width(data_type: &ArrowDataType) -> usize {
+    match data_type {
+        ArrowDataType::Int8 | ArrowDataType::UInt8 => 1,
+        ArrowDataType::Int16 | ArrowDataType::UInt16 => 2,
+        ArrowDataType::Int32 | ArrowDataType::UInt32 => 4,
+        _ => 8,
+    }
+}
+
+// impl Expression {
+//     fn to_arrow(&self, stats: &StructArray) -> Result<BooleanArray, ArrowError> {
+//         match self {
+//             Expression::LessThan(left, right) => {
+//                 lt_scalar(left.to_arrow(stats), right.to_arrow(stats))
+//             }
+//             Expression::Column(c) => todo!(),
+//             Expression::Literal(l) => todo!(),
+//         }
+//     }
+// }
+
+// transform data predicate into metadata predicate
+// WHERE x < 10 -> min(x) < 10
+// fn construct_metadata_filters(e: Expression) -> Expression {
+//     match e {
+//         // col < value
+//         Expression::LessThan(left, right) => {
+//             match (*left, *right.clone()) {
+//                 (Expression::Column(name), Expression::Literal(_)) => {
+//                     // column_min < value
+//                     Expression::LessThan(Box::new(min_stat_col(name)), right)
+//                 }
+//                 _ => todo!(),
+//             }
+//         }
+//         _ => todo!(),
+//     }
+// }
+
+// fn min_stat_col(col_name: Vec<String>) -> Expression {
+//     stat_col("min", col_name)
+// }
+//
+// fn stat_col(stat: &str, name: Vec<String>) -> Expression {
+//     let mut v = vec![stat.to_owned()];
+//     v.extend(name);
+//     Expression::Column(v)
+// }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{
+        BinaryArray, BooleanArray, Int32Array, Int64Array, RecordBatch, StringArray, StructArray,
+    };
+    use arrow_schema::Field;
+
+    use super::*;
+
+    /// Statistics with one row per file, holding the min values, max values and null counts of
+    /// each of the named `columns`.
+    fn stats_batch(columns: Vec<(&str, ArrayRef, ArrayRef, ArrayRef)>) -> RecordBatch {
+        let stats_struct = |values: Vec<(&str, ArrayRef)>| -> ArrayRef {
+            let values = values.into_iter().map(|(name, values)| {
+                let field = Field::new(name, values.data_type().clone(), true);
+                (Arc::new(field), values)
+            });
+            Arc::new(StructArray::from(values.collect::<Vec<_>>()))
+        };
+        let min = columns.iter().map(|(name, min, _, _)| (*name, min.clone()));
+        let max = columns.iter().map(|(name, _, max, _)| (*name, max.clone()));
+        let null_count = columns
+            .iter()
+            .map(|(name, _, _, count)| (*name, count.clone()));
+        RecordBatch::try_from_iter([
+            ("minValues", stats_struct(min.collect())),
+            ("maxValues", stats_struct(max.collect())),
+            ("nullCount", stats_struct(null_count.collect())),
+        ])
+        .unwrap()
+    }
+
+    /// Null counts of `num_files` files without any nulls.
+    fn no_nulls(num_files: usize) -> ArrayRef {
+        Arc::new(Int64Array::from(vec![0; num_files]))
+    }
+
+    /// Statistics of the single column `name` without nulls, see [`stats_batch`].
+    fn column_stats<A: Array + From<Vec<T>> + 'static, T>(
+        name: &str,
+        min: Vec<T>,
+        max: Vec<T>,
+    ) -> RecordBatch {
+        let num_files = min.len();
+        let (min, max) = (Arc::new(A::from(min)), Arc::new(A::from(max)));
+        stats_batch(vec![(name, min, max, no_nulls(num_files))])
+    }
+
+    fn binary_stats() -> RecordBatch {
+        column_stats::<BinaryArray, &[u8]>(
+            "b",
+            vec![b"\x00", b"\x05", b"\x10"],
+            vec![b"\x04", b"\x0f", b"\x20"],
+        )
+    }
+
+    #[test]
+    fn test_binary_skipping() {
+        let column = || Box::new(Expression::Column("b".into()));
+
+        let predicate =
+            Expression::Equal(column(), Box::new(Expression::Literal(vec![0x06].into())));
+        let filter = predicate.skipping_filter(&binary_stats()).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![false, true, false]));
+
+        let predicate =
+            Expression::LessThan(column(), Box::new(Expression::Literal(vec![0x05].into())));
+        let filter = predicate.skipping_filter(&binary_stats()).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![true, false, false]));
+    }
+
+    #[test]
+    fn test_integer_width_coercion() {
+        let stats = column_stats::<Int32Array, _>("ids", vec![0, 5, 10], vec![4, 9, 14]);
+        let column = || Box::new(Expression::Column("ids".into()));
+
+        for literal in [Scalar::Integer(5), Scalar::Long(5)] {
+            let predicate = Expression::LessThan(column(), Box::new(Expression::Literal(literal)));
+            let filter = predicate.skipping_filter(&stats).unwrap().unwrap();
+            assert_eq!(filter, BooleanArray::from(vec![true, false, false]));
+        }
+
+        // values out of range for the column type must not wrap around
+        let predicate = Expression::LessThan(
+            column(),
+            Box::new(Expression::Literal(Scalar::Long(i64::MAX))),
+        );
+        let filter = predicate.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![true, true, true]));
+
+        let predicate = Expression::Equal(
+            column(),
+            Box::new(Expression::Literal(Scalar::Double(12.5))),
+        );
+        let filter = predicate.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![false, false, true]));
+    }
+
+    #[test]
+    fn test_integer_literal_long_column() {
+        // the last file holds values beyond the range of an integer
+        let stats =
+            column_stats::<Int64Array, _>("ids", vec![0, 5, i64::MAX - 1], vec![4, 9, i64::MAX]);
+        let column = || Box::new(Expression::Column("ids".into()));
+
+        let predicate =
+            Expression::GreaterThan(column(), Box::new(Expression::Literal(Scalar::Integer(4))));
+        let filter = predicate.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![false, true, true]));
+
+        let predicate =
+            Expression::Equal(column(), Box::new(Expression::Literal(Scalar::Integer(7))));
+        let filter = predicate.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![false, true, false]));
+    }
+
+    #[test]
+    fn test_not_equal_skipping() {
+        // a constant file, a varied file containing the value and a constant file of another value
+        let stats = column_stats::<Int32Array, _>("ids", vec![5, 0, 6], vec![5, 10, 6]);
+
+        let predicate = Expression::NotEqual(
+            Box::new(Expression::Column("ids".into())),
+            Box::new(Expression::Literal(Scalar::Integer(5))),
+        );
+        let filter = predicate.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![false, true, true]));
+
+        let predicate = Expression::NotEqual(
+            Box::new(Expression::Column("ids".into())),
+            Box::new(Expression::Literal(Scalar::Long(5))),
+        );
+        let filter = predicate.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![false, true, true]));
+    }
+
+    #[test]
+    fn test_conjunction_skipping() {
+        let column = |name: &str| Box::new(Expression::Column(name.into()));
+        let literal = |value: &[u8]| Box::new(Expression::Literal(value.to_vec().into()));
+        let usable = || Expression::LessThan(column("b"), literal(b"\x05"));
+        let unusable = || Expression::LessThan(column("b"), column("missing"));
+
+        let predicate = Expression::And(Box::new(usable()), Box::new(unusable()));
+        let filter = predicate.skipping_filter(&binary_stats()).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![true, false, false])));
+
+        let predicate = Expression::Or(
+            Box::new(usable()),
+            Box::new(Expression::Equal(column("b"), literal(b"\x10"))),
+        );
+        let filter = predicate.skipping_filter(&binary_stats()).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![true, false, true])));
+
+        let predicate = Expression::Or(Box::new(usable()), Box::new(unusable()));
+        let filter = predicate.skipping_filter(&binary_stats()).unwrap();
+        assert_eq!(filter, None);
+    }
+
+    #[test]
+    fn test_truncated_string_stats() {
+        let prefix = "a".repeat(compiler::STRING_PREFIX_LENGTH);
+        // the max of the first file is truncated, the one of the second is exact
+        let stats = column_stats::<StringArray, _>("s", vec!["a", "a"], vec![&prefix, "a"]);
+        let column = || Box::new(Expression::Column("s".into()));
+
+        // the true max of the first file may be larger than the stored prefix
+        let greater_than = |value: String| {
+            Expression::GreaterThan(column(), Box::new(Expression::Literal(value.into())))
+        };
+        let filter = greater_than(format!("{prefix}b"))
+            .skipping_filter(&stats)
+            .unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![true, false])));
+
+        // values shorter than the prefix length are exact
+        let filter = greater_than("a".into()).skipping_filter(&stats).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![true, false])));
+
+        // values not starting with the prefix are still compared against it
+        let filter = greater_than("b".into()).skipping_filter(&stats).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![false, false])));
+
+        let literal = format!("{prefix}zzz");
+        let predicate = Expression::Equal(column(), Box::new(Expression::Literal(literal.into())));
+        let filter = predicate.skipping_filter(&stats).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![true, false])));
+    }
+
+    #[test]
+    fn test_balanced_conjunctions() {
+        fn depth(expression: &Expression) -> usize {
+            match expression {
+                Expression::And(left, right) | Expression::Or(left, right) => {
+                    1 + depth(left).max(depth(right))
+                }
+                _ => 0,
+            }
+        }
+        let stats = column_stats::<Int32Array, _>("x", vec![0, 500], vec![10, 2000]);
+        // x > i for i in 0..1000
+        let children = || {
+            (0..1000).map(|i| {
+                Expression::GreaterThan(
+                    Box::new(Expression::Column("x".into())),
+                    Box::new(Expression::Literal(Scalar::Integer(i))),
+                )
+            })
+        };
+
+        let conjunction = Expression::and_all(children()).unwrap();
+        assert_eq!(depth(&conjunction), 10);
+        assert_eq!(conjunction.columns().len(), 1000);
+        let filter = conjunction.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![false, true]));
+
+        let disjunction = Expression::or_all(children()).unwrap();
+        assert_eq!(depth(&disjunction), 10);
+        let filter = disjunction.skipping_filter(&stats).unwrap().unwrap();
+        assert_eq!(filter, BooleanArray::from(vec![true, true]));
+
+        assert!(Expression::and_all(std::iter::empty()).is_none());
+        assert!(matches!(
+            Expression::or_all(children().take(1)),
+            Some(Expression::GreaterThan(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_column_comparison_skipping() {
+        // a: [10, 20], [0, 5], [3, 3]
+        // b: [0, 5], [1, 10], [3, 3]
+        let stats = stats_batch(vec![
+            (
+                "a",
+                Arc::new(Int32Array::from(vec![10, 0, 3])),
+                Arc::new(Int32Array::from(vec![20, 5, 3])),
+                no_nulls(3),
+            ),
+            (
+                "b",
+                Arc::new(Int64Array::from(vec![0, 1, 3])),
+                Arc::new(Int64Array::from(vec![5, 10, 3])),
+                no_nulls(3),
+            ),
+        ]);
+        let column = |name: &str| Box::new(Expression::Column(name.into()));
+        let filter = |predicate: Expression| predicate.skipping_filter(&stats).unwrap().unwrap();
+
+        // all values of a exceed all values of b in the first file
+        assert_eq!(
+            filter(Expression::LessThan(column("a"), column("b"))),
+            BooleanArray::from(vec![false, true, false])
+        );
+        assert_eq!(
+            filter(Expression::GreaterThan(column("b"), column("a"))),
+            BooleanArray::from(vec![false, true, false])
+        );
+        assert_eq!(
+            filter(Expression::Equal(column("a"), column("b"))),
+            BooleanArray::from(vec![false, true, true])
+        );
+        assert_eq!(
+            filter(Expression::NotEqual(column("a"), column("b"))),
+            BooleanArray::from(vec![true, true, false])
+        );
+
+        // literals on the left are compared like columns
+        let literal = || Box::new(Expression::Literal(Scalar::Integer(6)));
+        assert_eq!(
+            filter(Expression::LessThan(literal(), column("a"))),
+            BooleanArray::from(vec![true, false, false])
+        );
+        assert_eq!(
+            filter(Expression::Equal(literal(), column("b"))),
+            BooleanArray::from(vec![false, true, false])
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let column = |name: &str| Box::new(Expression::Column(name.to_string()));
+        let literal = |value: i32| Box::new(Expression::Literal(Scalar::Integer(value)));
+
+        let normalized = Expression::GreaterThan(literal(10), column("x")).normalize();
+        assert!(matches!(
+            normalized,
+            Expression::LessThan(left, right)
+                if matches!(left.as_ref(), Expression::Column(name) if name == "x")
+                && matches!(right.as_ref(), Expression::Literal(Scalar::Integer(10)))
+        ));
+        let normalized = Expression::LessThan(column("b"), column("a")).normalize();
+        assert!(matches!(
+            normalized,
+            Expression::GreaterThan(left, right)
+                if matches!(left.as_ref(), Expression::Column(name) if name == "a")
+                && matches!(right.as_ref(), Expression::Column(name) if name == "b")
+        ));
+
+        // nested comparisons are normalized, the operands of connectives keep their order
+        let normalized = Expression::Or(
+            Box::new(Expression::Equal(literal(1), column("x"))),
+            Box::new(Expression::NotEqual(column("x"), literal(2))),
+        )
+        .normalize();
+        let Expression::Or(left, right) = normalized else {
+            panic!("expected a disjunction")
+        };
+        assert!(matches!(
+            (left.as_ref(), right.as_ref()),
+            (Expression::Equal(left, right), Expression::NotEqual(_, _))
+                if matches!(left.as_ref(), Expression::Column(_))
+                && matches!(right.as_ref(), Expression::Literal(Scalar::Integer(1)))
+        ));
+
+        // comparisons of literals and normalized comparisons are left as they are
+        let normalized = Expression::LessThan(literal(2), literal(1)).normalize();
+        assert!(matches!(
+            normalized,
+            Expression::LessThan(left, right)
+                if matches!(left.as_ref(), Expression::Literal(Scalar::Integer(2)))
+                && matches!(right.as_ref(), Expression::Literal(Scalar::Integer(1)))
+        ));
+        let normalized = Expression::LessThan(column("x"), literal(1)).normalize();
+        assert!(matches!(normalized, Expression::LessThan(_, _)));
+    }
+
+    #[test]
+    fn test_transform() {
+        let column = |name: &str| Box::new(Expression::Column(name.to_string()));
+        let predicate = Expression::And(
+            Box::new(Expression::LessThan(
+                column("a"),
+                Box::new(Expression::Literal(Scalar::Integer(1))),
+            )),
+            Box::new(Expression::Or(
+                Box::new(Expression::Equal(column("b"), column("c"))),
+                Box::new(Expression::NotEqual(
+                    column("a"),
+                    Box::new(Expression::Literal(Scalar::from("a"))),
+                )),
+            )),
+        );
+        let transformed = predicate.clone().transform(|expression| match expression {
+            Expression::Column(name) => Expression::Column(format!("nested.{name}")),
+            other => other,
+        });
+        assert_eq!(
+            transformed.columns(),
+            vec!["nested.a", "nested.b", "nested.c", "nested.a"]
+        );
+        // everything but the column names is unchanged
+        let restored = transformed.transform(|expression| match expression {
+            Expression::Column(name) => Expression::Column(name["nested.".len()..].to_string()),
+            other => other,
+        });
+        assert_eq!(format!("{restored:?}"), format!("{predicate:?}"));
+
+        // children are transformed before their parents
+        let mut visited = Vec::new();
+        Expression::Equal(column("a"), column("b")).transform(|expression| {
+            visited.push(format!("{expression:?}"));
+            expression
+        });
+        assert_eq!(visited.len(), 3);
+        assert!(visited[2].starts_with("Equal"));
+    }
+
+    #[test]
+    fn test_references_paths() {
+        let predicate = Expression::And(
+            Box::new(Expression::Equal(
+                Box::new(Expression::Column("a.b.c".to_string())),
+                Box::new(Expression::Literal(Scalar::Long(1))),
+            )),
+            Box::new(Expression::LessThan(
+                Box::new(Expression::Column("d".to_string())),
+                Box::new(Expression::Column("a.b.c".to_string())),
+            )),
+        );
+        let expected = HashSet::from([
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            vec!["d".to_string()],
+        ]);
+        assert_eq!(predicate.references_paths(), expected);
+    }
+
+    #[test]
+    fn test_visit() {
+        /// Renders the expression in infix notation.
+        struct Infix;
+        impl ExpressionVisitor for Infix {
+            type Output = String;
+            fn visit_literal(&mut self, value: &Scalar) -> String {
+                value.to_string()
+            }
+            fn visit_column(&mut self, name: &str) -> String {
+                name.to_string()
+            }
+            fn visit_binary(&mut self, op: BinaryOperator, left: String, right: String) -> String {
+                let op = match op {
+                    BinaryOperator::LessThan => "<",
+                    BinaryOperator::GreaterThan => ">",
+                    BinaryOperator::Equal => "=",
+                    BinaryOperator::NotEqual => "!=",
+                    BinaryOperator::And => "AND",
+                    BinaryOperator::Or => "OR",
+                };
+                format!("({left} {op} {right})")
+            }
+            fn visit_cast(&mut self, expr: String, _: &crate::schema::DataType) -> String {
+                format!("CAST({expr})")
+            }
+        }
+
+        let predicate = Expression::Or(
+            Box::new(Expression::GreaterThan(
+                Box::new(Expression::Column("a".to_string())),
+                Box::new(Expression::Literal(Scalar::Long(5))),
+            )),
+            Box::new(Expression::Equal(
+                Box::new(Expression::Column("b".to_string())),
+                Box::new(Expression::Literal(Scalar::from("x"))),
+            )),
+        );
+        assert_eq!(predicate.visit(&mut Infix), "((a > 5) OR (b = 'x'))");
+    }
+
+    #[test]
+    fn test_in_list_skipping() {
+        let x = || Box::new(Expression::Column("x".to_string()));
+        let is_in = |values: Vec<i64>| {
+            Expression::or_all(values.into_iter().map(|value| {
+                Expression::Equal(x(), Box::new(Expression::Literal(Scalar::Long(value))))
+            }))
+            .unwrap()
+        };
+
+        // every value twice
+        let predicate = is_in((0..20_000).map(|i| (i % 10_000) * 10).collect());
+        let (column, values) = in_list(&predicate).unwrap();
+        assert_eq!(column, "x");
+        assert_eq!(values.len(), 10_000);
+        assert_eq!(values[0], Scalar::Long(0));
+        assert_eq!(values[9_999], Scalar::Long(99_990));
+
+        let stats = |min, max| column_stats::<Int32Array, i32>("x", min, max);
+
+        // files outside the range of the list are skipped by the range check alone
+        let outside = stats(vec![-100, 100_000], vec![-1, 200_000]);
+        let filter = predicate.skipping_filter(&outside).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![false, false])));
+
+        // files within the range are checked against the individual values
+        let within = stats(vec![-100, 11, 50, 99_990], vec![0, 19, 50, 100_000]);
+        let filter = predicate.skipping_filter(&within).unwrap();
+        assert_eq!(
+            filter,
+            Some(BooleanArray::from(vec![true, false, true, true]))
+        );
+
+        // lists mixing columns are evaluated as disjunctions
+        let predicate = Expression::Or(
+            Box::new(is_in(vec![1, 2])),
+            Box::new(Expression::Equal(
+                Box::new(Expression::Column("y".to_string())),
+                Box::new(Expression::Literal(Scalar::Long(1))),
+            )),
+        );
+        assert!(in_list(&predicate).is_none());
+    }
+
+    #[test]
+    fn test_cast_skipping() {
+        use crate::schema::{DataType as DeltaDataType, PrimitiveType};
+
+        let cast = |expr: Expression, to: PrimitiveType| Expression::Cast {
+            expr: Box::new(expr),
+            to: DeltaDataType::Primitive(to),
+        };
+        let x = || Expression::Column("x".to_string());
+        let predicate = Expression::GreaterThan(
+            Box::new(cast(x(), PrimitiveType::Long)),
+            Box::new(cast(
+                Expression::Literal("5".into()),
+                PrimitiveType::Integer,
+            )),
+        );
+        assert_eq!(
+            predicate.to_string(),
+            "(CAST(x AS LONG) > CAST('5' AS INTEGER))"
+        );
+
+        // x: [0, 3], [4, 300]
+        let stats = column_stats::<Int32Array, _>("x", vec![0, 4], vec![3, 300]);
+
+        let filter = predicate.skipping_filter(&stats).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![false, true])));
+
+        // the literal side is cast even on the left
+        let predicate = Expression::LessThan(
+            Box::new(cast(
+                Expression::Literal("3".into()),
+                PrimitiveType::Integer,
+            )),
+            Box::new(x()),
+        );
+        let filter = predicate.skipping_filter(&stats).unwrap();
+        assert_eq!(filter, Some(BooleanArray::from(vec![false, true])));
+
+        // narrowing casts and casts to strings do not preserve the order of the values
+        for to in [PrimitiveType::Byte, PrimitiveType::String] {
+            let predicate = Expression::Equal(
+                Box::new(cast(x(), to)),
+                Box::new(Expression::Literal(Scalar::Byte(44))),
+            );
+            let filter = predicate.skipping_filter(&stats).unwrap();
+            assert!(filter.is_none());
+        }
+    }
+}
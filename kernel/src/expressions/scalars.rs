@@ -0,0 +1,548 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use arrow_array::{
+    new_null_array, ArrayRef, BinaryArray, BooleanArray, Date32Array, Float32Array, Float64Array,
+    Int16Array, Int32Array, Int64Array, Int8Array, StringArray, TimestampMicrosecondArray,
+};
+use arrow_schema::{ArrowError, DataType as ArrowDataType};
+
+use crate::schema::{DataType, PrimitiveType};
+use crate::{DeltaResult, Error};
+
+/// A single value, which can be null. Used for representing literal values
+/// in [Expressions][crate::expressions::Expression].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    Integer(i32),
+    Long(i64),
+    Short(i16),
+    Byte(i8),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Boolean(bool),
+    /// Microseconds since the Unix epoch
+    Timestamp(i64),
+    /// Microseconds since the Unix epoch, without a timezone
+    TimestampNtz(i64),
+    /// Days since the Unix epoch
+    Date(i32),
+    Binary(Vec<u8>),
+    Null(DataType),
+}
+
+impl Scalar {
+    /// The [`DataType`] of this value.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Self::Integer(_) => DataType::Primitive(PrimitiveType::Integer),
+            Self::Long(_) => DataType::Primitive(PrimitiveType::Long),
+            Self::Short(_) => DataType::Primitive(PrimitiveType::Short),
+            Self::Byte(_) => DataType::Primitive(PrimitiveType::Byte),
+            Self::Float(_) => DataType::Primitive(PrimitiveType::Float),
+            Self::Double(_) => DataType::Primitive(PrimitiveType::Double),
+            Self::String(_) => DataType::Primitive(PrimitiveType::String),
+            Self::Boolean(_) => DataType::Primitive(PrimitiveType::Boolean),
+            Self::Timestamp(_) => DataType::Primitive(PrimitiveType::Timestamp),
+            Self::TimestampNtz(_) => DataType::Primitive(PrimitiveType::TimestampNtz),
+            Self::Date(_) => DataType::Primitive(PrimitiveType::Date),
+            Self::Binary(_) => DataType::Primitive(PrimitiveType::Binary),
+            Self::Null(data_type) => data_type.clone(),
+        }
+    }
+
+    /// Create an arrow array of length `num_rows` with all values set to this value.
+    pub(crate) fn to_array(&self, num_rows: usize) -> Result<ArrayRef, ArrowError> {
+        let array: ArrayRef = match self {
+            Self::Integer(i) => Arc::new(Int32Array::from(vec![*i; num_rows])),
+            Self::Long(i) => Arc::new(Int64Array::from(vec![*i; num_rows])),
+            Self::Short(i) => Arc::new(Int16Array::from(vec![*i; num_rows])),
+            Self::Byte(i) => Arc::new(Int8Array::from(vec![*i; num_rows])),
+            Self::Float(f) => Arc::new(Float32Array::from(vec![*f; num_rows])),
+            Self::Double(f) => Arc::new(Float64Array::from(vec![*f; num_rows])),
+            Self::String(s) => Arc::new(StringArray::from(vec![s.as_str(); num_rows])),
+            Self::Boolean(b) => Arc::new(BooleanArray::from(vec![*b; num_rows])),
+            Self::Timestamp(ts) => {
+                Arc::new(TimestampMicrosecondArray::from(vec![*ts; num_rows]).with_timezone("UTC"))
+            }
+            Self::TimestampNtz(ts) => {
+                Arc::new(TimestampMicrosecondArray::from(vec![*ts; num_rows]))
+            }
+            Self::Date(d) => Arc::new(Date32Array::from(vec![*d; num_rows])),
+            Self::Binary(b) => Arc::new(BinaryArray::from(vec![b.as_slice(); num_rows])),
+            Self::Null(data_type) => new_null_array(&ArrowDataType::try_from(data_type)?, num_rows),
+        };
+        Ok(array)
+    }
+
+    /// Returns true if this value is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null(_))
+    }
+
+    /// Parse the string representation of a value of type `data_type`.
+    ///
+    /// Dates are expected as `YYYY-MM-DD`, timestamps as `YYYY-MM-DD HH:MM:SS[.ffffff]` in UTC
+    /// (or without timezone for `timestamp_ntz`), where the date and time may also be separated
+    /// by `T`. Dates and times out of range, e.g. `2021-02-29` or `24:00:00`, are invalid.
+    ///
+    /// Binary values and non-primitive types are not supported. Decimals are not supported
+    /// either, as there is no decimal [`Scalar`] yet, and parsing them returns an error.
+    pub fn try_from_string(raw: &str, data_type: &DataType) -> DeltaResult<Self> {
+        let invalid =
+            || Error::Generic(format!("Invalid value '{}' for type {:?}", raw, data_type));
+        let DataType::Primitive(primitive) = data_type else {
+            return Err(invalid());
+        };
+        let value = match primitive {
+            PrimitiveType::Decimal(..) => {
+                return Err(Error::Generic(format!(
+                    "Parsing decimal values is not supported, can not parse '{}' as {:?}",
+                    raw, data_type
+                )))
+            }
+            PrimitiveType::String => Self::String(raw.to_string()),
+            PrimitiveType::Long => Self::Long(raw.parse().map_err(|_| invalid())?),
+            PrimitiveType::Integer => Self::Integer(raw.parse().map_err(|_| invalid())?),
+            PrimitiveType::Short => Self::Short(raw.parse().map_err(|_| invalid())?),
+            PrimitiveType::Byte => Self::Byte(raw.parse().map_err(|_| invalid())?),
+            PrimitiveType::Float => Self::Float(raw.parse().map_err(|_| invalid())?),
+            PrimitiveType::Double => Self::Double(raw.parse().map_err(|_| invalid())?),
+            PrimitiveType::Boolean => match raw.to_ascii_lowercase().as_str() {
+                "true" => Self::Boolean(true),
+                "false" => Self::Boolean(false),
+                _ => return Err(invalid()),
+            },
+            PrimitiveType::Date => Self::Date(parse_date(raw).ok_or_else(invalid)?),
+            PrimitiveType::Timestamp => Self::Timestamp(parse_timestamp(raw).ok_or_else(invalid)?),
+            PrimitiveType::TimestampNtz => {
+                Self::TimestampNtz(parse_timestamp(raw).ok_or_else(invalid)?)
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(value)
+    }
+
+    /// Parse a literal as written by the [`Display`] implementation of a value of `data_type`.
+    ///
+    /// Strings must be enclosed in single quotes, with embedded quotes doubled, e.g. `'it''s'`.
+    /// Other values are parsed with [`Scalar::try_from_string`], which takes strings as they are,
+    /// e.g. raw partition values, and therefore does not unescape them.
+    pub fn try_from_literal(literal: &str, data_type: &DataType) -> DeltaResult<Self> {
+        if data_type != &DataType::Primitive(PrimitiveType::String) {
+            return Self::try_from_string(literal, data_type);
+        }
+        let invalid = || Error::Generic(format!("Invalid string literal {}", literal));
+        let inner = literal
+            .strip_prefix('\'')
+            .and_then(|rest| rest.strip_suffix('\''))
+            .ok_or_else(invalid)?;
+        // every quote within the literal must be escaped by another quote
+        if inner.split("''").any(|part| part.contains('\'')) {
+            return Err(invalid());
+        }
+        Ok(Self::String(inner.replace("''", "'")))
+    }
+}
+
+/// Parse a `YYYY-MM-DD` date into days since the Unix epoch.
+fn parse_date(raw: &str) -> Option<i32> {
+    let mut parts = raw.splitn(3, '-');
+    let year = parse_digits(parts.next()?)?;
+    let month = parse_digits(parts.next()?)?;
+    let day = parse_digits(parts.next()?)?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    // see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146097 + day_of_era - 719468).try_into().ok()
+}
+
+/// The number of days of `month` in `year` of the Gregorian calendar.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parse a non-empty string of decimal digits, i.e. without a sign.
+fn parse_digits(raw: &str) -> Option<i64> {
+    if raw.is_empty() || !raw.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    raw.parse().ok()
+}
+
+/// Parse a `YYYY-MM-DD HH:MM:SS[.ffffff]` timestamp into microseconds since the Unix epoch.
+fn parse_timestamp(raw: &str) -> Option<i64> {
+    let (date, time) = raw.split_once([' ', 'T'])?;
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, fraction),
+        None => (time, "0"),
+    };
+    let mut parts = time.splitn(3, ':');
+    let hours = parse_digits(parts.next()?)?;
+    let minutes = parse_digits(parts.next()?)?;
+    let seconds = parse_digits(parts.next()?)?;
+    if hours > 23 || minutes > 59 || seconds > 59 || fraction.len() > 6 {
+        return None;
+    }
+    let micros = parse_digits(&format!("{:0<6}", fraction))?;
+    let seconds = i64::from(parse_date(date)?) * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    Some(seconds * 1_000_000 + micros)
+}
+
+/// Values are only comparable with values of the same type. Nulls and values of different types
+/// are incomparable, see [`Scalar::compare_widened`] to compare numeric values of different types.
+impl PartialOrd for Scalar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a.partial_cmp(b),
+            (Self::Long(a), Self::Long(b)) => a.partial_cmp(b),
+            (Self::Short(a), Self::Short(b)) => a.partial_cmp(b),
+            (Self::Byte(a), Self::Byte(b)) => a.partial_cmp(b),
+            (Self::Float(a), Self::Float(b)) => a.partial_cmp(b),
+            (Self::Double(a), Self::Double(b)) => a.partial_cmp(b),
+            (Self::String(a), Self::String(b)) => a.partial_cmp(b),
+            (Self::Boolean(a), Self::Boolean(b)) => a.partial_cmp(b),
+            (Self::Date(a), Self::Date(b)) => a.partial_cmp(b),
+            (Self::Timestamp(a), Self::Timestamp(b)) => a.partial_cmp(b),
+            (Self::TimestampNtz(a), Self::TimestampNtz(b)) => a.partial_cmp(b),
+            (Self::Binary(a), Self::Binary(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+impl Scalar {
+    /// Compare two values like [`PartialOrd`], but also compare numeric values of different
+    /// types: integral values of different widths are widened to `i64` and are compared as `f64`
+    /// against floating point values.
+    pub fn compare_widened(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::Float(_) | Self::Double(_), _) | (_, Self::Float(_) | Self::Double(_)) => {
+                self.as_f64()?.partial_cmp(&other.as_f64()?)
+            }
+            (Self::Integer(_) | Self::Long(_) | Self::Short(_) | Self::Byte(_), _) => {
+                self.as_i64()?.partial_cmp(&other.as_i64()?)
+            }
+            _ => self.partial_cmp(other),
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Long(v) => Some(*v),
+            Self::Integer(v) => Some(*v as i64),
+            Self::Short(v) => Some(*v as i64),
+            Self::Byte(v) => Some(*v as i64),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Double(v) => Some(*v),
+            Self::Float(v) => Some(*v as f64),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+}
+
+impl Display for Scalar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(i) => write!(f, "{}", i),
+            Self::Long(i) => write!(f, "{}", i),
+            Self::Short(i) => write!(f, "{}", i),
+            Self::Byte(i) => write!(f, "{}", i),
+            Self::Float(fl) => write!(f, "{}", fl),
+            Self::Double(fl) => write!(f, "{}", fl),
+            // embedded quotes are doubled, as in SQL string literals
+            Self::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            Self::Boolean(b) => write!(f, "{}", b),
+            Self::Timestamp(ts) | Self::TimestampNtz(ts) => write!(f, "{}", ts),
+            Self::Date(d) => write!(f, "{}", d),
+            Self::Binary(b) => {
+                write!(f, "X'")?;
+                for byte in b {
+                    write!(f, "{:02X}", byte)?;
+                }
+                write!(f, "'")
+            }
+            Self::Null(_) => write!(f, "null"),
+        }
+    }
+}
+
+impl From<i32> for Scalar {
+    fn from(i: i32) -> Self {
+        Self::Integer(i)
+    }
+}
+
+impl From<i64> for Scalar {
+    fn from(i: i64) -> Self {
+        Self::Long(i)
+    }
+}
+
+impl From<f32> for Scalar {
+    fn from(f: f32) -> Self {
+        Self::Float(f)
+    }
+}
+
+impl From<f64> for Scalar {
+    fn from(f: f64) -> Self {
+        Self::Double(f)
+    }
+}
+
+impl From<bool> for Scalar {
+    fn from(b: bool) -> Self {
+        Self::Boolean(b)
+    }
+}
+
+impl From<&str> for Scalar {
+    fn from(s: &str) -> Self {
+        Self::String(s.into())
+    }
+}
+
+impl From<String> for Scalar {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<Vec<u8>> for Scalar {
+    fn from(b: Vec<u8>) -> Self {
+        Self::Binary(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::Array;
+
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Scalar::from("a"), Scalar::String("a".to_string()));
+        assert_eq!(Scalar::from("a"), Scalar::from("a".to_string()));
+        assert_eq!(Scalar::from("a").to_string(), "'a'");
+    }
+
+    #[test]
+    fn test_display_escaping() {
+        assert_eq!(Scalar::from("it's").to_string(), "'it''s'");
+        assert_eq!(Scalar::from("'").to_string(), "''''");
+        assert_eq!(Scalar::from("").to_string(), "''");
+        // only quotes are escaped, backslashes and newlines are kept as is
+        assert_eq!(Scalar::from(r"a\b").to_string(), r"'a\b'");
+        assert_eq!(Scalar::from("a\\'b").to_string(), "'a\\''b'");
+        assert_eq!(Scalar::from("a\nb").to_string(), "'a\nb'");
+    }
+
+    #[test]
+    fn test_string_literal_round_trip() {
+        let string = DataType::Primitive(PrimitiveType::String);
+        for value in ["it's", "'", "''", "", r"a\b", "a\\'b", "a\nb", "'quoted'"] {
+            let literal = Scalar::from(value).to_string();
+            assert_eq!(
+                Scalar::try_from_literal(&literal, &string).unwrap(),
+                Scalar::from(value),
+                "{literal}"
+            );
+        }
+        for invalid in ["it's", "'it's'", "'unterminated", "'", "'''"] {
+            assert!(
+                Scalar::try_from_literal(invalid, &string).is_err(),
+                "{invalid}"
+            );
+        }
+        assert_eq!(
+            Scalar::try_from_literal("5", &DataType::Primitive(PrimitiveType::Long)).unwrap(),
+            Scalar::Long(5)
+        );
+    }
+
+    #[test]
+    fn test_binary() {
+        let scalar = Scalar::from(vec![0x0a, 0xff, 0x00]);
+        assert_eq!(scalar, Scalar::Binary(vec![10, 255, 0]));
+        assert_eq!(
+            scalar.data_type(),
+            DataType::Primitive(PrimitiveType::Binary)
+        );
+        assert_eq!(scalar.to_string(), "X'0AFF00'");
+        assert_eq!(Scalar::Binary(vec![]).to_string(), "X''");
+
+        let array = scalar.to_array(2).unwrap();
+        let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.value(1), &[0x0a, 0xff, 0x00]);
+    }
+
+    #[test]
+    fn test_partial_cmp_same_type() {
+        assert!(Scalar::Integer(5) < Scalar::Integer(10));
+        assert!(Scalar::from("a") < Scalar::from("b"));
+        assert!(Scalar::from("b") > Scalar::from("abc"));
+        assert!(Scalar::Date(1) >= Scalar::Date(1));
+        assert!(Scalar::Boolean(false) < Scalar::Boolean(true));
+        assert!(Scalar::from(vec![0x01]) < Scalar::from(vec![0x01, 0x00]));
+        assert_eq!(
+            Scalar::Double(f64::NAN).partial_cmp(&Scalar::Double(1.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_partial_cmp_widened() {
+        assert_eq!(Scalar::Integer(5).partial_cmp(&Scalar::Long(5)), None);
+        assert_eq!(
+            Scalar::Integer(5).compare_widened(&Scalar::Long(5)),
+            Some(Ordering::Equal)
+        );
+        let widened = |a: Scalar, b: Scalar| a.compare_widened(&b);
+        assert_eq!(
+            widened(Scalar::Long(i64::MAX), Scalar::Integer(i32::MAX)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            widened(Scalar::Byte(-1), Scalar::Short(0)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            widened(Scalar::Integer(1), Scalar::Double(1.5)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            widened(Scalar::Float(2.5), Scalar::Long(2)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            widened(Scalar::from("a"), Scalar::from("b")),
+            Some(Ordering::Less)
+        );
+        assert_eq!(widened(Scalar::Date(5), Scalar::Integer(5)), None);
+    }
+
+    #[test]
+    fn test_partial_cmp_consistent_with_eq() {
+        let values = [
+            Scalar::Integer(5),
+            Scalar::Long(5),
+            Scalar::Short(5),
+            Scalar::Byte(5),
+            Scalar::Float(5.0),
+            Scalar::Double(5.0),
+            Scalar::Date(5),
+            Scalar::Timestamp(5),
+            Scalar::TimestampNtz(5),
+            Scalar::from("5"),
+            Scalar::Boolean(true),
+            Scalar::from(vec![5]),
+        ];
+        for a in &values {
+            for b in &values {
+                assert_eq!(
+                    a.partial_cmp(b) == Some(Ordering::Equal),
+                    a == b,
+                    "{a:?} vs {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_from_string() {
+        let parse = |raw, primitive| Scalar::try_from_string(raw, &DataType::Primitive(primitive));
+        assert_eq!(
+            parse("2021-03-01", PrimitiveType::Date).unwrap(),
+            Scalar::Date(18687)
+        );
+        assert_eq!(
+            parse("1969-12-31 23:59:59.5", PrimitiveType::Timestamp).unwrap(),
+            Scalar::Timestamp(-500_000)
+        );
+        assert_eq!(
+            parse("2021-03-01T00:00:01", PrimitiveType::Timestamp).unwrap(),
+            Scalar::Timestamp(18687 * 86_400_000_000 + 1_000_000)
+        );
+        assert_eq!(parse("-5", PrimitiveType::Long).unwrap(), Scalar::Long(-5));
+        assert_eq!(
+            parse("TRUE", PrimitiveType::Boolean).unwrap(),
+            Scalar::Boolean(true)
+        );
+        assert!(parse("1.5.0", PrimitiveType::Double).is_err());
+        assert!(parse("300", PrimitiveType::Byte).is_err());
+        assert!(parse("2021-03-01 24:00:00", PrimitiveType::Timestamp).is_err());
+        let err = parse("1.5", PrimitiveType::Decimal(2, 1)).unwrap_err();
+        assert!(err.to_string().contains("decimal"), "{err}");
+
+        assert_eq!(
+            parse("2020-02-29", PrimitiveType::Date).unwrap(),
+            Scalar::Date(18321)
+        );
+        for invalid in [
+            "2021-02-29",
+            "2021-02-31",
+            "1900-02-29",
+            "2021-04-31",
+            "2021-1-+1",
+        ] {
+            assert!(parse(invalid, PrimitiveType::Date).is_err(), "{invalid}");
+        }
+        for invalid in [
+            "2021-03-01 -1:00:00",
+            "2021-03-01 00:-0:00",
+            "2021-03-01 00:00:60",
+            "2021-03-01 00:00:00.-1",
+            "2021-02-30 00:00:00",
+        ] {
+            assert!(
+                parse(invalid, PrimitiveType::Timestamp).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_timestamp_ntz() {
+        let ts = Scalar::try_from_string(
+            "2021-03-01 00:00:01",
+            &DataType::Primitive(PrimitiveType::TimestampNtz),
+        )
+        .unwrap();
+        assert_eq!(ts, Scalar::TimestampNtz(18687 * 86_400_000_000 + 1_000_000));
+        assert_eq!(
+            ts.data_type(),
+            DataType::Primitive(PrimitiveType::TimestampNtz)
+        );
+
+        let array = ts.to_array(2).unwrap();
+        assert_eq!(
+            array.data_type(),
+            &ArrowDataType::Timestamp(arrow_schema::TimeUnit::Microsecond, None)
+        );
+        assert_eq!(
+            Scalar::TimestampNtz(1).partial_cmp(&Scalar::Timestamp(1)),
+            None
+        );
+    }
+}
@@ -48,6 +48,7 @@ use futures::stream::{BoxStream, Stream};
 use url::Url;
 
 use self::schema::SchemaRef;
+use self::snapshot::LogCache;
 
 pub mod actions;
 pub mod error;
@@ -57,10 +58,11 @@ pub mod scan;
 pub mod schema;
 pub mod snapshot;
 pub mod table;
+pub mod table_changes;
 
 pub use actions::{types::*, ActionType};
 pub use error::{DeltaResult, Error};
-pub use expressions::Expression;
+pub use expressions::{Expression, Scalar};
 pub use table::Table;
 
 #[cfg(feature = "default-client")]
@@ -82,7 +84,7 @@ pub type FileDataReadResultStream = Pin<Box<dyn Stream<Item = DeltaResult<Record
 pub struct FileMeta {
     /// The fully qualified path to the object
     pub location: Url,
-    /// The last modified time
+    /// The last modified time, in milliseconds since the Unix epoch
     pub last_modified: i64,
     /// The size in bytes of the object
     pub size: usize,
@@ -239,4 +241,10 @@ pub trait TableClient {
     fn get_parquet_handler(
         &self,
     ) -> Arc<dyn ParquetHandler<FileReadContext = Self::ParquetReadContext>>;
+
+    /// Get the connector provided [`LogCache`], if log metadata should be shared between
+    /// snapshots.
+    fn get_log_cache(&self) -> Option<Arc<LogCache>> {
+        None
+    }
 }
@@ -11,7 +11,9 @@ const DELIMITER: &str = "/";
 
 lazy_static! {
     static ref CHECKPOINT_FILE_PATTERN: Regex =
-        Regex::new(r#"\d+\.checkpoint(\.\d+\.\d+)?\.parquet"#).unwrap();
+        Regex::new(r#"\d+\.checkpoint(\.(\d+)\.(\d+))?\.parquet"#).unwrap();
+    static ref V2_CHECKPOINT_FILE_PATTERN: Regex =
+        Regex::new(r#"\d+\.checkpoint\.[0-9a-fA-F-]{36}\.(json|parquet)"#).unwrap();
     static ref DELTA_FILE_PATTERN: Regex = Regex::new(r#"\d+\.json"#).unwrap();
 }
 
@@ -49,12 +51,31 @@ impl<'a> LogPath<'a> {
         self.filename()
             .map(|name| CHECKPOINT_FILE_PATTERN.captures(name).is_some())
             .unwrap_or(false)
+            || self.is_v2_checkpoint_file()
+    }
+
+    /// The part number and the total number of parts of a multi-part checkpoint file, e.g.
+    /// `(1, 2)` for `00000000000000000010.checkpoint.0000000001.0000000002.parquet`.
+    pub(crate) fn checkpoint_part(&self) -> Option<(u32, u32)> {
+        let captures = CHECKPOINT_FILE_PATTERN.captures(self.filename()?)?;
+        let part = captures.get(2)?.as_str().parse().ok()?;
+        let num_parts = captures.get(3)?.as_str().parse().ok()?;
+        Some((part, num_parts))
+    }
+
+    /// Returns true for uuid named V2 checkpoint files, which may reference sidecar files.
+    pub(crate) fn is_v2_checkpoint_file(&self) -> bool {
+        self.filename()
+            .map(|name| V2_CHECKPOINT_FILE_PATTERN.captures(name).is_some())
+            .unwrap_or(false)
     }
 
     pub(crate) fn is_commit_file(&self) -> bool {
         self.filename()
             .map(|name| DELTA_FILE_PATTERN.captures(name).is_some())
             .unwrap_or(false)
+            // json V2 checkpoints look like commits
+            && !self.is_v2_checkpoint_file()
     }
 
     /// Parse the version number assuming a commit json or checkpoint parquet file
@@ -121,5 +142,25 @@ mod tests {
         assert!(!log_path.is_commit_file());
         assert!(log_path.is_checkpoint_file());
         assert_eq!(log_path.commit_version(), Some(2));
+        assert!(!log_path.is_v2_checkpoint_file());
+        assert_eq!(log_path.checkpoint_part(), None);
+
+        let log_path = log_path
+            .child("00000000000000000002.checkpoint.0000000001.0000000002.parquet")
+            .unwrap();
+        let log_path = LogPath(&log_path);
+        assert!(log_path.is_checkpoint_file());
+        assert!(!log_path.is_commit_file());
+        assert_eq!(log_path.commit_version(), Some(2));
+        assert_eq!(log_path.checkpoint_part(), Some((1, 2)));
+
+        let log_path = log_path
+            .child("00000000000000000002.checkpoint.80a083e8-7026-4e79-81be-64bd76c43a11.json")
+            .unwrap();
+        let log_path = LogPath(&log_path);
+        assert!(log_path.is_checkpoint_file());
+        assert!(log_path.is_v2_checkpoint_file());
+        assert!(!log_path.is_commit_file());
+        assert_eq!(log_path.commit_version(), Some(2));
     }
 }
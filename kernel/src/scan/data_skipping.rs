@@ -1,21 +1,270 @@
+use std::collections::{HashMap, HashSet};
 use std::io::BufReader;
 use std::sync::Arc;
 
-use arrow_arith::boolean::{is_not_null, not};
-use arrow_array::{new_null_array, RecordBatch, StringArray, StructArray};
+use arrow_arith::boolean::{and, is_not_null, is_null, or, or_kleene};
+use arrow_array::{new_null_array, Array, BooleanArray, RecordBatch, StringArray, StructArray};
 use arrow_json::ReaderBuilder;
-use arrow_schema::{DataType, Field, Schema};
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef};
 use arrow_select::concat::concat_batches;
 use arrow_select::filter::filter_record_batch;
-use arrow_select::nullif::nullif;
+use itertools::Itertools;
 use tracing::debug;
 
 use crate::error::{DeltaResult, Error};
-use crate::scan::Expression;
+use crate::expressions::{
+    preserves_order, stats_type, widen_string_maxima, ArrowExpressionCompiler, CompiledExpression,
+};
+use crate::scan::{Expression, MissingStatsPolicy, SkippingApplicability};
+use crate::schema::{DataType as DeltaDataType, Schema as DeltaSchema};
 
-pub(crate) fn data_skipping_filter(
-    actions: RecordBatch,
+/// Table property listing the columns for which statistics are collected.
+const STATS_COLUMNS_KEY: &str = "delta.dataSkippingStatsColumns";
+/// Table property limiting statistics to the first `n` columns of the schema.
+const NUM_INDEXED_COLS_KEY: &str = "delta.dataSkippingNumIndexedCols";
+const DEFAULT_NUM_INDEXED_COLS: usize = 32;
+
+/// Determine the leaf columns for which writers collect file statistics, as dot separated paths.
+pub(crate) fn indexed_columns(
+    schema: &DeltaSchema,
+    configuration: &HashMap<String, Option<String>>,
+) -> HashSet<String> {
+    stats_columns(schema, configuration).into_iter().collect()
+}
+
+/// The leaf columns for which writers collect file statistics as dot separated paths, in the
+/// order of the `delta.dataSkippingStatsColumns` property if set, or else the first
+/// `delta.dataSkippingNumIndexedCols` leaf columns of the schema in schema order.
+pub(crate) fn stats_columns(
+    schema: &DeltaSchema,
+    configuration: &HashMap<String, Option<String>>,
+) -> Vec<String> {
+    if let Some(Some(columns)) = configuration.get(STATS_COLUMNS_KEY) {
+        return columns
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unique()
+            .collect();
+    }
+    let num_indexed = match configuration.get(NUM_INDEXED_COLS_KEY) {
+        Some(Some(value)) => match value.parse::<i64>() {
+            // negative values denote collecting statistics for all columns
+            Ok(n) if n < 0 => usize::MAX,
+            Ok(n) => n as usize,
+            Err(_) => DEFAULT_NUM_INDEXED_COLS,
+        },
+        _ => DEFAULT_NUM_INDEXED_COLS,
+    };
+    let mut leaves = Vec::new();
+    leaf_paths(schema, "", &mut leaves);
+    leaves.truncate(num_indexed);
+    leaves
+}
+
+/// Collect the dot separated paths of the leaf columns of `schema`, i.e. of all fields that are
+/// not structs, in schema order.
+fn leaf_paths(schema: &DeltaSchema, prefix: &str, leaves: &mut Vec<String>) {
+    for field in schema.fields.iter() {
+        let path = format!("{prefix}{}", field.name);
+        match &field.data_type {
+            DeltaDataType::Struct(nested) => leaf_paths(nested, &format!("{path}."), leaves),
+            _ => leaves.push(path),
+        }
+    }
+}
+
+/// Build the schema to parse the file statistics needed to evaluate `predicate` with.
+///
+/// Columns may reference nested fields with dot separated paths. The schema only contains the
+/// referenced leaf fields that have statistics, typed according to the table schema, so wide
+/// nested statistics are not parsed in full. Returns `None` if no referenced column has
+/// statistics.
+pub(crate) fn stats_schema(
     predicate: &Expression,
+    table_schema: &DeltaSchema,
+    indexed_columns: &HashSet<String>,
+) -> DeltaResult<Option<SchemaRef>> {
+    let mut leaves = Vec::new();
+    for name in predicate.columns() {
+        if !indexed_columns.contains(name.as_str()) {
+            continue;
+        }
+        let path = name.split('.').collect::<Vec<_>>();
+        let Some(field) = table_schema.resolve_path(&path) else {
+            continue;
+        };
+        // statistics are only collected for primitive columns
+        if let DeltaDataType::Primitive(_) = field.data_type {
+            insert_leaf(&mut leaves, &path, DataType::try_from(&field.data_type)?);
+        }
+    }
+    if leaves.is_empty() {
+        return Ok(None);
+    }
+    let fields = to_fields(leaves);
+    Ok(Some(Arc::new(Schema::new(vec![
+        Field::new("minValues", DataType::Struct(fields.clone()), true),
+        Field::new("maxValues", DataType::Struct(fields), true),
+    ]))))
+}
+
+/// Node of the tree of referenced stats fields.
+enum StatsNode {
+    Leaf(DataType),
+    Struct(Vec<(String, StatsNode)>),
+}
+
+fn insert_leaf(nodes: &mut Vec<(String, StatsNode)>, path: &[&str], data_type: DataType) {
+    let Some((name, rest)) = path.split_first() else {
+        return;
+    };
+    let idx = match nodes.iter().position(|(node, _)| node == name) {
+        Some(idx) => idx,
+        None => {
+            let node = match rest.is_empty() {
+                true => StatsNode::Leaf(data_type.clone()),
+                false => StatsNode::Struct(Vec::new()),
+            };
+            nodes.push((name.to_string(), node));
+            nodes.len() - 1
+        }
+    };
+    if let StatsNode::Struct(children) = &mut nodes[idx].1 {
+        insert_leaf(children, rest, data_type);
+    }
+}
+
+fn to_fields(nodes: Vec<(String, StatsNode)>) -> Fields {
+    nodes
+        .into_iter()
+        .map(|(name, node)| match node {
+            StatsNode::Leaf(data_type) => Field::new(name, data_type, true),
+            StatsNode::Struct(children) => {
+                Field::new(name, DataType::Struct(to_fields(children)), true)
+            }
+        })
+        .collect()
+}
+
+/// Returns true if `stats_schema` contains statistics for the column `name`.
+fn has_stats(stats_schema: &Schema, name: &str) -> bool {
+    stats_type(stats_schema, name).is_some()
+}
+
+/// Whether an operand of a comparison can be bounded by the statistics, and if so whether it
+/// references a column. Casts of columns are only usable if they preserve the order of values.
+fn bounded_operand(operand: &Expression, stats_schema: Option<&Schema>) -> Option<bool> {
+    match operand {
+        Expression::Literal(_) => Some(false),
+        Expression::Column(name) => has_stats(stats_schema?, name).then_some(true),
+        Expression::Cast { expr, to } => match expr.as_ref() {
+            Expression::Column(name) => {
+                let from = stats_type(stats_schema?, name)?;
+                let to = DataType::try_from(to).ok()?;
+                preserves_order(from, &to).then_some(true)
+            }
+            expr => match bounded_operand(expr, stats_schema)? {
+                false => Some(false),
+                true => None,
+            },
+        },
+        _ => None,
+    }
+}
+
+/// Evaluates a predicate against the file statistics recorded in `add` actions.
+///
+/// The statistics schema is derived and the predicate compiled against it once when the filter
+/// is created, so the filter can be applied to each batch of actions read during log replay.
+#[derive(Clone)]
+pub(crate) struct DataSkippingFilter {
+    /// The predicate rewritten against the statistics, see [`Expression::stats_predicate`]
+    stats_predicate: Option<Expression>,
+    /// The `stats_predicate` compiled against the `stats_schema`
+    compiled_predicate: Option<CompiledExpression>,
+    /// Schema to parse the statistics referenced by the predicate with, see [`stats_schema`]
+    stats_schema: SchemaRef,
+    /// Report statistics that can not be parsed or evaluated as errors
+    strict: bool,
+    /// How to handle files without statistics
+    missing_stats: MissingStatsPolicy,
+}
+
+impl std::fmt::Debug for DataSkippingFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataSkippingFilter")
+            .field("stats_predicate", &self.stats_predicate)
+            .field("stats_schema", &self.stats_schema)
+            .field("strict", &self.strict)
+            .field("missing_stats", &self.missing_stats)
+            .finish()
+    }
+}
+
+impl DataSkippingFilter {
+    /// Create a new filter for the `predicate`.
+    ///
+    /// Returns `None` if no column referenced by the predicate has statistics.
+    pub(crate) fn try_new(
+        predicate: &Expression,
+        table_schema: &DeltaSchema,
+        indexed_columns: &HashSet<String>,
+        strict: bool,
+    ) -> DeltaResult<Option<Self>> {
+        let Some(stats_schema) = stats_schema(predicate, table_schema, indexed_columns)? else {
+            return Ok(None);
+        };
+        let stats_predicate = predicate.stats_predicate(&stats_schema);
+        let compiler = ArrowExpressionCompiler::new(stats_schema.clone());
+        let compiled_predicate = match stats_predicate.as_ref().map(|p| compiler.compile(p)) {
+            Some(Ok(compiled)) => Some(compiled),
+            Some(Err(err)) if strict => return Err(err),
+            // the predicate can not be used for skipping, so all files are kept
+            Some(Err(err)) => {
+                debug!("failed to compile the statistics predicate: {err}");
+                None
+            }
+            None => None,
+        };
+        Ok(Some(Self {
+            stats_predicate,
+            compiled_predicate,
+            stats_schema,
+            strict,
+            missing_stats: MissingStatsPolicy::default(),
+        }))
+    }
+
+    /// Set how files without statistics are handled, see [`MissingStatsPolicy`].
+    pub(crate) fn with_missing_stats_policy(mut self, policy: MissingStatsPolicy) -> Self {
+        self.missing_stats = policy;
+        self
+    }
+
+    /// Drop the add actions which cannot contain rows matching the predicate given their stats.
+    ///
+    /// Parts of the predicate referencing columns without statistics are not used for skipping.
+    /// Files without statistics are kept unless the [`MissingStatsPolicy`] is `Error`. In strict
+    /// mode statistics that can not be parsed or evaluated are reported as errors, otherwise the
+    /// affected files are kept.
+    pub(crate) fn apply(&self, actions: &RecordBatch) -> DeltaResult<RecordBatch> {
+        data_skipping_filter(
+            actions,
+            self.compiled_predicate.as_ref(),
+            &self.stats_schema,
+            self.strict,
+            self.missing_stats,
+        )
+    }
+}
+
+fn data_skipping_filter(
+    actions: &RecordBatch,
+    stats_predicate: Option<&CompiledExpression>,
+    stats_schema: &SchemaRef,
+    strict: bool,
+    missing_stats: MissingStatsPolicy,
 ) -> DeltaResult<RecordBatch> {
     let adds = actions
         .column_by_name("add")
@@ -35,33 +284,64 @@ pub(crate) fn data_skipping_filter(
         ))?;
     // parse each row as json using the stats schema from data skipping filter
     // HACK see https://github.com/apache/arrow/issues/33662
-    let data_fields: Vec<_> = predicate
-        .columns()
-        .iter()
-        .map(|name| Field::new(name, DataType::Int32, true))
-        .collect();
-    let stats_schema = Schema::new(vec![
-        Field::new(
-            "minValues",
-            DataType::Struct(data_fields.clone().into()),
-            true,
-        ),
-        Field::new("maxValues", DataType::Struct(data_fields.into()), true),
-    ]);
-    let parsed = concat_batches(
-        &stats_schema.into(),
-        stats
-            .iter()
-            .map(hack_parse)
-            .collect::<Result<Vec<_>, _>>()?
-            .iter(),
-    )?;
+    let paths = adds
+        .column_by_name("path")
+        .and_then(|paths| paths.as_any().downcast_ref::<StringArray>());
+    let path = |idx| paths.map(|paths| paths.value(idx)).unwrap_or_default();
+    let mut batches = Vec::with_capacity(stats.len());
+    let mut unusable = Vec::with_capacity(stats.len());
+    for (idx, json_string) in stats.iter().enumerate() {
+        match json_string.map(|json_string| hack_parse(stats_schema, json_string)) {
+            Some(Ok(batch)) => {
+                batches.push(batch);
+                unusable.push(false);
+                continue;
+            }
+            Some(Err(err)) if strict => return Err(err),
+            // e.g. invalid escape sequences, the file is kept as if it had no stats
+            Some(Err(err)) => debug!("failed to parse statistics of file {}: {err}", path(idx)),
+            // rows of other actions have no stats either
+            None if missing_stats == MissingStatsPolicy::Error && adds.is_valid(idx) => {
+                return Err(Error::MissingStatistics(path(idx).to_string()));
+            }
+            None => (),
+        }
+        batches.push(null_stats(stats_schema)?);
+        unusable.push(true);
+    }
+    let parsed = concat_batches(stats_schema, batches.iter())?;
 
-    let skipping_vector = predicate.construct_metadata_filters(parsed)?;
-    let skipping_vector = &is_not_null(&nullif(&skipping_vector, &not(&skipping_vector)?)?)?;
+    let filter = match stats_predicate.map(|predicate| evaluate_stats(predicate, &parsed)) {
+        Some(Ok(filter)) => Some(filter),
+        Some(Err(err)) if strict => return Err(err),
+        Some(Err(err)) => {
+            debug!("failed to evaluate file statistics: {err}");
+            None
+        }
+        None => None,
+    };
+    let Some(skipping_vector) = filter else {
+        // the predicate can not be used for skipping, only drop rows without file actions
+        let keep = match actions.column_by_name("remove") {
+            Some(removes) => or(&is_not_null(adds)?, &is_not_null(removes)?)?,
+            None => is_not_null(adds)?,
+        };
+        return Ok(filter_record_batch(actions, &keep)?);
+    };
+    // the stats of a file may lack the referenced columns, e.g. if they were added to the
+    // table later on, which makes the comparisons null. Such files may contain matches.
+    let skipping_vector = or_kleene(&skipping_vector, &is_null(&skipping_vector)?)?;
+    // files whose statistics can not be used are kept
+    let unusable = and(&BooleanArray::from(unusable), &is_not_null(adds)?)?;
+    let skipping_vector = or(&skipping_vector, &unusable)?;
+    // data skipping only applies to add actions, removes must always be retained for log replay
+    let skipping_vector = &match actions.column_by_name("remove") {
+        Some(removes) => or(&is_not_null(removes)?, &skipping_vector)?,
+        None => skipping_vector,
+    };
 
     let before_count = actions.num_rows();
-    let after = filter_record_batch(&actions, skipping_vector)?;
+    let after = filter_record_batch(actions, skipping_vector)?;
     debug!(
         "number of actions before/after data skipping: {before_count} / {}",
         after.num_rows()
@@ -69,37 +349,373 @@ pub(crate) fn data_skipping_filter(
     Ok(after)
 }
 
-fn hack_parse(json_string: Option<&str>) -> DeltaResult<RecordBatch> {
-    let data_fields = vec![Field::new("ids", DataType::Int32, true)];
-    let stats_schema = Schema::new(vec![
-        Field::new(
-            "minValues",
-            DataType::Struct(data_fields.clone().into()),
-            true,
-        ),
-        Field::new(
-            "maxValues",
-            DataType::Struct(data_fields.clone().into()),
+/// Evaluate the compiled `stats_predicate` on the parsed statistics of the files.
+fn evaluate_stats(
+    stats_predicate: &CompiledExpression,
+    stats: &RecordBatch,
+) -> DeltaResult<BooleanArray> {
+    let stats = widen_string_maxima(stats)?;
+    stats_predicate(&stats)?
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .cloned()
+        .ok_or(Error::UnexpectedColumnType(
+            "Expected type 'BooleanArray'.".into(),
+        ))
+}
+
+/// Determine to what extent file statistics can be used to skip files for `predicate`.
+pub(crate) fn skipping_applicability(
+    predicate: &Expression,
+    stats_schema: Option<&Schema>,
+) -> SkippingApplicability {
+    use SkippingApplicability::*;
+    let applicability = |expression| skipping_applicability(expression, stats_schema);
+    match predicate {
+        Expression::And(left, right) => match (applicability(left), applicability(right)) {
+            (Full, Full) => Full,
+            (NotApplicable, NotApplicable) => NotApplicable,
+            _ => Partial,
+        },
+        Expression::Or(left, right) => match (applicability(left), applicability(right)) {
+            (Full, Full) => Full,
+            (NotApplicable, _) | (_, NotApplicable) => NotApplicable,
+            _ => Partial,
+        },
+        Expression::LessThan(left, right)
+        | Expression::GreaterThan(left, right)
+        | Expression::Equal(left, right)
+        | Expression::NotEqual(left, right) => {
+            match (
+                bounded_operand(left, stats_schema),
+                bounded_operand(right, stats_schema),
+            ) {
+                (Some(left), Some(right)) if left || right => Full,
+                _ => NotApplicable,
+            }
+        }
+        Expression::Literal(_) | Expression::Column(_) | Expression::Cast { .. } => NotApplicable,
+    }
+}
+
+fn hack_parse(stats_schema: &SchemaRef, json_string: &str) -> DeltaResult<RecordBatch> {
+    ReaderBuilder::new(stats_schema.clone())
+        .build(BufReader::new(json_string.as_bytes()))?
+        .next()
+        .transpose()?
+        .ok_or(Error::MissingData("Expected data".into()))
+}
+
+/// A single row of statistics with all values null.
+fn null_stats(stats_schema: &SchemaRef) -> DeltaResult<RecordBatch> {
+    Ok(RecordBatch::try_new(
+        stats_schema.clone(),
+        stats_schema
+            .fields()
+            .iter()
+            .map(|field| new_null_array(field.data_type(), 1))
+            .collect(),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::ArrayRef;
+
+    use super::*;
+    use crate::schema::{PrimitiveType, StructField, StructType};
+
+    #[test]
+    fn test_indexed_columns() {
+        let schema = DeltaSchema::new(
+            ["a", "b", "c"]
+                .into_iter()
+                .map(|name| {
+                    StructField::new(name, DeltaDataType::Primitive(PrimitiveType::Long), true)
+                })
+                .collect(),
+        );
+        let config = |key: &str, value: &str| HashMap::from([(key.into(), Some(value.into()))]);
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+        assert_eq!(
+            indexed_columns(&schema, &HashMap::new()),
+            names(&["a", "b", "c"])
+        );
+        assert_eq!(
+            indexed_columns(&schema, &config(NUM_INDEXED_COLS_KEY, "2")),
+            names(&["a", "b"])
+        );
+        assert_eq!(
+            indexed_columns(&schema, &config(NUM_INDEXED_COLS_KEY, "-1")),
+            names(&["a", "b", "c"])
+        );
+        assert_eq!(
+            indexed_columns(&schema, &config(STATS_COLUMNS_KEY, "c, a")),
+            names(&["a", "c"])
+        );
+    }
+
+    #[test]
+    fn test_nested_indexed_columns() {
+        let long = || DeltaDataType::Primitive(PrimitiveType::Long);
+        let nested = StructType::new(vec![
+            StructField::new("x", long(), true),
+            StructField::new("y", long(), true),
+        ]);
+        let schema = DeltaSchema::new(vec![
+            StructField::new("a", long(), true),
+            StructField::new("s", DeltaDataType::Struct(Box::new(nested)), true),
+            StructField::new("b", long(), true),
+        ]);
+        let config = |key: &str, value: &str| HashMap::from([(key.into(), Some(value.into()))]);
+
+        // leaf columns are counted, so the struct does not index the columns after it
+        assert_eq!(
+            stats_columns(&schema, &config(NUM_INDEXED_COLS_KEY, "2")),
+            vec!["a", "s.x"]
+        );
+        assert_eq!(
+            stats_columns(&schema, &HashMap::new()),
+            vec!["a", "s.x", "s.y", "b"]
+        );
+
+        // configured columns are matched against the full paths of the leaves
+        let has_stats_for = |configured: &str, name: &str| {
+            let indexed = indexed_columns(&schema, &config(STATS_COLUMNS_KEY, configured));
+            stats_schema(&column_gt(name, 1), &schema, &indexed)
+                .unwrap()
+                .is_some()
+        };
+        assert!(has_stats_for("s.x, b", "s.x"));
+        assert!(!has_stats_for("s.x, b", "s.y"));
+        assert!(!has_stats_for("s", "s.x"));
+        assert!(!has_stats_for("s.x", "b"));
+    }
+
+    fn nested_schema() -> DeltaSchema {
+        let long = || DeltaDataType::Primitive(PrimitiveType::Long);
+        let wide = StructType::new(
+            (0..200)
+                .map(|i| StructField::new(format!("c{i}"), long(), true))
+                .collect(),
+        );
+        DeltaSchema::new(vec![
+            StructField::new("id", long(), true),
+            StructField::new("wide", DeltaDataType::Struct(Box::new(wide)), true),
+        ])
+    }
+
+    fn column_gt(name: &str, value: i64) -> Expression {
+        Expression::GreaterThan(
+            Box::new(Expression::Column(name.into())),
+            Box::new(Expression::Literal(value.into())),
+        )
+    }
+
+    #[test]
+    fn test_nested_stats_schema() {
+        let schema = nested_schema();
+        let indexed = HashSet::from(["id", "wide.c7", "wide.c42"].map(String::from));
+        let predicate = Expression::And(
+            Box::new(column_gt("wide.c7", 1)),
+            Box::new(Expression::Or(
+                Box::new(column_gt("wide.c42", 1)),
+                Box::new(column_gt("wide.c7", 2)),
+            )),
+        );
+        let stats = stats_schema(&predicate, &schema, &indexed)
+            .unwrap()
+            .unwrap();
+
+        let leaf = |name: &str| Field::new(name, DataType::Int64, true);
+        let fields = Fields::from(vec![Field::new(
+            "wide",
+            DataType::Struct(vec![leaf("c7"), leaf("c42")].into()),
             true,
-        ),
-    ]);
-    match json_string {
-        Some(s) => Ok(ReaderBuilder::new(stats_schema.into())
-            .build(BufReader::new(s.as_bytes()))?
-            .collect::<Vec<_>>()
+        )]);
+        let expected = Schema::new(vec![
+            Field::new("minValues", DataType::Struct(fields.clone()), true),
+            Field::new("maxValues", DataType::Struct(fields), true),
+        ]);
+        assert_eq!(stats.as_ref(), &expected);
+        assert!(has_stats(&stats, "wide.c42"));
+        assert!(!has_stats(&stats, "wide"));
+        assert!(!has_stats(&stats, "wide.c0"));
+
+        // neither unknown, non-leaf nor unindexed columns get statistics
+        for name in ["wide.missing", "wide", "id.c7"] {
+            assert!(stats_schema(&column_gt(name, 1), &schema, &indexed)
+                .unwrap()
+                .is_none());
+        }
+        let indexed = HashSet::from(["id".to_string()]);
+        assert!(stats_schema(&column_gt("wide.c7", 1), &schema, &indexed)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_nested_data_skipping() {
+        let schema = nested_schema();
+        let indexed = HashSet::from(["wide.c7".to_string()]);
+        let predicate = column_gt("wide.c7", 10);
+        let filter = DataSkippingFilter::try_new(&predicate, &schema, &indexed, false)
+            .unwrap()
+            .unwrap();
+
+        let stats = StringArray::from(vec![
+            Some(r#"{"minValues":{"wide":{"c7":1,"c8":1}},"maxValues":{"wide":{"c7":5,"c8":50}}}"#),
+            Some(r#"{"minValues":{"wide":{"c7":1}},"maxValues":{"wide":{"c7":50}}}"#),
+        ]);
+        let stats: ArrayRef = Arc::new(stats);
+        let add: ArrayRef = Arc::new(StructArray::from(vec![(
+            Arc::new(Field::new("stats", DataType::Utf8, true)),
+            stats,
+        )]));
+        let actions = RecordBatch::try_from_iter(vec![("add", add)]).unwrap();
+
+        let result = filter.apply(&actions).unwrap();
+        assert_eq!(result.num_rows(), 1);
+        let add = result
+            .column(0)
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .unwrap();
+        let stats = add
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert!(stats.value(0).contains(r#""c7":50"#));
+    }
+
+    #[test]
+    fn test_strict_data_skipping() {
+        let schema = nested_schema();
+        let indexed = HashSet::from(["wide.c7".to_string()]);
+        let predicate = column_gt("wide.c7", 10);
+        let filter = |strict| {
+            DataSkippingFilter::try_new(&predicate, &schema, &indexed, strict)
+                .unwrap()
+                .unwrap()
+        };
+
+        let stats = StringArray::from(vec![
+            Some(r#"{"minValues":{"wide":{"c7":1}},"maxValues":{"wide":{"c7":5}}}"#),
+            Some("{not json"),
+            None,
+        ]);
+        let stats: ArrayRef = Arc::new(stats);
+        let add: ArrayRef = Arc::new(StructArray::from(vec![(
+            Arc::new(Field::new("stats", DataType::Utf8, true)),
+            stats,
+        )]));
+        let actions = RecordBatch::try_from_iter(vec![("add", add)]).unwrap();
+
+        // files whose stats cannot be used are kept
+        let result = filter(false).apply(&actions).unwrap();
+        assert_eq!(result.num_rows(), 2);
+
+        assert!(filter(true).apply(&actions).is_err());
+    }
+
+    #[test]
+    fn test_missing_stats_policy() {
+        let schema = nested_schema();
+        let indexed = HashSet::from(["wide.c7".to_string()]);
+        let predicate = column_gt("wide.c7", 10);
+        let filter = |policy| {
+            DataSkippingFilter::try_new(&predicate, &schema, &indexed, false)
+                .unwrap()
+                .unwrap()
+                .with_missing_stats_policy(policy)
+        };
+
+        let paths: ArrayRef = Arc::new(StringArray::from(vec!["a.parquet", "b.parquet"]));
+        let stats: ArrayRef = Arc::new(StringArray::from(vec![
+            Some(r#"{"minValues":{"wide":{"c7":1}},"maxValues":{"wide":{"c7":20}}}"#),
+            None,
+        ]));
+        let add: ArrayRef = Arc::new(StructArray::from(vec![
+            (Arc::new(Field::new("path", DataType::Utf8, false)), paths),
+            (Arc::new(Field::new("stats", DataType::Utf8, true)), stats),
+        ]));
+        let actions = RecordBatch::try_from_iter(vec![("add", add)]).unwrap();
+
+        let result = filter(MissingStatsPolicy::Keep).apply(&actions).unwrap();
+        assert_eq!(result.num_rows(), 2);
+
+        let result = filter(MissingStatsPolicy::Error).apply(&actions);
+        assert!(matches!(result, Err(Error::MissingStatistics(path)) if path == "b.parquet"));
+
+        // the policy only applies to files that could be skipped
+        let result = filter(MissingStatsPolicy::Error).apply(&actions.slice(0, 1));
+        assert_eq!(result.unwrap().num_rows(), 1);
+    }
+
+    #[test]
+    fn test_missing_stats_columns() {
+        let schema = nested_schema();
+        let indexed = HashSet::from(["wide.c7", "wide.c8"].map(String::from));
+        let predicate = Expression::And(
+            Box::new(column_gt("wide.c7", 10)),
+            Box::new(column_gt("wide.c8", 10)),
+        );
+        let filter = DataSkippingFilter::try_new(&predicate, &schema, &indexed, true)
+            .unwrap()
+            .unwrap();
+
+        // older files were written before c8 was added to the table
+        let stats = StringArray::from(vec![
+            Some(r#"{"minValues":{"wide":{"c7":1}},"maxValues":{"wide":{"c7":20}}}"#),
+            Some(r#"{"minValues":{"wide":{"c7":1}},"maxValues":{"wide":{"c7":5}}}"#),
+            Some(r#"{"minValues":{},"maxValues":{}}"#),
+            Some(r#"{"numRecords":3}"#),
+            Some(r#"{"minValues":{"wide":{"c7":1,"c8":1}},"maxValues":{"wide":{"c7":20,"c8":5}}}"#),
+        ]);
+        let stats: ArrayRef = Arc::new(stats);
+        let add: ArrayRef = Arc::new(StructArray::from(vec![(
+            Arc::new(Field::new("stats", DataType::Utf8, true)),
+            stats,
+        )]));
+        let actions = RecordBatch::try_from_iter(vec![("add", add)]).unwrap();
+
+        // only files whose known stats rule out a match are skipped
+        let result = filter.apply(&actions).unwrap();
+        assert_eq!(result.num_rows(), 3);
+    }
+
+    #[test]
+    fn test_incremental_data_skipping() {
+        let schema = nested_schema();
+        let indexed = HashSet::from(["wide.c7".to_string()]);
+        let filter =
+            DataSkippingFilter::try_new(&column_gt("wide.c7", 10), &schema, &indexed, false)
+                .unwrap()
+                .unwrap();
+
+        let stats = StringArray::from_iter((0..9).map(|i| {
+            Some(format!(
+                r#"{{"minValues":{{"wide":{{"c7":{i}}}}},"maxValues":{{"wide":{{"c7":{}}}}}}}"#,
+                i * 3
+            ))
+        }));
+        let stats: ArrayRef = Arc::new(stats);
+        let add: ArrayRef = Arc::new(StructArray::from(vec![(
+            Arc::new(Field::new("stats", DataType::Utf8, true)),
+            stats,
+        )]));
+        let actions = RecordBatch::try_from_iter(vec![("add", add)]).unwrap();
+
+        let expected = filter.apply(&actions).unwrap();
+        assert_eq!(expected.num_rows(), 5);
+
+        let survivors = [0, 3, 6]
             .into_iter()
-            .next()
-            .transpose()?
-            .ok_or(Error::MissingData("Expected data".into()))?),
-        None => Ok(RecordBatch::try_new(
-            stats_schema.into(),
-            vec![
-                Arc::new(new_null_array(
-                    &DataType::Struct(data_fields.clone().into()),
-                    1,
-                )),
-                Arc::new(new_null_array(&DataType::Struct(data_fields.into()), 1)),
-            ],
-        )?),
+            .map(|offset| filter.apply(&actions.slice(offset, 3)).unwrap())
+            .collect::<Vec<_>>();
+        let result = concat_batches(&actions.schema(), &survivors).unwrap();
+        assert_eq!(result, expected);
     }
 }
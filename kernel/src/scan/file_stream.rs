@@ -11,7 +11,8 @@ use futures::task::{Context, Poll};
 use roaring::RoaringTreemap;
 use url::Url;
 
-use super::data_skipping::data_skipping_filter;
+use super::data_skipping::DataSkippingFilter;
+use super::partition_skipping::PartitionSkippingFilter;
 use crate::actions::{parse_actions, Action, ActionType, Add};
 use crate::expressions::Expression;
 use crate::{DeltaResult, Error, FileSystemClient};
@@ -20,10 +21,16 @@ use crate::{DeltaResult, Error, FileSystemClient};
 pub struct LogReplayStream {
     stream: BoxStream<'static, DeltaResult<RecordBatch>>,
     predicate: Option<Expression>,
+    partition_filter: Option<PartitionSkippingFilter>,
+    data_filter: Option<DataSkippingFilter>,
     seen: HashSet<(String, Option<String>)>,
     // ages: HashMap<Version, HashSet<PathBuf>>
     fs_client: Arc<dyn FileSystemClient>,
     table_root: Url,
+    read_deletion_vectors: bool,
+    sort_by_path: bool,
+    // the files collected so far if sorting by path, `None` once they were yielded
+    sorted: Option<Vec<DataFile>>,
 }
 
 impl std::fmt::Debug for LogReplayStream {
@@ -39,17 +46,37 @@ impl LogReplayStream {
     pub(crate) fn new(
         stream: BoxStream<'static, DeltaResult<RecordBatch>>,
         predicate: Option<Expression>,
+        partition_filter: Option<PartitionSkippingFilter>,
+        data_filter: Option<DataSkippingFilter>,
         fs_client: Arc<dyn FileSystemClient>,
         table_root: Url,
     ) -> DeltaResult<Self> {
         Ok(Self {
             predicate,
+            partition_filter,
+            data_filter,
             stream,
             fs_client,
             table_root,
+            read_deletion_vectors: true,
+            sort_by_path: false,
+            sorted: Some(Vec::new()),
             seen: Default::default(),
         })
     }
+
+    /// Yield all files as a single batch sorted by their paths, once the log was replayed.
+    pub(crate) fn sorted_by_path(mut self) -> Self {
+        self.sort_by_path = true;
+        self
+    }
+
+    /// Do not read the deletion vectors of the files, the [`DataFile::dv`] of all files is
+    /// `None` and only their [`Add::deletion_vector`] describes them.
+    pub(crate) fn without_deletion_vectors(mut self) -> Self {
+        self.read_deletion_vectors = false;
+        self
+    }
 }
 
 /// A fallible future that resolves to a stream of [`RecordBatch`]
@@ -68,53 +95,83 @@ impl Stream for LogReplayStream {
         mut self: Pin<&mut Self>,
         ctx: &mut Context<'_>,
     ) -> Poll<Option<<Self as futures::Stream>::Item>> {
+        if !self.sort_by_path {
+            return self.poll_batch(ctx);
+        }
+        loop {
+            match self.poll_batch(ctx) {
+                Poll::Ready(Some(Ok(files))) => {
+                    if let Some(sorted) = self.sorted.as_mut() {
+                        sorted.extend(files);
+                    }
+                }
+                Poll::Ready(None) => {
+                    return Poll::Ready(self.sorted.take().map(|mut files| {
+                        files.sort_by(|a, b| a.add.path.cmp(&b.add.path));
+                        Ok(files)
+                    }));
+                }
+                other => return other,
+            }
+        }
+    }
+}
+
+impl LogReplayStream {
+    /// Replay the next batch of log actions, yielding the files that survive it.
+    fn poll_batch(&mut self, ctx: &mut Context<'_>) -> Poll<Option<DeltaResult<Vec<DataFile>>>> {
         let stream = Pin::new(&mut self.stream);
         match stream.poll_next(ctx) {
             futures::task::Poll::Ready(value) => match value {
+                // e.g. V2 checkpoints, whose add actions are stored in sidecar files
+                Some(Ok(actions)) if actions.column_by_name("add").is_none() => {
+                    futures::task::Poll::Ready(Some(Ok(Vec::new())))
+                }
                 Some(Ok(actions)) => {
-                    let skipped = if let Some(predicate) = &self.predicate {
-                        data_skipping_filter(actions, predicate)?
-                    } else {
-                        let predicate = filter_nulls(&actions)?;
-                        filter_record_batch(&actions, &predicate)?
+                    let skipped = match (&self.partition_filter, &self.data_filter) {
+                        // stats are of no use for predicates only referencing partition columns
+                        (None, Some(data_filter)) => data_filter.apply(&actions)?,
+                        _ => {
+                            let predicate = filter_nulls(&actions)?;
+                            filter_record_batch(&actions, &predicate)?
+                        }
                     };
-                    let filtered_actions =
-                        parse_actions(&skipped, &[ActionType::Remove, ActionType::Add])?
-                            .filter_map(|action| match action {
-                                Action::Add(add)
-                                    // TODO right now this may not work as expected if we have the same add
-                                    // file with different deletetion vectors in the log - i.e. additional
-                                    // rows were deleted in spearate op. Is this a case to consider?
-                                    if !self
-                                        .seen
-                                        .contains(&(add.path.clone(), add.dv_unique_id())) =>
-                                {
-                                    self.seen.insert((add.path.clone(), add.dv_unique_id()));
-                                    let dvv = add.deletion_vector.clone();
-                                    let dv = if let Some(dv_def) = dvv {
-                                        let dv_def = dv_def.clone();
-                                        let fut = dv_def.read(self.fs_client.clone(), self.table_root.clone()).unwrap();
-                                        Some(fut)
-                                    } else {
-                                        None
-                                    };
-                                    Some(DataFile {
-                                        add: add.clone(),
-                                        dv
-                                    })
-                                }
-                                Action::Add(add) => {
-                                    self.seen.insert((add.path.clone(), add.dv_unique_id()));
-                                    None
-                                }
-                                Action::Remove(remove) => {
-                                    self.seen
-                                        .insert((remove.path.clone(), remove.dv_unique_id()));
-                                    None
+                    let mut filtered_actions = Vec::new();
+                    for action in parse_actions(&skipped, &[ActionType::Remove, ActionType::Add])? {
+                        match action {
+                            // TODO right now this may not work as expected if we have the same add
+                            // file with different deletetion vectors in the log - i.e. additional
+                            // rows were deleted in spearate op. Is this a case to consider?
+                            Action::Add(add)
+                                if !self.seen.contains(&(add.path.clone(), add.dv_unique_id())) =>
+                            {
+                                self.seen.insert((add.path.clone(), add.dv_unique_id()));
+                                if let Some(filter) = &self.partition_filter {
+                                    if !filter.apply(&add)? {
+                                        continue;
+                                    }
                                 }
-                                _ => None,
-                            })
-                            .collect();
+                                let dv = match &add.deletion_vector {
+                                    Some(dv_def) if self.read_deletion_vectors => {
+                                        Some(dv_def.read(
+                                            self.fs_client.clone(),
+                                            self.table_root.clone(),
+                                        )?)
+                                    }
+                                    _ => None,
+                                };
+                                filtered_actions.push(DataFile { add, dv });
+                            }
+                            Action::Add(add) => {
+                                self.seen.insert((add.path.clone(), add.dv_unique_id()));
+                            }
+                            Action::Remove(remove) => {
+                                self.seen
+                                    .insert((remove.path.clone(), remove.dv_unique_id()));
+                            }
+                            _ => (),
+                        }
+                    }
                     futures::task::Poll::Ready(Some(Ok(filtered_actions)))
                 }
                 Some(Err(err)) => futures::task::Poll::Ready(Some(Err(err))),
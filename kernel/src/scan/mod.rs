@@ -1,30 +1,91 @@
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
-use arrow_array::{BooleanArray, RecordBatch};
-use arrow_schema::{Fields, Schema as ArrowSchema};
+use arrow_array::{
+    new_null_array, Array, ArrayRef, BooleanArray, Int64Array, RecordBatch, RecordBatchOptions,
+    StructArray,
+};
+use arrow_schema::{
+    DataType as ArrowDataType, Field as ArrowField, Fields, Schema as ArrowSchema,
+    SchemaRef as ArrowSchemaRef,
+};
 use arrow_select::concat::concat_batches;
 use arrow_select::filter::filter_record_batch;
-use futures::stream::{StreamExt, TryStreamExt};
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use itertools::Itertools;
+use roaring::RoaringTreemap;
 use url::Url;
 
-use self::file_stream::LogReplayStream;
-use crate::actions::ActionType;
-use crate::expressions::Expression;
-use crate::schema::{Schema, SchemaRef};
+pub(crate) use self::data_skipping::stats_columns;
+use self::data_skipping::DataSkippingFilter;
+use self::file_stream::{DataFile, LogReplayStream};
+use self::partition_skipping::{parse_partition_value, PartitionSkippingFilter};
+use crate::actions::{parse_actions, Action, ActionType, Add, Remove};
+use crate::expressions::{Expression, Scalar};
+use crate::path::LogPath;
+use crate::schema::{DataType, Schema, SchemaRef, StructField, StructType};
 use crate::snapshot::LogSegment;
-use crate::{DeltaResult, FileMeta, TableClient};
+use crate::table_changes::list_commit_files;
+use crate::{DeltaResult, Error, FileMeta, ParquetHandler, TableClient, Version};
+
+const COLUMN_MAPPING_MODE_KEY: &str = "delta.columnMapping.mode";
 
 mod data_skipping;
 pub mod file_stream;
+mod partition_skipping;
+
+/// To what extent the predicate of a [`Scan`] can be used to skip reading files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkippingApplicability {
+    /// The whole predicate is used to skip files.
+    Full,
+    /// Only some parts of the predicate are used, e.g. one leg of a conjunction.
+    Partial,
+    /// The predicate can not be used, or there is no predicate, so all files are read.
+    NotApplicable,
+}
+
+/// How a [`Scan`] with a predicate handles files without statistics, see
+/// [`ScanBuilder::with_missing_stats_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingStatsPolicy {
+    /// Keep the files, as they may contain rows matching the predicate.
+    #[default]
+    Keep,
+    /// Return [`Error::MissingStatistics`] if the predicate could be used to skip the file.
+    Error,
+}
+
+/// The files that changed between two versions of a table, see [`Scan::incremental`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IncrementalFiles {
+    /// Files added in the version range that are still part of the table at its end, or were
+    /// only rewritten without changing the data.
+    pub adds: Vec<Add>,
+    /// Files that were part of the table before the version range and were removed in it.
+    pub removes: Vec<Remove>,
+}
 
 // TODO projection: something like fn select(self, columns: &[&str])
 /// Builder to scan a snapshot of a table.
 pub struct ScanBuilder<JRC: Send, PRC: Send> {
     table_root: Url,
     log_segment: LogSegment,
+    version: Version,
     snapshot_schema: SchemaRef,
+    partition_columns: Vec<String>,
+    indexed_columns: HashSet<String>,
     schema: Option<SchemaRef>,
     predicate: Option<Expression>,
+    batch_size: Option<usize>,
+    strict_skipping: bool,
+    missing_stats: MissingStatsPolicy,
+    column_mapping: bool,
+    row_index: Option<String>,
+    passthrough: bool,
+    exact_schema: bool,
+    deterministic_order: bool,
     table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
 }
 
@@ -42,15 +103,29 @@ impl<JRC: Send, PRC: Send + Sync> ScanBuilder<JRC, PRC> {
     pub(crate) fn new(
         table_root: Url,
         snapshot_schema: SchemaRef,
+        partition_columns: Vec<String>,
+        configuration: &HashMap<String, Option<String>>,
         log_segment: LogSegment,
+        version: Version,
         table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
     ) -> Self {
         Self {
             table_root,
+            version,
+            indexed_columns: data_skipping::indexed_columns(&snapshot_schema, configuration),
             snapshot_schema,
+            partition_columns,
             log_segment,
             schema: None,
             predicate: None,
+            batch_size: None,
+            strict_skipping: false,
+            missing_stats: MissingStatsPolicy::default(),
+            column_mapping: column_mapping_enabled(configuration),
+            row_index: None,
+            passthrough: false,
+            exact_schema: false,
+            deterministic_order: false,
             table_client,
         }
     }
@@ -76,28 +151,168 @@ impl<JRC: Send, PRC: Send + Sync> ScanBuilder<JRC, PRC> {
         self
     }
 
+    /// Use the conjunction of `predicates` as the predicate of the scan, see
+    /// [`ScanBuilder::with_predicate`].
+    ///
+    /// An empty list of predicates removes the predicate, so files are not skipped.
+    pub fn with_predicates(mut self, predicates: Vec<Expression>) -> Self {
+        self.predicate = Expression::and_all(predicates);
+        self
+    }
+
+    /// Number of rows in the batches returned by [`Scan::execute`].
+    ///
+    /// The batches read from the data files are split and combined to contain `rows` rows
+    /// each, only the last batch may be smaller. By default the batches are returned as read.
+    pub fn with_batch_size(mut self, rows: NonZeroUsize) -> Self {
+        self.batch_size = Some(rows.get());
+        self
+    }
+
+    /// Report file statistics that can not be parsed or evaluated for data skipping as errors.
+    ///
+    /// By default the files with unusable statistics are kept, as if the predicate could not
+    /// be used for skipping them.
+    pub fn with_strict_skipping(mut self, strict: bool) -> Self {
+        self.strict_skipping = strict;
+        self
+    }
+
+    /// Set how files without statistics are handled when skipping files with the predicate.
+    ///
+    /// By default such files are kept. Files are only checked if the predicate references
+    /// columns with statistics, see [`Scan::skipping_applicability`].
+    pub fn with_missing_stats_policy(mut self, policy: MissingStatsPolicy) -> Self {
+        self.missing_stats = policy;
+        self
+    }
+
+    /// Append a column named `column_name` with the index of each row within its data file.
+    ///
+    /// The index is the position of the row in the data file as written, rows removed by a
+    /// deletion vector are skipped without renumbering the remaining ones. Indices start at 0
+    /// for every file, so together with the path of the file they identify a row.
+    pub fn with_row_index(mut self, column_name: String) -> Self {
+        self.row_index = Some(column_name);
+        self
+    }
+
+    /// Return batches with exactly the [`Scan::logical_schema`] converted to arrow.
+    ///
+    /// By default columns read from the data files keep the field of the file, so the field
+    /// metadata, nullability and types of nested fields may differ from the table schema. With
+    /// `exact` set, columns are cast to the type of the table schema, nested fields are
+    /// reordered by name and the fields of the batches are taken from the table schema. Values
+    /// that can not be represented in the table schema, e.g. nulls in a non-nullable column,
+    /// are an error. A row index column is still appended after the columns of the schema.
+    pub fn with_exact_schema(mut self, exact: bool) -> Self {
+        self.exact_schema = exact;
+        self
+    }
+
+    /// List and read the files of the scan in the order of their paths, so the output is the
+    /// same on every run.
+    ///
+    /// By default files are yielded in log order while the log is replayed. To sort them, the
+    /// whole log has to be replayed and all files kept in memory before the first one is
+    /// yielded, which delays the first batch of [`Scan::execute_stream`] on large tables.
+    pub fn with_deterministic_order(mut self, deterministic: bool) -> Self {
+        self.deterministic_order = deterministic;
+        self
+    }
+
+    /// Only list the files of the scan, for engines handling the data files themselves.
+    ///
+    /// [`Scan::files`] of a passthrough scan yields the files with their full metadata, but no
+    /// deletion vectors are read, they are only described by [`Add::deletion_vector`]. Data
+    /// files are never opened, and [`Scan::execute`] and [`Scan::execute_stream`] return an
+    /// error.
+    pub fn passthrough(mut self) -> Self {
+        self.passthrough = true;
+        self
+    }
+
     /// Build the [`Scan`].
     ///
     /// This is lazy and performs no 'work' at this point. The [`Scan`] type itself can be used
     /// to fetch the files and associated metadata required to perform actual data reads.
-    pub fn build(self) -> Scan<JRC, PRC> {
+    ///
+    /// Returns [`Error::MissingColumn`] if the projected schema or the predicate reference a
+    /// column which is not part of the table schema.
+    pub fn build(self) -> DeltaResult<Scan<JRC, PRC>> {
         // if no schema is provided, use snapshot's entire schema (e.g. SELECT *)
         let schema = self.schema.unwrap_or_else(|| self.snapshot_schema.clone());
-        Scan {
+        let missing = schema
+            .fields
+            .iter()
+            .map(|field| field.name.clone())
+            .find(|name| self.snapshot_schema.field(name).is_none())
+            .or_else(|| {
+                self.predicate.as_ref().and_then(|predicate| {
+                    predicate.columns().into_iter().find(|name| {
+                        let path = name.split('.').collect::<Vec<_>>();
+                        self.snapshot_schema.resolve_path(&path).is_none()
+                    })
+                })
+            });
+        if let Some(name) = missing {
+            return Err(Error::MissingColumn(format!(
+                "Column '{}' not found in table schema, valid columns are: {}",
+                name,
+                self.snapshot_schema
+                    .fields
+                    .iter()
+                    .map(|field| &field.name)
+                    .join(", ")
+            )));
+        }
+        if let Some(name) = &self.row_index {
+            if schema.field(name).is_some() {
+                return Err(Error::Generic(format!(
+                    "Row index column '{}' conflicts with a column of the scan schema",
+                    name
+                )));
+            }
+        }
+        Ok(Scan {
             table_root: self.table_root,
             log_segment: self.log_segment,
+            version: self.version,
+            snapshot_schema: self.snapshot_schema,
+            partition_columns: self.partition_columns,
+            indexed_columns: self.indexed_columns,
             schema,
             predicate: self.predicate,
+            batch_size: self.batch_size,
+            strict_skipping: self.strict_skipping,
+            missing_stats: self.missing_stats,
+            column_mapping: self.column_mapping,
+            row_index: self.row_index,
+            passthrough: self.passthrough,
+            exact_schema: self.exact_schema,
+            deterministic_order: self.deterministic_order,
             table_client: self.table_client,
-        }
+        })
     }
 }
 
 pub struct Scan<JRC: Send, PRC: Send + Sync> {
     table_root: Url,
     log_segment: LogSegment,
+    version: Version,
+    snapshot_schema: SchemaRef,
+    partition_columns: Vec<String>,
+    indexed_columns: HashSet<String>,
     schema: SchemaRef,
     predicate: Option<Expression>,
+    batch_size: Option<usize>,
+    strict_skipping: bool,
+    missing_stats: MissingStatsPolicy,
+    column_mapping: bool,
+    row_index: Option<String>,
+    passthrough: bool,
+    exact_schema: bool,
+    deterministic_order: bool,
     table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
 }
 
@@ -117,16 +332,87 @@ impl<JRC: Send, PRC: Send + Sync + 'static> Scan<JRC, PRC> {
         &self.schema
     }
 
+    /// The schema of the batches returned by the scan, i.e. the projected columns with their
+    /// names in the table schema, including partition columns.
+    ///
+    /// This is the same schema as [`Scan::schema`].
+    pub fn logical_schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// The schema to read from the data files of the scan.
+    ///
+    /// Contains the projected columns that are stored in the data files, i.e. all but the
+    /// partition columns. If column mapping is enabled for the table, the fields, including
+    /// nested fields, carry their physical names.
+    pub fn physical_read_schema(&self) -> StructType {
+        physical_read_schema(&self.schema, &self.partition_columns, self.column_mapping)
+    }
+
     /// Get the predicate [`Expression`] of the scan.
     pub fn predicate(&self) -> &Option<Expression> {
         &self.predicate
     }
 
+    /// Report to what extent the predicate of this scan is used to skip files.
+    ///
+    /// Predicates only referencing partition columns are fully applied to the partition values
+    /// of the files, all other predicates are applied to the file statistics. Returns an error
+    /// if the statistics schema can not be derived, e.g. for columns of unsupported types.
+    pub fn skipping_applicability(&self) -> DeltaResult<SkippingApplicability> {
+        let Some(predicate) = &self.predicate else {
+            return Ok(SkippingApplicability::NotApplicable);
+        };
+        if PartitionSkippingFilter::try_new(
+            predicate,
+            &self.snapshot_schema,
+            &self.partition_columns,
+        )
+        .is_some()
+        {
+            return Ok(SkippingApplicability::Full);
+        }
+        let stats_schema = self.stats_schema()?;
+        Ok(data_skipping::skipping_applicability(
+            predicate,
+            stats_schema.as_deref(),
+        ))
+    }
+
+    fn stats_schema(&self) -> DeltaResult<Option<ArrowSchemaRef>> {
+        match &self.predicate {
+            Some(predicate) => {
+                data_skipping::stats_schema(predicate, &self.snapshot_schema, &self.indexed_columns)
+            }
+            None => Ok(None),
+        }
+    }
+
     /// This is the main method to 'materialize' the scan. It returns a `ScanFileBatchIterator`
     /// which yields record batches of scan files and their associated metadata. Rows of the scan
     /// files batches correspond to data reads, and the DeltaReader is used to materialize the scan
     /// files into actual table data.
+    ///
+    /// The log is replayed on every call, so `files` and [`Scan::execute`] can both be
+    /// called on the same [`Scan`]. If the [`TableClient`] provides a [`LogCache`], the
+    /// replayed actions are cached and the log is only read once per table version.
+    ///
+    /// The files of each batch of log actions are yielded as the log is replayed, in log order.
+    /// With [`ScanBuilder::with_deterministic_order`] the files are instead yielded as a single
+    /// batch sorted by their paths once the log was replayed.
+    ///
+    /// [`LogCache`]: crate::snapshot::LogCache
     pub fn files(&self) -> DeltaResult<LogReplayStream> {
+        let stream = self.replay()?;
+        Ok(match self.deterministic_order {
+            true => stream.sorted_by_path(),
+            false => stream,
+        })
+    }
+
+    /// Replay the log, yielding the files of each batch of log actions in log order, i.e. the
+    /// files of newer commits first, followed by those of the checkpoint.
+    fn replay(&self) -> DeltaResult<LogReplayStream> {
         // TODO use LogSegmentNEw replay ...
         // TODO create function to generate native schema
         let schema = ArrowSchema {
@@ -152,54 +438,566 @@ impl<JRC: Send, PRC: Send + Sync + 'static> Scan<JRC, PRC> {
             .read_json_files(commit_reads, schema.clone())?
             .chain(parquet_handler.read_parquet_files(checkpoint_reads, schema.clone())?)
             .boxed();
+        let stream = match self.table_client.get_log_cache() {
+            Some(cache) => cache.file_actions(&self.table_root, self.version, stream)?,
+            None => stream,
+        };
+
+        // predicates only referencing partition columns are evaluated against partition values
+        let partition_filter = self.predicate.as_ref().and_then(|predicate| {
+            PartitionSkippingFilter::try_new(
+                predicate,
+                &self.snapshot_schema,
+                &self.partition_columns,
+            )
+        });
+
+        let data_filter = match &self.predicate {
+            Some(predicate) => DataSkippingFilter::try_new(
+                predicate,
+                &self.snapshot_schema,
+                &self.indexed_columns,
+                self.strict_skipping,
+            )?
+            .map(|filter| filter.with_missing_stats_policy(self.missing_stats)),
+            None => None,
+        };
 
-        LogReplayStream::new(
+        let stream = LogReplayStream::new(
             stream,
             self.predicate.clone(),
+            partition_filter,
+            data_filter,
             self.table_client.get_file_system_client(),
             self.table_root.clone(),
-        )
+        )?;
+        Ok(match self.passthrough {
+            true => stream.without_deletion_vectors(),
+            false => stream,
+        })
     }
 
-    pub async fn execute(&self) -> DeltaResult<Vec<RecordBatch>> {
-        let parquet_handler = self.table_client.get_parquet_handler();
-        let mut stream = self.files()?.boxed();
-
-        let mut results = Vec::new();
-        while let Some(Ok(data)) = stream.next().await {
-            for file in data {
-                let meta = FileMeta {
-                    last_modified: file.add.modification_time,
-                    size: file.add.size as usize,
-                    location: self.table_root.join(&file.add.path)?,
-                };
-                let context = parquet_handler.contextualize_file_reads(vec![meta], None)?;
-                let batches = parquet_handler
-                    .read_parquet_files(context, self.schema.clone())?
-                    .try_collect::<Vec<_>>()
-                    .await?;
-                if batches.is_empty() {
-                    continue;
-                }
-                let schema = batches[0].schema();
-                let batch = concat_batches(&schema, &batches)?;
-                if let Some(fut_dv) = file.dv {
-                    let dv = fut_dv.await?;
-                    let vec: Vec<_> = (0..batch.num_rows())
-                        .map(|i| Some(!dv.contains(i.try_into().expect("fit into u32"))))
-                        .collect();
-                    let dv = BooleanArray::from(vec);
-                    results.push(filter_record_batch(&batch, &dv)?);
-                } else {
-                    results.push(batch);
+    /// Stream the [`Add`] actions of all files that survive log replay and data skipping.
+    ///
+    /// In contrast to [`Scan::files`], which yields the files in batches, this yields the files
+    /// one by one in log order. Log batches are only read as the stream is polled, so a slow
+    /// consumer does not cause the log to be buffered in memory.
+    pub fn files_stream(&self) -> DeltaResult<BoxStream<'static, DeltaResult<Add>>> {
+        Ok(self
+            .replay()?
+            .map_ok(|files| futures::stream::iter(files.into_iter().map(|file| Ok(file.add))))
+            .try_flatten()
+            .boxed())
+    }
+
+    /// Get the files that changed in the versions `from..=to`.
+    ///
+    /// In contrast to the change data feed this is file granular: it yields the net [`Add`]
+    /// actions introduced and [`Remove`] actions applied in the range, so a file added and
+    /// removed within the range is not reported at all. The predicate of the scan is not
+    /// applied.
+    ///
+    /// Actions with `dataChange = false`, e.g. those of compaction commits, only rearrange
+    /// existing data and are skipped. Files rewritten by such commits are therefore reported as
+    /// they were added, even if they are no longer part of the table at the end of the range.
+    pub async fn incremental(&self, from: Version, to: Version) -> DeltaResult<IncrementalFiles> {
+        if to < from {
+            return Err(Error::Generic(format!(
+                "end version {} is before start version {}",
+                to, from
+            )));
+        }
+        let log_root = LogPath(&self.table_root).child("_delta_log/")?;
+        let commit_files =
+            list_commit_files(self.table_client.as_ref(), &log_root, from, Some(to)).await?;
+
+        let schema = ArrowSchema {
+            fields: Fields::from_iter([ActionType::Add.field(), ActionType::Remove.field()]),
+            metadata: Default::default(),
+        };
+        let schema = Arc::new(Schema::try_from(&schema)?);
+        let json_handler = self.table_client.get_json_handler();
+        let commit_reads = json_handler.contextualize_file_reads(
+            // replay newest first, so only the latest action for each file is used
+            commit_files
+                .into_iter()
+                .rev()
+                .map(|(_, meta)| meta)
+                .collect(),
+            None,
+        )?;
+        let batches = json_handler
+            .read_json_files(commit_reads, schema)?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut seen = HashSet::new();
+        let mut added = HashSet::new();
+        let mut files = IncrementalFiles::default();
+        for batch in batches {
+            // actions of a single commit never conflict, so their order does not matter
+            for action in parse_actions(&batch, &[ActionType::Remove, ActionType::Add])? {
+                match action {
+                    Action::Add(add) if add.data_change => {
+                        let key = (add.path.clone(), add.dv_unique_id());
+                        added.insert(key.clone());
+                        if seen.insert(key) {
+                            files.adds.push(add);
+                        }
+                    }
+                    Action::Remove(remove) if remove.data_change => {
+                        let key = (remove.path.clone(), remove.dv_unique_id());
+                        if seen.insert(key) {
+                            files.removes.push(remove);
+                        }
+                    }
+                    _ => (),
                 }
             }
         }
+        // removes of files only added within the range are of no interest to the reader
+        files
+            .removes
+            .retain(|remove| !added.contains(&(remove.path.clone(), remove.dv_unique_id())));
+        Ok(files)
+    }
+
+    pub async fn execute(&self) -> DeltaResult<Vec<RecordBatch>> {
+        let results = self.execute_stream()?.try_collect::<Vec<_>>().await?;
+        match self.batch_size {
+            Some(batch_size) => rebatch(results, batch_size),
+            None => Ok(results),
+        }
+    }
+
+    /// Stream the data of the scan, yielding the remaining rows of one file after another.
+    ///
+    /// Files are read in the order they are yielded by [`Scan::files`], i.e. in log order unless
+    /// [`ScanBuilder::with_deterministic_order`] is set. The batches of a file, e.g. one per row
+    /// group, are read and yielded as they are needed. The log is only replayed as far as
+    /// needed to find the next file to read, data files are only read as the stream is polled
+    /// and no work is scheduled in the background, so dropping the stream cancels the scan
+    /// without reading any further files. Note that [`ScanBuilder::with_batch_size`] only
+    /// applies to [`Scan::execute`].
+    pub fn execute_stream(&self) -> DeltaResult<BoxStream<'static, DeltaResult<RecordBatch>>> {
+        if self.passthrough {
+            return Err(Error::Generic(
+                "Passthrough scans can not be executed, use Scan::files instead".to_string(),
+            ));
+        }
+        let reader = self.data_file_reader();
+        Ok(self
+            .files()?
+            .map_ok(|files| futures::stream::iter(files.into_iter().map(Ok::<_, Error>)))
+            .try_flatten()
+            .map_ok(move |file| reader.clone().read(file))
+            .try_flatten()
+            .boxed())
+    }
 
-        Ok(results)
+    fn data_file_reader(&self) -> Arc<DataFileReader<PRC>> {
+        Arc::new(DataFileReader {
+            row_index: self.row_index.clone(),
+            exact_schema: self.exact_schema,
+            ..DataFileReader::new(
+                self.table_client.get_parquet_handler(),
+                self.table_root.clone(),
+                self.schema.clone(),
+                self.partition_columns.clone(),
+                self.column_mapping,
+            )
+        })
     }
 }
 
+/// Whether the table `configuration` enables column mapping, i.e. the data files store the
+/// columns by their physical names.
+pub(crate) fn column_mapping_enabled(configuration: &HashMap<String, Option<String>>) -> bool {
+    configuration
+        .get(COLUMN_MAPPING_MODE_KEY)
+        .and_then(|mode| mode.as_deref())
+        .is_some_and(|mode| mode != "none")
+}
+
+/// The columns of `schema` stored in the data files, i.e. all but the `partition_columns`, see
+/// [`Scan::physical_read_schema`].
+fn physical_read_schema(
+    schema: &Schema,
+    partition_columns: &[String],
+    column_mapping: bool,
+) -> StructType {
+    StructType::new(
+        schema
+            .fields
+            .iter()
+            .filter(|field| !partition_columns.contains(&field.name))
+            .map(|field| match column_mapping {
+                true => field.to_physical(),
+                false => field.clone(),
+            })
+            .collect(),
+    )
+}
+
+/// Reads the data files of a scan and arranges their batches in the scan schema.
+pub(crate) struct DataFileReader<PRC> {
+    parquet_handler: Arc<dyn ParquetHandler<FileReadContext = PRC>>,
+    table_root: Url,
+    /// The logical schema of the returned batches
+    schema: SchemaRef,
+    /// The columns to read from the data files, see [`Scan::physical_read_schema`]
+    physical_schema: SchemaRef,
+    partition_columns: Vec<String>,
+    column_mapping: bool,
+    row_index: Option<String>,
+    exact_schema: bool,
+}
+
+impl<PRC: Send + 'static> DataFileReader<PRC> {
+    /// Create a reader returning batches with the columns of `schema`, where the values of the
+    /// `partition_columns` are taken from the partition values of the files.
+    pub(crate) fn new(
+        parquet_handler: Arc<dyn ParquetHandler<FileReadContext = PRC>>,
+        table_root: Url,
+        schema: SchemaRef,
+        partition_columns: Vec<String>,
+        column_mapping: bool,
+    ) -> Self {
+        Self {
+            parquet_handler,
+            table_root,
+            physical_schema: Arc::new(physical_read_schema(
+                &schema,
+                &partition_columns,
+                column_mapping,
+            )),
+            schema,
+            partition_columns,
+            column_mapping,
+            row_index: None,
+            exact_schema: false,
+        }
+    }
+
+    /// Read the rows of `file` not removed by its deletion vector, if any.
+    ///
+    /// The batches of the file are read and yielded one at a time as the stream is polled, so
+    /// only the row groups needed for the batches consumed so far are read. Batches without any
+    /// remaining rows are skipped. The columns of the returned batches are in the order of
+    /// `schema`, the values of partition columns are taken from the partition values of the
+    /// file. If `row_index` is set, a column with that name and the position of each row in the
+    /// file is appended. See [`project_batch`] for `exact_schema`.
+    pub(crate) fn read(
+        self: Arc<Self>,
+        file: DataFile,
+    ) -> BoxStream<'static, DeltaResult<RecordBatch>> {
+        let setup = async move {
+            let meta = FileMeta {
+                last_modified: file.add.modification_time,
+                size: file.add.size as usize,
+                location: self.table_root.join(&file.add.path)?,
+            };
+            let context = self
+                .parquet_handler
+                .contextualize_file_reads(vec![meta], None)?;
+            let batches = self
+                .parquet_handler
+                .read_parquet_files(context, self.physical_schema.clone())?;
+            let dv = match file.dv {
+                Some(fut_dv) => Some(fut_dv.await?),
+                None => None,
+            };
+            let add = file.add;
+            // the position of the first row of the next batch in the file
+            let mut offset = 0;
+            Ok::<_, Error>(batches.try_filter_map(move |batch| {
+                let start = offset;
+                offset += batch.num_rows();
+                let batch = project_batch(
+                    &batch,
+                    &self.schema,
+                    &self.partition_columns,
+                    &add,
+                    self.column_mapping,
+                    self.exact_schema,
+                )
+                .and_then(|batch| {
+                    remaining_rows(batch, start, self.row_index.as_deref(), dv.as_ref())
+                });
+                futures::future::ready(batch)
+            }))
+        };
+        futures::stream::once(setup).try_flatten().boxed()
+    }
+}
+
+/// The rows of `batch`, starting at row `offset` of its file, not removed by `dv`, or `None` if
+/// all rows were removed.
+fn remaining_rows(
+    batch: RecordBatch,
+    offset: usize,
+    row_index: Option<&str>,
+    dv: Option<&RoaringTreemap>,
+) -> DeltaResult<Option<RecordBatch>> {
+    // the index is added before applying the deletion vector to keep the original positions
+    let batch = match row_index {
+        Some(name) => append_row_index(&batch, name, offset)?,
+        None => batch,
+    };
+    let Some(dv) = dv else {
+        return Ok((batch.num_rows() > 0).then_some(batch));
+    };
+    let vec: Vec<_> = (offset..offset + batch.num_rows())
+        .map(|i| Some(!dv.contains(i as u64)))
+        .collect();
+    let dv = BooleanArray::from(vec);
+    let batch = match batch.num_columns() {
+        0 => new_empty_batch(batch.schema(), dv.true_count())?,
+        _ => filter_record_batch(&batch, &dv)?,
+    };
+    Ok((batch.num_rows() > 0).then_some(batch))
+}
+
+/// A batch of `num_rows` rows without any columns.
+fn new_empty_batch(schema: ArrowSchemaRef, num_rows: usize) -> DeltaResult<RecordBatch> {
+    let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+    Ok(RecordBatch::try_new_with_options(
+        schema,
+        Vec::new(),
+        &options,
+    )?)
+}
+
+/// Arrange the columns of `batch` in the order of `schema`, materializing partition columns.
+///
+/// `batch` is read with the physical names of the columns if `column_mapping` is enabled, they
+/// are renamed to the logical names of `schema`, including the fields of nested structs. The
+/// partition values of column mapped tables are keyed by physical name as well.
+///
+/// Columns read from the data file keep the field of the file, unless `exact_schema` is set, in
+/// which case they are conformed to the field of `schema`. Nullable columns missing from the
+/// data file, e.g. because they were added to the table later, are filled with nulls, while a
+/// missing non-nullable column is an error.
+fn project_batch(
+    batch: &RecordBatch,
+    schema: &Schema,
+    partition_columns: &[String],
+    add: &Add,
+    column_mapping: bool,
+    exact_schema: bool,
+) -> DeltaResult<RecordBatch> {
+    let num_rows = batch.num_rows();
+    let mut fields = Vec::with_capacity(schema.fields.len());
+    let mut columns = Vec::with_capacity(schema.fields.len());
+    for field in &schema.fields {
+        let physical_name = match column_mapping {
+            true => field.physical_name(),
+            false => field.name.as_str(),
+        };
+        let read = batch
+            .schema()
+            .index_of(physical_name)
+            .ok()
+            .filter(|_| !partition_columns.contains(&field.name));
+        if let Some(idx) = read {
+            let (file_field, column) = match column_mapping {
+                true => to_logical(batch.schema().field(idx), batch.column(idx), field)?,
+                false => (batch.schema().field(idx).clone(), batch.column(idx).clone()),
+            };
+            if exact_schema {
+                let arrow_field = ArrowField::try_from(field)?;
+                columns.push(conform_column(&column, &arrow_field, None)?);
+                fields.push(arrow_field);
+            } else {
+                fields.push(file_field);
+                columns.push(column);
+            }
+            continue;
+        }
+        let arrow_field = ArrowField::try_from(field)?;
+        let column = if partition_columns.contains(&field.name) {
+            let raw = add
+                .partition_values
+                .get(physical_name)
+                .and_then(|value| value.as_deref());
+            // types not used for partition skipping, e.g. timestamps, are parsed as is
+            let value = match parse_partition_value(raw, &field.data_type)? {
+                Some(value) => value,
+                None => Scalar::try_from_string(raw.unwrap_or_default(), &field.data_type)
+                    .map_err(|_| {
+                        Error::Generic(format!(
+                            "Unsupported type {:?} of partition column '{}'",
+                            field.data_type, field.name
+                        ))
+                    })?,
+            };
+            value.to_array(num_rows)?
+        } else if field.nullable {
+            new_null_array(arrow_field.data_type(), num_rows)
+        } else {
+            return Err(Error::MissingColumn(format!(
+                "Required column '{}' not found in data file {}",
+                field.name, add.path
+            )));
+        };
+        fields.push(arrow_field);
+        columns.push(column);
+    }
+    let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+    Ok(RecordBatch::try_new_with_options(
+        Arc::new(ArrowSchema::new(fields)),
+        columns,
+        &options,
+    )?)
+}
+
+/// Rename `column`, read with the physical names of `logical` and its nested fields, to the
+/// logical names.
+///
+/// The children of structs are matched by physical name and returned in the order of the
+/// logical schema, missing children are filled with nulls.
+fn to_logical(
+    field: &ArrowField,
+    column: &ArrayRef,
+    logical: &StructField,
+) -> DeltaResult<(ArrowField, ArrayRef)> {
+    let (DataType::Struct(children), Some(array)) = (
+        &logical.data_type,
+        column.as_any().downcast_ref::<StructArray>(),
+    ) else {
+        return Ok((field.clone().with_name(&logical.name), column.clone()));
+    };
+    let (fields, columns): (Vec<_>, Vec<_>) = children
+        .fields
+        .iter()
+        .map(|child| match array.fields().find(child.physical_name()) {
+            Some((idx, field)) => to_logical(field, array.column(idx), child),
+            None => {
+                let data_type = ArrowDataType::try_from(&child.data_type)?;
+                let column = new_null_array(&data_type, array.len());
+                Ok((ArrowField::new(&child.name, data_type, true), column))
+            }
+        })
+        .collect::<DeltaResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let array = StructArray::try_new(fields.into(), columns, array.nulls().cloned())?;
+    let field = ArrowField::new(
+        &logical.name,
+        array.data_type().clone(),
+        field.is_nullable(),
+    )
+    .with_metadata(field.metadata().clone());
+    Ok((field, Arc::new(array)))
+}
+
+/// Convert `column` to the type of `field`.
+///
+/// The children of struct columns are matched by name, so they may be in any order, and
+/// missing nullable children are filled with nulls. Other types are cast. Rows where the
+/// enclosing struct `parent` is null may be null in any child.
+fn conform_column(
+    column: &ArrayRef,
+    field: &ArrowField,
+    parent: Option<&StructArray>,
+) -> DeltaResult<ArrayRef> {
+    let has_nulls = column.null_count() > 0
+        && (0..column.len())
+            .any(|idx| column.is_null(idx) && parent.is_none_or(|parent| parent.is_valid(idx)));
+    if !field.is_nullable() && has_nulls {
+        return Err(Error::Generic(format!(
+            "Column '{}' contains nulls but is not nullable",
+            field.name()
+        )));
+    }
+    if column.data_type() == field.data_type() {
+        return Ok(column.clone());
+    }
+    match (column.data_type(), field.data_type()) {
+        (ArrowDataType::Struct(_), ArrowDataType::Struct(children)) => {
+            let array = column
+                .as_any()
+                .downcast_ref::<StructArray>()
+                .ok_or_else(|| Error::UnexpectedColumnType("Expected a struct column".into()))?;
+            let columns = children
+                .iter()
+                .map(|child| match array.column_by_name(child.name()) {
+                    Some(column) => conform_column(column, child, Some(array)),
+                    None if child.is_nullable() => {
+                        Ok(new_null_array(child.data_type(), array.len()))
+                    }
+                    None => Err(Error::MissingColumn(format!(
+                        "Required field '{}' not found in column '{}'",
+                        child.name(),
+                        field.name()
+                    ))),
+                })
+                .collect::<DeltaResult<Vec<_>>>()?;
+            Ok(Arc::new(StructArray::try_new(
+                children.clone(),
+                columns,
+                array.nulls().cloned(),
+            )?))
+        }
+        (_, to) => Ok(arrow_cast::cast(column, to)?),
+    }
+}
+
+/// Append a non-nullable column `name` numbering the rows of `batch` from `offset`.
+fn append_row_index(batch: &RecordBatch, name: &str, offset: usize) -> DeltaResult<RecordBatch> {
+    let schema = batch.schema();
+    let mut fields = schema.fields().iter().cloned().collect::<Vec<_>>();
+    fields.push(Arc::new(ArrowField::new(name, ArrowDataType::Int64, false)));
+    let mut columns = batch.columns().to_vec();
+    let start = offset as i64;
+    columns.push(Arc::new(Int64Array::from_iter_values(
+        start..start + batch.num_rows() as i64,
+    )));
+    Ok(RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(fields)),
+        columns,
+    )?)
+}
+
+/// Split and combine `batches` into batches of `batch_size` rows.
+///
+/// Batches with different schemas are never combined, so a batch preceding a schema change may
+/// be smaller as well.
+fn rebatch(batches: Vec<RecordBatch>, batch_size: usize) -> DeltaResult<Vec<RecordBatch>> {
+    let mut results = Vec::new();
+    let mut pending: Vec<RecordBatch> = Vec::new();
+    let mut pending_rows = 0;
+    fn flush(pending: &mut Vec<RecordBatch>, results: &mut Vec<RecordBatch>) -> DeltaResult<()> {
+        if let Some(first) = pending.first() {
+            results.push(concat_batches(&first.schema(), pending.iter())?);
+            pending.clear();
+        }
+        Ok(())
+    }
+    for batch in batches {
+        if pending
+            .first()
+            .is_some_and(|first| first.schema() != batch.schema())
+        {
+            flush(&mut pending, &mut results)?;
+            pending_rows = 0;
+        }
+        let mut offset = 0;
+        while offset < batch.num_rows() {
+            let len = (batch_size - pending_rows).min(batch.num_rows() - offset);
+            pending.push(batch.slice(offset, len));
+            pending_rows += len;
+            offset += len;
+            if pending_rows == batch_size {
+                flush(&mut pending, &mut results)?;
+                pending_rows = 0;
+            }
+        }
+    }
+    flush(&mut pending, &mut results)?;
+    Ok(results)
+}
+
 #[cfg(all(test, feature = "default-client"))]
 mod tests {
     use std::path::PathBuf;
@@ -207,21 +1005,27 @@ mod tests {
     use futures::TryStreamExt;
 
     use super::*;
+    use crate::client::json::JsonReadContext;
+    use crate::client::parquet::ParquetReadContext;
     use crate::client::DefaultTableClient;
+    use crate::schema::{DataType, PrimitiveType, StructField};
     use crate::Table;
 
-    #[tokio::test]
-    async fn test_scan_files() {
-        let path =
-            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+    /// The table `name` of the `tests/data` folder, read with a [`DefaultTableClient`].
+    fn test_table(name: &str) -> Table<JsonReadContext, ParquetReadContext> {
+        let path = std::fs::canonicalize(PathBuf::from(format!("./tests/data/{name}/"))).unwrap();
         let url = url::Url::from_directory_path(path).unwrap();
         let table_client = Arc::new(
             DefaultTableClient::try_new(&url, std::iter::empty::<(&str, &str)>()).unwrap(),
         );
+        Table::new(url, table_client)
+    }
 
-        let table = Table::new(url, table_client);
+    #[tokio::test]
+    async fn test_scan_files() {
+        let table = test_table("table-without-dv-small");
         let snapshot = table.snapshot(None).await.unwrap();
-        let scan = snapshot.scan().await.unwrap().build();
+        let scan = snapshot.scan().await.unwrap().build().unwrap();
         let files = scan.files().unwrap().try_collect::<Vec<_>>().await.unwrap();
 
         assert_eq!(files.len(), 1);
@@ -229,20 +1033,277 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_scan_data() {
-        let path =
-            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
-        let url = url::Url::from_directory_path(path).unwrap();
-        let table_client = Arc::new(
-            DefaultTableClient::try_new(&url, std::iter::empty::<(&str, &str)>()).unwrap(),
+    async fn test_skipping_correctness() {
+        let column = || Box::new(Expression::Column("value".to_string()));
+        let literal = |value: i32| Box::new(Expression::Literal(Scalar::from(value)));
+        let predicates = [
+            Expression::LessThan(column(), literal(3)),
+            Expression::LessThan(column(), literal(0)),
+            Expression::GreaterThan(column(), literal(8)),
+            Expression::GreaterThan(column(), literal(9)),
+            Expression::Equal(column(), literal(5)),
+            Expression::Equal(column(), literal(20)),
+            Expression::NotEqual(column(), literal(5)),
+            Expression::Or(
+                Box::new(Expression::LessThan(column(), literal(-1))),
+                Box::new(Expression::GreaterThan(column(), literal(10))),
+            ),
+        ];
+        let mut pruned = 0;
+        for name in ["table-with-dv-small", "table-without-dv-small"] {
+            let table = test_table(name);
+            let snapshot = table.snapshot(None).await.unwrap();
+
+            for predicate in &predicates {
+                let scan = snapshot.scan().await.unwrap().build().unwrap();
+                let kept = snapshot
+                    .scan()
+                    .await
+                    .unwrap()
+                    .with_predicate(predicate.clone())
+                    .build()
+                    .unwrap()
+                    .files_stream()
+                    .unwrap()
+                    .map_ok(|add| add.path)
+                    .try_collect::<HashSet<_>>()
+                    .await
+                    .unwrap();
+                let files = scan.files().unwrap().try_concat().await.unwrap();
+                for file in files {
+                    if kept.contains(&file.add.path) {
+                        continue;
+                    }
+                    pruned += 1;
+                    let batches = scan
+                        .data_file_reader()
+                        .read(file)
+                        .try_collect::<Vec<_>>()
+                        .await
+                        .unwrap();
+                    for batch in batches {
+                        assert!(
+                            !predicate.matches_any(&batch).unwrap(),
+                            "{name}: skipped a file with rows matching {predicate:?}"
+                        );
+                    }
+                }
+            }
+        }
+        assert!(pruned > 0);
+    }
+
+    #[tokio::test]
+    async fn test_scan_files_stream() {
+        let table = test_table("table-with-dv-small");
+        let snapshot = table.snapshot(None).await.unwrap();
+        let scan = snapshot.scan().await.unwrap().build().unwrap();
+
+        let streamed = scan
+            .files_stream()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let files = scan
+            .files()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .map(|file| file.add)
+            .collect::<Vec<_>>();
+
+        assert_eq!(streamed.len(), 1);
+        assert_eq!(streamed, files);
+    }
+
+    #[tokio::test]
+    async fn test_scan_file_accessors() {
+        let table = test_table("table-with-dv-small");
+        let snapshot = table.snapshot(None).await.unwrap();
+        let scan = snapshot.scan().await.unwrap().build().unwrap();
+        let files = scan
+            .files_stream()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 1);
+
+        let add = &files[0];
+        assert_eq!(
+            add.path(),
+            "part-00000-fae5310a-a37d-4e51-827b-c3d5516560ca-c000.snappy.parquet"
         );
+        assert_eq!(add.size(), 635);
+        assert_eq!(add.num_records(), Some(10));
+        assert!(add.partition_values().is_empty());
+        assert!(add.stats().unwrap().contains("\"tightBounds\":false"));
+        let dv = add.deletion_vector().unwrap();
+        assert_eq!(dv.storage_type, "u");
+        assert_eq!(dv.cardinality, 2);
+    }
 
-        let table = Table::new(url, table_client);
+    #[tokio::test]
+    async fn test_scan_data() {
+        let table = test_table("table-without-dv-small");
         let snapshot = table.snapshot(None).await.unwrap();
-        let scan = snapshot.scan().await.unwrap().build();
+        let scan = snapshot.scan().await.unwrap().build().unwrap();
         let files = scan.execute().await.unwrap();
 
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].num_rows(), 10)
     }
+
+    #[tokio::test]
+    async fn test_scan_files_and_execute() {
+        let table = test_table("table-without-dv-small");
+        let snapshot = table.snapshot(None).await.unwrap();
+        let scan = snapshot.scan().await.unwrap().build().unwrap();
+
+        let files = scan.files().unwrap().try_collect::<Vec<_>>().await.unwrap();
+        let files = files.into_iter().flatten().collect::<Vec<_>>();
+        let batches = scan.execute().await.unwrap();
+
+        assert_eq!(files.len(), batches.len());
+        let num_records: i64 = files
+            .iter()
+            .map(|f| {
+                let stats: serde_json::Value =
+                    serde_json::from_str(f.add.stats.as_ref().unwrap()).unwrap();
+                stats["numRecords"].as_i64().unwrap()
+            })
+            .sum();
+        let num_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(num_records as usize, num_rows);
+
+        // the snapshot is still usable after creating a scan
+        assert_eq!(snapshot.version(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_scan_skipping_applicability() {
+        let table = test_table("table-with-dv-small");
+        let snapshot = table.snapshot(None).await.unwrap();
+        let column = || Box::new(Expression::Column("value".into()));
+        let literal = || Box::new(Expression::Literal(3.into()));
+        let usable = || Box::new(Expression::LessThan(column(), literal()));
+        let unusable = || Box::new(Expression::LessThan(literal(), literal()));
+
+        let scan = snapshot.scan().await.unwrap().build().unwrap();
+        assert_eq!(
+            scan.skipping_applicability().unwrap(),
+            SkippingApplicability::NotApplicable
+        );
+
+        for (predicate, expected) in [
+            (*usable(), SkippingApplicability::Full),
+            (*unusable(), SkippingApplicability::NotApplicable),
+            (
+                Expression::LessThan(literal(), column()),
+                SkippingApplicability::Full,
+            ),
+            (
+                Expression::LessThan(column(), column()),
+                SkippingApplicability::Full,
+            ),
+            (
+                Expression::And(usable(), unusable()),
+                SkippingApplicability::Partial,
+            ),
+            (
+                Expression::Or(usable(), unusable()),
+                SkippingApplicability::NotApplicable,
+            ),
+        ] {
+            let scan = snapshot
+                .scan()
+                .await
+                .unwrap()
+                .with_predicate(predicate)
+                .build()
+                .unwrap();
+            assert_eq!(scan.skipping_applicability().unwrap(), expected);
+        }
+
+        // the usable leg of a partially applicable predicate still does not prune the file
+        let scan = snapshot
+            .scan()
+            .await
+            .unwrap()
+            .with_predicate(Expression::And(usable(), unusable()))
+            .build()
+            .unwrap();
+        let files = scan
+            .files_stream()
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_build_missing_columns() {
+        let table = test_table("table-without-dv-small");
+        let snapshot = table.snapshot(None).await.unwrap();
+        let assert_missing = |result: DeltaResult<Scan<_, _>>, name: &str| match result {
+            Err(Error::MissingColumn(msg)) => {
+                assert!(msg.contains(&format!("'{name}'")), "{msg}");
+                assert!(msg.ends_with("valid columns are: value"), "{msg}");
+            }
+            _ => panic!("expected missing column '{name}'"),
+        };
+
+        let schema = Arc::new(Schema::new(vec![StructField::new(
+            "valeu",
+            DataType::Primitive(PrimitiveType::Long),
+            true,
+        )]));
+        let result = snapshot.scan().await.unwrap().with_schema(schema).build();
+        assert_missing(result, "valeu");
+
+        let predicate = Expression::LessThan(
+            Box::new(Expression::Column("value.nested".into())),
+            Box::new(Expression::Literal(1.into())),
+        );
+        let result = snapshot
+            .scan()
+            .await
+            .unwrap()
+            .with_predicate(predicate)
+            .build();
+        assert_missing(result, "value.nested");
+    }
+
+    #[test]
+    fn test_conform_column() {
+        let a = Arc::new(ArrowField::new("a", ArrowDataType::Int64, true));
+        let b = Arc::new(ArrowField::new("b", ArrowDataType::Utf8, false));
+        let b_values: ArrayRef = Arc::new(arrow_array::StringArray::from(vec!["x", "y"]));
+        let a_values: ArrayRef = Arc::new(Int64Array::from(vec![Some(1), None]));
+        let column: ArrayRef = Arc::new(StructArray::from(vec![(b, b_values), (a, a_values)]));
+
+        // children are reordered by name and cast to the target type
+        let children = Fields::from(vec![
+            ArrowField::new("a", ArrowDataType::Int32, true),
+            ArrowField::new("b", ArrowDataType::Utf8, true),
+            ArrowField::new("c", ArrowDataType::Boolean, true),
+        ]);
+        let field = ArrowField::new("s", ArrowDataType::Struct(children), true);
+        let conformed = conform_column(&column, &field, None).unwrap();
+        assert_eq!(conformed.data_type(), field.data_type());
+        let conformed = conformed.as_any().downcast_ref::<StructArray>().unwrap();
+        let expected: ArrayRef = Arc::new(arrow_array::Int32Array::from(vec![Some(1), None]));
+        assert_eq!(conformed.column(0), &expected);
+        assert_eq!(conformed.column(2).null_count(), 2);
+
+        // nulls in a non-nullable field are an error
+        let children = Fields::from(vec![ArrowField::new("a", ArrowDataType::Int64, false)]);
+        let field = ArrowField::new("s", ArrowDataType::Struct(children), true);
+        assert!(conform_column(&column, &field, None).is_err());
+    }
 }
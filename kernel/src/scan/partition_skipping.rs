@@ -0,0 +1,402 @@
+//! Pruning of scan files based on the partition values recorded in their `add` actions.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::actions::Add;
+use crate::expressions::{Expression, Scalar};
+use crate::schema::{DataType, PrimitiveType, Schema};
+use crate::DeltaResult;
+
+/// Partition value used by Delta (and Hive) to denote a null partition.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Evaluates a predicate that only references partition columns against the partition values
+/// of individual files.
+#[derive(Debug, Clone)]
+pub(crate) struct PartitionSkippingFilter {
+    predicate: Expression,
+    /// Declared types of the partition columns referenced by the predicate
+    columns: HashMap<String, DataType>,
+}
+
+impl PartitionSkippingFilter {
+    /// Create a new filter for the `predicate`.
+    ///
+    /// Returns `None` if the predicate references any column that is not a partition column, in
+    /// which case partition values alone cannot decide whether a file can be skipped.
+    pub(crate) fn try_new(
+        predicate: &Expression,
+        schema: &Schema,
+        partition_columns: &[String],
+    ) -> Option<Self> {
+        let referenced = predicate.columns();
+        if referenced.is_empty() {
+            return None;
+        }
+        let columns = referenced
+            .into_iter()
+            .map(|name| {
+                if !partition_columns.contains(&name) {
+                    return None;
+                }
+                let field = schema.fields.iter().find(|field| field.name == name)?;
+                Some((name, field.data_type.clone()))
+            })
+            .collect::<Option<HashMap<_, _>>>()?;
+        Some(Self {
+            predicate: predicate.clone(),
+            columns,
+        })
+    }
+
+    /// Returns true if the file described by `add` may contain rows matching the predicate.
+    pub(crate) fn apply(&self, add: &Add) -> DeltaResult<bool> {
+        let keep =
+            self.evaluate(|name| add.partition_values.get(name).and_then(|v| v.as_deref()))?;
+        // the predicate could not be evaluated, so the file has to be read
+        Ok(keep.unwrap_or(true))
+    }
+
+    /// Evaluate the predicate for the raw partition values returned by `raw`, or `None` if it
+    /// can not be evaluated.
+    fn evaluate<'a>(&self, raw: impl Fn(&str) -> Option<&'a str>) -> DeltaResult<Option<bool>> {
+        let mut values = HashMap::with_capacity(self.columns.len());
+        for (name, data_type) in &self.columns {
+            values.insert(name.as_str(), parse_partition_value(raw(name), data_type)?);
+        }
+        Ok(match evaluate(&self.predicate, &values) {
+            Some(Scalar::Boolean(keep)) => Some(keep),
+            // a null result means no row in the file can match
+            Some(Scalar::Null(_)) => Some(false),
+            _ => None,
+        })
+    }
+}
+
+impl Expression {
+    /// Evaluate this predicate against the partition values of a single file.
+    ///
+    /// The raw `values` are parsed according to the types of the columns in `schema`, missing
+    /// values, empty strings and `__HIVE_DEFAULT_PARTITION__` are treated as null. Returns
+    /// whether the file may contain rows matching the predicate, or `None` if the predicate
+    /// references columns without a partition value or can not be evaluated, e.g. because of
+    /// unsupported types.
+    pub fn evaluate_partition(
+        &self,
+        values: &HashMap<String, String>,
+        schema: &Schema,
+    ) -> DeltaResult<Option<bool>> {
+        let partition_columns = values.keys().cloned().collect::<Vec<_>>();
+        match PartitionSkippingFilter::try_new(self, schema, &partition_columns) {
+            Some(filter) => filter.evaluate(|name| values.get(name).map(String::as_str)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Parse the string representation of a partition value into a [`Scalar`] of the column's
+/// declared type.
+///
+/// Missing values, empty strings and the [`HIVE_DEFAULT_PARTITION`] sentinel are parsed as
+/// null. Returns `None` for types that are not supported for partition skipping.
+pub(crate) fn parse_partition_value(
+    raw: Option<&str>,
+    data_type: &DataType,
+) -> DeltaResult<Option<Scalar>> {
+    let DataType::Primitive(primitive) = data_type else {
+        return Ok(None);
+    };
+    let raw = match raw {
+        None | Some("") | Some(HIVE_DEFAULT_PARTITION) => {
+            return Ok(Some(Scalar::Null(data_type.clone())))
+        }
+        Some(raw) => raw,
+    };
+    match primitive {
+        PrimitiveType::String
+        | PrimitiveType::Long
+        | PrimitiveType::Integer
+        | PrimitiveType::Short
+        | PrimitiveType::Byte
+        | PrimitiveType::Float
+        | PrimitiveType::Double
+        | PrimitiveType::Boolean
+        | PrimitiveType::Date => Ok(Some(Scalar::try_from_string(raw, data_type)?)),
+        _ => Ok(None),
+    }
+}
+
+/// Evaluate the expression for a single set of partition values.
+///
+/// Returns `None` if the expression cannot be evaluated, e.g. because a value is of a type that
+/// is not supported or two values cannot be compared.
+fn evaluate(expression: &Expression, values: &HashMap<&str, Option<Scalar>>) -> Option<Scalar> {
+    match expression {
+        Expression::Literal(scalar) => Some(scalar.clone()),
+        Expression::Column(name) => values.get(name.as_str()).cloned().flatten(),
+        Expression::LessThan(left, right) => {
+            compare(evaluate(left, values)?, evaluate(right, values)?, |ord| {
+                ord == Ordering::Less
+            })
+        }
+        Expression::GreaterThan(left, right) => {
+            compare(evaluate(left, values)?, evaluate(right, values)?, |ord| {
+                ord == Ordering::Greater
+            })
+        }
+        Expression::Equal(left, right) => {
+            compare(evaluate(left, values)?, evaluate(right, values)?, |ord| {
+                ord == Ordering::Equal
+            })
+        }
+        Expression::NotEqual(left, right) => {
+            compare(evaluate(left, values)?, evaluate(right, values)?, |ord| {
+                ord != Ordering::Equal
+            })
+        }
+        Expression::And(left, right) => match (evaluate(left, values)?, evaluate(right, values)?) {
+            (Scalar::Boolean(false), _) | (_, Scalar::Boolean(false)) => {
+                Some(Scalar::Boolean(false))
+            }
+            (Scalar::Boolean(true), Scalar::Boolean(true)) => Some(Scalar::Boolean(true)),
+            (Scalar::Boolean(_) | Scalar::Null(_), Scalar::Boolean(_) | Scalar::Null(_)) => {
+                Some(Scalar::Null(DataType::Primitive(PrimitiveType::Boolean)))
+            }
+            _ => None,
+        },
+        Expression::Or(left, right) => match (evaluate(left, values)?, evaluate(right, values)?) {
+            (Scalar::Boolean(true), _) | (_, Scalar::Boolean(true)) => Some(Scalar::Boolean(true)),
+            (Scalar::Boolean(false), Scalar::Boolean(false)) => Some(Scalar::Boolean(false)),
+            (Scalar::Boolean(_) | Scalar::Null(_), Scalar::Boolean(_) | Scalar::Null(_)) => {
+                Some(Scalar::Null(DataType::Primitive(PrimitiveType::Boolean)))
+            }
+            _ => None,
+        },
+        // casts are not evaluated on partition values
+        Expression::Cast { .. } => None,
+    }
+}
+
+fn compare(left: Scalar, right: Scalar, op: impl Fn(Ordering) -> bool) -> Option<Scalar> {
+    if left.is_null() || right.is_null() {
+        return Some(Scalar::Null(DataType::Primitive(PrimitiveType::Boolean)));
+    }
+    Some(Scalar::Boolean(op(left.compare_widened(&right)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::StructField;
+
+    fn add_with_partitions(values: &[(&str, Option<&str>)]) -> Add {
+        Add {
+            path: "part-00000.parquet".into(),
+            partition_values: values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.map(|v| v.to_string())))
+                .collect(),
+            size: 0,
+            modification_time: 0,
+            data_change: true,
+            stats: None,
+            tags: HashMap::new(),
+            deletion_vector: None,
+            base_row_id: None,
+            default_row_commit_version: None,
+        }
+    }
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            StructField::new("id", DataType::Primitive(PrimitiveType::Long), true),
+            StructField::new(
+                "part_int",
+                DataType::Primitive(PrimitiveType::Integer),
+                true,
+            ),
+            StructField::new("part_date", DataType::Primitive(PrimitiveType::Date), true),
+        ])
+    }
+
+    fn partition_columns() -> Vec<String> {
+        vec!["part_int".to_string(), "part_date".to_string()]
+    }
+
+    fn equal(column: &str, value: Scalar) -> Expression {
+        Expression::Equal(
+            Box::new(Expression::Column(column.to_string())),
+            Box::new(Expression::Literal(value)),
+        )
+    }
+
+    #[test]
+    fn test_parse_partition_values() {
+        let int = DataType::Primitive(PrimitiveType::Integer);
+        assert_eq!(
+            parse_partition_value(Some("5"), &int).unwrap(),
+            Some(Scalar::Integer(5))
+        );
+        assert!(parse_partition_value(Some("five"), &int).is_err());
+
+        let long = DataType::Primitive(PrimitiveType::Long);
+        assert_eq!(
+            parse_partition_value(Some("-9000000000"), &long).unwrap(),
+            Some(Scalar::Long(-9000000000))
+        );
+
+        let date = DataType::Primitive(PrimitiveType::Date);
+        assert_eq!(
+            parse_partition_value(Some("1970-01-01"), &date).unwrap(),
+            Some(Scalar::Date(0))
+        );
+        assert_eq!(
+            parse_partition_value(Some("2021-03-01"), &date).unwrap(),
+            Some(Scalar::Date(18687))
+        );
+        assert_eq!(
+            parse_partition_value(Some("1969-12-31"), &date).unwrap(),
+            Some(Scalar::Date(-1))
+        );
+        assert!(parse_partition_value(Some("2021-13-01"), &date).is_err());
+
+        let boolean = DataType::Primitive(PrimitiveType::Boolean);
+        assert_eq!(
+            parse_partition_value(Some("true"), &boolean).unwrap(),
+            Some(Scalar::Boolean(true))
+        );
+
+        let string = DataType::Primitive(PrimitiveType::String);
+        assert_eq!(
+            parse_partition_value(Some("a"), &string).unwrap(),
+            Some(Scalar::String("a".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_null_partition_values() {
+        // empty strings are null for every type, including strings
+        for data_type in [PrimitiveType::Integer, PrimitiveType::String] {
+            let data_type = DataType::Primitive(data_type);
+            for raw in [None, Some(HIVE_DEFAULT_PARTITION), Some("")] {
+                assert_eq!(
+                    parse_partition_value(raw, &data_type).unwrap(),
+                    Some(Scalar::Null(data_type.clone()))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_partition_filter_int() {
+        let predicate = equal("part_int", Scalar::Integer(5));
+        let filter =
+            PartitionSkippingFilter::try_new(&predicate, &schema(), &partition_columns()).unwrap();
+
+        assert!(filter
+            .apply(&add_with_partitions(&[("part_int", Some("5"))]))
+            .unwrap());
+        assert!(!filter
+            .apply(&add_with_partitions(&[("part_int", Some("6"))]))
+            .unwrap());
+
+        // long literals are compared against integer partition values
+        let predicate = Expression::LessThan(
+            Box::new(Expression::Column("part_int".into())),
+            Box::new(Expression::Literal(Scalar::Long(6))),
+        );
+        let filter =
+            PartitionSkippingFilter::try_new(&predicate, &schema(), &partition_columns()).unwrap();
+        assert!(filter
+            .apply(&add_with_partitions(&[("part_int", Some("5"))]))
+            .unwrap());
+        assert!(!filter
+            .apply(&add_with_partitions(&[("part_int", Some("6"))]))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_partition_filter_date() {
+        let predicate = equal("part_date", Scalar::Date(18687));
+        let filter =
+            PartitionSkippingFilter::try_new(&predicate, &schema(), &partition_columns()).unwrap();
+
+        assert!(filter
+            .apply(&add_with_partitions(&[("part_date", Some("2021-03-01"))]))
+            .unwrap());
+        assert!(!filter
+            .apply(&add_with_partitions(&[("part_date", Some("2021-03-02"))]))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_partition_filter_null() {
+        let predicate = equal("part_int", Scalar::Integer(5));
+        let filter =
+            PartitionSkippingFilter::try_new(&predicate, &schema(), &partition_columns()).unwrap();
+
+        assert!(!filter
+            .apply(&add_with_partitions(&[(
+                "part_int",
+                Some(HIVE_DEFAULT_PARTITION)
+            )]))
+            .unwrap());
+        assert!(!filter
+            .apply(&add_with_partitions(&[("part_int", None)]))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_partition_filter_requires_partition_columns() {
+        let predicate = equal("id", Scalar::Integer(5));
+        assert!(
+            PartitionSkippingFilter::try_new(&predicate, &schema(), &partition_columns()).is_none()
+        );
+    }
+
+    #[test]
+    fn test_evaluate_partition() {
+        let values = HashMap::from([
+            ("part_int".to_string(), "5".to_string()),
+            ("part_date".to_string(), "2023-01-01".to_string()),
+        ]);
+        let predicate = |column: &str, value: Scalar| {
+            Expression::LessThan(
+                Box::new(Expression::Column(column.to_string())),
+                Box::new(Expression::Literal(value)),
+            )
+        };
+        let evaluate = |predicate: Expression| predicate.evaluate_partition(&values, &schema());
+
+        assert_eq!(
+            evaluate(predicate("part_int", Scalar::Integer(6))).unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            evaluate(predicate("part_int", Scalar::Integer(5))).unwrap(),
+            Some(false)
+        );
+        assert_eq!(evaluate(predicate("id", Scalar::Long(5))).unwrap(), None);
+
+        // empty string partition values are null, so no row can equal the empty string
+        let string_schema = Schema::new(vec![StructField::new(
+            "part_str",
+            DataType::Primitive(PrimitiveType::String),
+            true,
+        )]);
+        let empty = HashMap::from([("part_str".to_string(), String::new())]);
+        assert_eq!(
+            equal("part_str", Scalar::from(""))
+                .evaluate_partition(&empty, &string_schema)
+                .unwrap(),
+            Some(false)
+        );
+
+        // invalid partition values are reported
+        let values = HashMap::from([("part_int".to_string(), "five".to_string())]);
+        assert!(predicate("part_int", Scalar::Integer(6))
+            .evaluate_partition(&values, &schema())
+            .is_err());
+    }
+}
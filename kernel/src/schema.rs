@@ -116,6 +116,25 @@ impl StructField {
     pub const fn metadata(&self) -> &HashMap<String, MetadataValue> {
         &self.metadata
     }
+
+    /// The name of the column in the data files when column mapping is enabled, falling back
+    /// to the logical name if the field has no physical name.
+    pub fn physical_name(&self) -> &str {
+        match self.get_config_value(&ColumnMetadataKey::ColumnMappingPhysicalName) {
+            Some(MetadataValue::String(name)) => name,
+            _ => &self.name,
+        }
+    }
+
+    /// This field with the physical names of itself and all nested fields.
+    pub(crate) fn to_physical(&self) -> StructField {
+        StructField {
+            name: self.physical_name().to_string(),
+            data_type: self.data_type.to_physical(),
+            nullable: self.nullable,
+            metadata: self.metadata.clone(),
+        }
+    }
 }
 
 /// A struct is used to represent both the top-level schema of the table
@@ -139,6 +158,29 @@ impl StructType {
     pub fn fields(&self) -> Vec<&StructField> {
         self.fields.iter().collect()
     }
+
+    /// The direct child field called `name`, if any.
+    pub fn field(&self, name: &str) -> Option<&StructField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// The position of the direct child field called `name`, if any.
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name == name)
+    }
+
+    /// The (possibly nested) field referenced by `path`, descending into struct fields.
+    ///
+    /// Returns `None` for an empty path, or if any part of the path does not exist.
+    pub fn resolve_path(&self, path: &[&str]) -> Option<&StructField> {
+        let (name, rest) = path.split_first()?;
+        let field = self.field(name)?;
+        match (&field.data_type, rest.is_empty()) {
+            (_, true) => Some(field),
+            (DataType::Struct(inner), false) => inner.resolve_path(rest),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -217,7 +259,7 @@ fn default_true() -> bool {
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(try_from = "String", into = "String")]
 pub enum PrimitiveType {
     /// UTF-8 encoded string of characters
     String,
@@ -236,8 +278,68 @@ pub enum PrimitiveType {
     Boolean,
     Binary,
     Date,
+    /// Microsecond precision timestamp, adjusted to UTC.
     Timestamp,
-    Deciaml(String),
+    /// Microsecond precision timestamp with no timezone.
+    TimestampNtz,
+    /// Fixed-point decimal with the given precision (max 38) and scale.
+    Decimal(u8, u8),
+}
+
+impl TryFrom<String> for PrimitiveType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let primitive = match value.as_str() {
+            "string" => Self::String,
+            "long" => Self::Long,
+            "integer" => Self::Integer,
+            "short" => Self::Short,
+            "byte" => Self::Byte,
+            "float" => Self::Float,
+            "double" => Self::Double,
+            "boolean" => Self::Boolean,
+            "binary" => Self::Binary,
+            "date" => Self::Date,
+            "timestamp" => Self::Timestamp,
+            "timestamp_ntz" => Self::TimestampNtz,
+            decimal => {
+                let invalid = || format!("Invalid primitive type: {decimal}");
+                let (precision, scale) = decimal
+                    .strip_prefix("decimal(")
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .and_then(|rest| rest.split_once(','))
+                    .ok_or_else(invalid)?;
+                let precision: u8 = precision.trim().parse().map_err(|_| invalid())?;
+                let scale: u8 = scale.trim().parse().map_err(|_| invalid())?;
+                if precision == 0 || precision > 38 || scale > precision {
+                    return Err(invalid());
+                }
+                Self::Decimal(precision, scale)
+            }
+        };
+        Ok(primitive)
+    }
+}
+
+impl From<PrimitiveType> for String {
+    fn from(value: PrimitiveType) -> Self {
+        match value {
+            PrimitiveType::String => "string".into(),
+            PrimitiveType::Long => "long".into(),
+            PrimitiveType::Integer => "integer".into(),
+            PrimitiveType::Short => "short".into(),
+            PrimitiveType::Byte => "byte".into(),
+            PrimitiveType::Float => "float".into(),
+            PrimitiveType::Double => "double".into(),
+            PrimitiveType::Boolean => "boolean".into(),
+            PrimitiveType::Binary => "binary".into(),
+            PrimitiveType::Date => "date".into(),
+            PrimitiveType::Timestamp => "timestamp".into(),
+            PrimitiveType::TimestampNtz => "timestamp_ntz".into(),
+            PrimitiveType::Decimal(precision, scale) => format!("decimal({precision},{scale})"),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -255,11 +357,57 @@ pub enum DataType {
     Map(Box<MapType>),
 }
 
+impl DataType {
+    /// This type with the physical names of all nested fields.
+    fn to_physical(&self) -> DataType {
+        match self {
+            Self::Primitive(_) => self.clone(),
+            Self::Array(array) => Self::Array(Box::new(ArrayType::new(
+                array.element_type.to_physical(),
+                array.contains_null,
+            ))),
+            Self::Struct(inner) => Self::Struct(Box::new(StructType::new(
+                inner.fields.iter().map(StructField::to_physical).collect(),
+            ))),
+            Self::Map(map) => Self::Map(Box::new(MapType::new(
+                map.key_type.to_physical(),
+                map.value_type.to_physical(),
+                map.value_contains_null,
+            ))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json;
 
+    #[test]
+    fn test_field_lookup() {
+        let long = || DataType::Primitive(PrimitiveType::Long);
+        let inner = StructType::new(vec![
+            StructField::new("x", long(), true),
+            StructField::new("y", long(), true),
+        ]);
+        let schema = StructType::new(vec![
+            StructField::new("a", long(), true),
+            StructField::new("b", DataType::Struct(Box::new(inner)), true),
+        ]);
+
+        assert_eq!(schema.field("b").map(|f| f.name()), Some(&"b".to_string()));
+        assert!(schema.field("x").is_none());
+        assert_eq!(schema.field_index("b"), Some(1));
+        assert_eq!(schema.field_index("c"), None);
+
+        assert_eq!(schema.resolve_path(&["a"]).unwrap().data_type, long());
+        let nested = schema.resolve_path(&["b", "y"]).unwrap();
+        assert_eq!(nested.name(), "y");
+        assert!(schema.resolve_path(&["b", "z"]).is_none());
+        assert!(schema.resolve_path(&["a", "x"]).is_none());
+        assert!(schema.resolve_path(&[]).is_none());
+    }
+
     #[test]
     fn test_serde_data_types() {
         let data = r#"
@@ -381,6 +529,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serde_primitive_types() {
+        for (name, primitive) in [
+            ("\"timestamp\"", PrimitiveType::Timestamp),
+            ("\"timestamp_ntz\"", PrimitiveType::TimestampNtz),
+            ("\"decimal(10,2)\"", PrimitiveType::Decimal(10, 2)),
+            ("\"decimal(38,0)\"", PrimitiveType::Decimal(38, 0)),
+        ] {
+            let data_type: DataType = serde_json::from_str(name).unwrap();
+            assert_eq!(data_type, DataType::Primitive(primitive));
+            assert_eq!(serde_json::to_string(&data_type).unwrap(), name);
+        }
+
+        let data_type: DataType = serde_json::from_str("\"decimal(5, 3)\"").unwrap();
+        assert_eq!(data_type, DataType::Primitive(PrimitiveType::Decimal(5, 3)));
+
+        for invalid in [
+            "\"decimal(39,0)\"",
+            "\"decimal(2,3)\"",
+            "\"decimal(10)\"",
+            "\"varchar\"",
+        ] {
+            assert!(serde_json::from_str::<DataType>(invalid).is_err());
+        }
+    }
+
     #[test]
     fn test_read_schemas() {
         let file = std::fs::File::open("./tests/serde/schema.json").unwrap();
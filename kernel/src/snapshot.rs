@@ -2,25 +2,38 @@
 //! has schema etc.)
 //!
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use arrow_array::RecordBatch;
+use arrow_array::cast::AsArray;
+use arrow_array::types::Int64Type;
+use arrow_array::{Array, RecordBatch, StructArray};
 use arrow_schema::{Fields, Schema as ArrowSchema};
+use futures::stream::BoxStream;
 use futures::{StreamExt, TryStreamExt};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::actions::{parse_action, Action, ActionType, Metadata, Protocol};
+use crate::actions::{
+    get_log_schema, parse_action, parse_actions, Action, ActionType, Metadata, Protocol, Remove,
+};
 use crate::path::LogPath;
-use crate::scan::ScanBuilder;
-use crate::schema::Schema;
+use crate::scan::{stats_columns, ScanBuilder};
+use crate::schema::{ColumnMetadataKey, DataType, MetadataValue, PrimitiveType, Schema};
+use crate::table_changes::list_commit_files;
 use crate::{DeltaResult, Error, FileMeta, FileSystemClient, TableClient, Version};
 
 const LAST_CHECKPOINT_FILE_NAME: &str = "_last_checkpoint";
+/// Prefix of the table properties holding the expressions of CHECK constraints.
+const CHECK_CONSTRAINT_KEY_PREFIX: &str = "delta.constraints.";
+/// The versions of the Iceberg compatibility features, enabled by the table property
+/// `delta.enableIcebergCompatV<version>`.
+const ICEBERG_COMPAT_VERSIONS: [u32; 2] = [1, 2];
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LogSegment {
     log_root: Url,
     /// Reverse order soprted commit files in the log segment
@@ -34,13 +47,17 @@ impl LogSegment {
         self.commit_files.iter()
     }
 
-    // TODO just a stop gap implementation, eventually we likely want a stream of batches...
-    async fn replay<JRC: Send, PRC: Send>(
+    /// Stream the `action_types` of the log segment, newest commit first.
+    ///
+    /// Files are only read as the stream is polled, so consumers can stop reading the log once
+    /// they found the actions they are looking for.
+    fn replay<JRC: Send, PRC: Send>(
         &self,
         table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
-    ) -> DeltaResult<Vec<RecordBatch>> {
+        action_types: &[ActionType],
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<RecordBatch>>> {
         let read_schema = Arc::new(ArrowSchema {
-            fields: Fields::from_iter([ActionType::Metadata.field(), ActionType::Protocol.field()]),
+            fields: Fields::from_iter(action_types.iter().map(|action| action.field())),
             metadata: Default::default(),
         });
 
@@ -58,37 +75,73 @@ impl LogSegment {
         let checkpoint_stream = parquet_client
             .read_parquet_files(read_contexts, Arc::new(read_schema.clone().try_into()?))?;
 
-        let batches = commit_stream
-            .chain(checkpoint_stream)
-            .try_collect::<Vec<_>>()
-            .await?;
+        Ok(commit_stream.chain(checkpoint_stream).boxed())
+    }
+
+    /// Stream the `action_types` of the log segment like [`LogSegment::replay`], but yield all
+    /// batches of a commit file together, so the actions of a commit can be reconciled with each
+    /// other. Checkpoint batches are yielded one by one, as a checkpoint holds at most one action
+    /// per file.
+    fn replay_commits<JRC: Send, PRC: Send>(
+        &self,
+        table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+        action_types: &[ActionType],
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<Vec<RecordBatch>>>> {
+        let read_schema = ArrowSchema {
+            fields: Fields::from_iter(action_types.iter().map(|action| action.field())),
+            metadata: Default::default(),
+        };
+        let read_schema = Arc::new(Schema::try_from(&read_schema)?);
 
-        Ok(batches)
+        let mut commit_files: Vec<_> = self.commit_files().cloned().collect();
+        commit_files.sort_unstable_by(|a, b| b.location.cmp(&a.location));
+        let json_client = table_client.get_json_handler();
+        let commits = commit_files
+            .into_iter()
+            .map(|file| {
+                let read_contexts = json_client.contextualize_file_reads(vec![file], None)?;
+                Ok(json_client
+                    .read_json_files(read_contexts, read_schema.clone())?
+                    .try_collect::<Vec<_>>())
+            })
+            .collect::<DeltaResult<Vec<_>>>()?;
+
+        let parquet_client = table_client.get_parquet_handler();
+        let read_contexts =
+            parquet_client.contextualize_file_reads(self.checkpoint_files.clone(), None)?;
+        let checkpoint_stream = parquet_client
+            .read_parquet_files(read_contexts, read_schema)?
+            .map_ok(|batch| vec![batch]);
+
+        Ok(futures::stream::iter(commits)
+            .then(|commit| commit)
+            .chain(checkpoint_stream)
+            .boxed())
     }
 
     async fn read_metadata<JRC: Send, PRC: Send>(
         &self,
         table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
     ) -> DeltaResult<Option<(Metadata, Protocol)>> {
-        let batches = self.replay(table_client).await?;
+        let mut batches =
+            self.replay(table_client, &[ActionType::Metadata, ActionType::Protocol])?;
         let mut metadata_opt = None;
         let mut protocol_opt = None;
-        for batch in batches {
-            if let Ok(mut metas) = parse_action(&batch, &ActionType::Metadata) {
-                match metas.next() {
-                    Some(Action::Metadata(meta)) => {
-                        metadata_opt = Some(meta.clone());
+        while let Some(batch) = batches.try_next().await? {
+            // the log is read newest first, so the first action found is the latest one
+            if metadata_opt.is_none() {
+                if let Ok(mut metas) = parse_action(&batch, &ActionType::Metadata) {
+                    if let Some(Action::Metadata(meta)) = metas.next() {
+                        metadata_opt = Some(meta);
                     }
-                    _ => (),
                 }
             }
 
-            if let Ok(mut protos) = parse_action(&batch, &ActionType::Protocol) {
-                match protos.next() {
-                    Some(Action::Protocol(proto)) => {
-                        protocol_opt = Some(proto.clone());
+            if protocol_opt.is_none() {
+                if let Ok(mut protos) = parse_action(&batch, &ActionType::Protocol) {
+                    if let Some(Action::Protocol(proto)) = protos.next() {
+                        protocol_opt = Some(proto);
                     }
-                    _ => (),
                 }
             }
 
@@ -98,6 +151,193 @@ impl LogSegment {
         }
         Ok(None)
     }
+
+    async fn app_transaction_version<JRC: Send, PRC: Send>(
+        &self,
+        table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+        app_id: &str,
+    ) -> DeltaResult<Option<i64>> {
+        let mut batches = self.replay(table_client, &[ActionType::Txn])?;
+        let mut version = None;
+        while let Some(batch) = batches.try_next().await? {
+            for action in parse_actions(&batch, &[ActionType::Txn])? {
+                match action {
+                    Action::Txn(txn) if txn.app_id == app_id => {
+                        version = version.max(Some(txn.version))
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(version)
+    }
+
+    /// The configuration of the latest metadata action for `domain`, or `None` if the domain
+    /// does not exist or was removed.
+    async fn domain_configuration<JRC: Send, PRC: Send>(
+        &self,
+        table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+        domain: &str,
+    ) -> DeltaResult<Option<String>> {
+        let mut batches = self.replay(table_client, &[ActionType::DomainMetadata])?;
+        while let Some(batch) = batches.try_next().await? {
+            for action in parse_actions(&batch, &[ActionType::DomainMetadata])? {
+                match action {
+                    // the log is read newest first, so the first action for the domain is active
+                    Action::DomainMetadata(metadata) if metadata.domain == domain => {
+                        return Ok((!metadata.removed).then_some(metadata.configuration));
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// The latest remove actions of files that are not part of the table anymore and were
+    /// removed before `cutoff`, in milliseconds since the Unix epoch.
+    async fn tombstones_before<JRC: Send, PRC: Send>(
+        &self,
+        table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+        cutoff: i64,
+    ) -> DeltaResult<Vec<Remove>> {
+        let mut commits =
+            self.replay_commits(table_client, &[ActionType::Add, ActionType::Remove])?;
+        // the log is read newest first, so only the first action seen for a path is relevant.
+        // the adds of a commit are seen before its removes, so files removed and added again in
+        // a commit are still live, even if the commit is read in multiple batches.
+        let mut seen = HashSet::new();
+        let mut tombstones = Vec::new();
+        while let Some(batches) = commits.try_next().await? {
+            for batch in &batches {
+                for action in parse_actions(batch, &[ActionType::Add])? {
+                    if let Action::Add(add) = action {
+                        seen.insert(add.path);
+                    }
+                }
+            }
+            for batch in &batches {
+                for action in parse_actions(batch, &[ActionType::Remove])? {
+                    match action {
+                        Action::Remove(remove)
+                            if seen.insert(remove.path.clone())
+                                && remove.deletion_timestamp.unwrap_or(0) < cutoff =>
+                        {
+                            tombstones.push(remove);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+        Ok(tombstones)
+    }
+
+    /// The number of live files and the sum of their sizes in bytes.
+    async fn live_files_size<JRC: Send, PRC: Send>(
+        &self,
+        table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+    ) -> DeltaResult<(usize, i64)> {
+        let mut commits =
+            self.replay_commits(table_client, &[ActionType::Add, ActionType::Remove])?;
+        // files are identified by path and deletion vector, as during scan log replay. the adds
+        // of a commit are seen before its removes, as in `tombstones_before`.
+        let mut seen = HashSet::new();
+        let (mut num_files, mut total_bytes) = (0, 0);
+        while let Some(batches) = commits.try_next().await? {
+            for batch in &batches {
+                for action in parse_actions(batch, &[ActionType::Add])? {
+                    match action {
+                        Action::Add(add) if seen.insert((add.path.clone(), add.dv_unique_id())) => {
+                            num_files += 1;
+                            total_bytes += add.size;
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            for batch in &batches {
+                for action in parse_actions(batch, &[ActionType::Remove])? {
+                    if let Action::Remove(remove) = action {
+                        seen.insert((remove.path.clone(), remove.dv_unique_id()));
+                    }
+                }
+            }
+        }
+        Ok((num_files, total_bytes))
+    }
+}
+
+/// The log metadata of one version of a table, each part is read at most once.
+#[derive(Debug, Default)]
+struct LogCacheEntry {
+    metadata: futures::lock::Mutex<Option<(Metadata, Protocol)>>,
+    /// The replayed add and remove actions, newest first
+    file_actions: futures::lock::Mutex<Option<Vec<RecordBatch>>>,
+}
+
+/// Cache of the log of tables, shared between the snapshots and scans created with a
+/// [`TableClient`].
+///
+/// Caches the table [`Metadata`] and [`Protocol`] as well as the add and remove actions
+/// replayed by [`Scan::files`]. Entries are keyed by table root and version. Only the latest
+/// cached version of each table is retained, so an entry is invalidated once a snapshot of a
+/// newer version is created.
+///
+/// [`Scan::files`]: crate::scan::Scan::files
+#[derive(Debug, Default)]
+pub struct LogCache {
+    entries: Mutex<HashMap<Url, (Version, Arc<LogCacheEntry>)>>,
+}
+
+impl LogCache {
+    /// Create a new, empty [`LogCache`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the entry for `version` of the table at `table_root`, creating it if the version is
+    /// not older than the one cached. Older versions are not cached.
+    fn entry(&self, table_root: &Url, version: Version) -> DeltaResult<Option<Arc<LogCacheEntry>>> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| Error::Generic("failed to get log cache lock".into()))?;
+        match entries.get(table_root) {
+            Some((cached, entry)) if *cached == version => Ok(Some(entry.clone())),
+            Some((cached, _)) if *cached > version => Ok(None),
+            _ => {
+                let entry = Arc::new(LogCacheEntry::default());
+                entries.insert(table_root.clone(), (version, entry.clone()));
+                Ok(Some(entry))
+            }
+        }
+    }
+
+    /// Stream the add and remove actions of `version` of the table at `table_root`.
+    ///
+    /// The `replay` stream of the actions is only polled if they are not cached yet, in which
+    /// case it is read to the end and its batches are cached. Concurrent scans of the same
+    /// version wait for the first one to replay the log.
+    pub(crate) fn file_actions(
+        &self,
+        table_root: &Url,
+        version: Version,
+        replay: BoxStream<'static, DeltaResult<RecordBatch>>,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<RecordBatch>>> {
+        let Some(entry) = self.entry(table_root, version)? else {
+            return Ok(replay);
+        };
+        let batches = async move {
+            let mut cached = entry.file_actions.lock().await;
+            let batches = match cached.as_ref() {
+                Some(batches) => batches.clone(),
+                None => cached.insert(replay.try_collect().await?).clone(),
+            };
+            Ok::<_, Error>(futures::stream::iter(batches.into_iter().map(Ok)))
+        };
+        Ok(futures::stream::once(batches).try_flatten().boxed())
+    }
 }
 
 // TODO expose methods for accessing the files of a table (with file pruning).
@@ -111,6 +351,8 @@ pub struct Snapshot<JRC: Send, PRC: Send> {
     log_segment: LogSegment,
     version: Version,
     metadata: Arc<RwLock<Option<(Metadata, Protocol)>>>,
+    /// The number of live files and their total size, computed on first use
+    files_size: futures::lock::Mutex<Option<(usize, i64)>>,
 }
 
 impl<JRC: Send, PRC: Send> std::fmt::Debug for Snapshot<JRC, PRC> {
@@ -135,28 +377,36 @@ impl<JRC: Send, PRC: Send + Sync> Snapshot<JRC, PRC> {
         table_root: Url,
         table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
         version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        Self::try_new_with_checkpoint_validation(
+            table_root,
+            table_client,
+            version,
+            CheckpointValidation::Skip,
+        )
+        .await
+    }
+
+    /// Create a new [`Snapshot`] instance for the given version, validating the checkpoint
+    /// referenced by the `_last_checkpoint` file against its recorded `size` and
+    /// `numOfAddFiles`, see [`CheckpointValidation`].
+    pub async fn try_new_with_checkpoint_validation(
+        table_root: Url,
+        table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
+        version: Option<Version>,
+        validation: CheckpointValidation,
     ) -> DeltaResult<Self> {
         let fs_client = table_client.get_file_system_client();
         let log_url = LogPath(&table_root).child("_delta_log/").unwrap();
 
         // List relevant files from log
-        let (mut commit_files, checkpoint_files) =
-            match read_last_checkpoint(fs_client.as_ref(), &log_url).await {
-                Err(err) => return Err(err),
-                Ok(Some(cp)) => {
-                    if let Some(v) = version {
-                        if cp.version >= v {
-                            list_log_files_with_checkpoint(&cp, fs_client.as_ref(), &log_url)
-                                .await?
-                        } else {
-                            list_log_files(fs_client.as_ref(), &log_url).await?
-                        }
-                    } else {
-                        list_log_files(fs_client.as_ref(), &log_url).await?
-                    }
-                }
-                Ok(None) => list_log_files(fs_client.as_ref(), &log_url).await?,
-            };
+        let last_checkpoint = read_last_checkpoint(fs_client.as_ref(), &log_url).await?;
+        let (mut commit_files, mut checkpoint_files) = match (&last_checkpoint, version) {
+            (Some(cp), Some(v)) if cp.version >= v => {
+                list_log_files_with_checkpoint(cp, fs_client.as_ref(), &log_url).await?
+            }
+            _ => list_log_files(fs_client.as_ref(), &log_url).await?,
+        };
 
         // remove all files above requested version
         if let Some(version) = version {
@@ -192,6 +442,36 @@ impl<JRC: Send, PRC: Send + Sync> Snapshot<JRC, PRC> {
             }
         }
 
+        let checkpoint_version = checkpoint_files
+            .first()
+            .and_then(|f| LogPath(&f.location).commit_version());
+        match last_checkpoint {
+            Some(cp)
+                if validation != CheckpointValidation::Skip
+                    && checkpoint_version == Some(cp.version) =>
+            {
+                match validate_checkpoint(table_client.as_ref(), &cp, &checkpoint_files).await {
+                    Err(Error::CorruptCheckpoint(_))
+                        if validation == CheckpointValidation::FallbackToReplay =>
+                    {
+                        let all_commits = list_commit_files(
+                            table_client.as_ref(),
+                            &log_url,
+                            0,
+                            Some(version_eff),
+                        )
+                        .await?;
+                        commit_files = all_commits.into_iter().rev().map(|(_, f)| f).collect();
+                        checkpoint_files = Vec::new();
+                    }
+                    result => result?,
+                }
+            }
+            _ => (),
+        }
+
+        let checkpoint_files =
+            resolve_sidecars(table_client.as_ref(), &log_url, checkpoint_files).await?;
         let log_segment = LogSegment {
             log_root: log_url,
             commit_files,
@@ -204,9 +484,44 @@ impl<JRC: Send, PRC: Send + Sync> Snapshot<JRC, PRC> {
             log_segment,
             version: version_eff,
             metadata: Default::default(),
+            files_size: Default::default(),
         })
     }
 
+    /// Create a [`Snapshot`] of the latest version of the table whose commit can be read.
+    ///
+    /// A commit which is still being written may be listed with incomplete content. If the
+    /// latest commit file is corrupt, it is treated as not yet committed and the snapshot is
+    /// created for the previous version instead. A corrupt commit preceding a readable one is
+    /// always reported as [`Error::CorruptCommit`].
+    pub async fn try_new_latest_committed(
+        table_root: Url,
+        table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
+    ) -> DeltaResult<Self> {
+        let snapshot = Self::try_new(table_root.clone(), table_client.clone(), None).await?;
+        match snapshot.check_latest_commit().await {
+            Err(Error::CorruptCommit { version, .. }) if version > 0 => {
+                let snapshot = Self::try_new(table_root, table_client, Some(version - 1)).await?;
+                snapshot.check_latest_commit().await?;
+                Ok(snapshot)
+            }
+            result => result.map(|_| snapshot),
+        }
+    }
+
+    /// Read the latest commit of the log segment, reporting unparsable content as
+    /// [`Error::CorruptCommit`].
+    async fn check_latest_commit(&self) -> DeltaResult<()> {
+        let Some(commit_file) = self.log_segment.commit_files.first() else {
+            return Ok(());
+        };
+        let Some(version) = LogPath(&commit_file.location).commit_version() else {
+            return Ok(());
+        };
+        read_commit(self.table_client.as_ref(), commit_file.clone(), version).await?;
+        Ok(())
+    }
+
     /// Create a new [`Snapshot`] instance.
     pub fn new(
         location: Url,
@@ -220,6 +535,7 @@ impl<JRC: Send, PRC: Send + Sync> Snapshot<JRC, PRC> {
             log_segment,
             version,
             metadata: Default::default(),
+            files_size: Default::default(),
         }
     }
 
@@ -229,20 +545,17 @@ impl<JRC: Send, PRC: Send + Sync> Snapshot<JRC, PRC> {
     }
 
     async fn get_or_insert_metadata(&self) -> DeltaResult<(Metadata, Protocol)> {
-        let read_lock = self
-            .metadata
-            .read()
-            .map_err(|_| Error::Generic("filed to get read lock".into()))?;
-        if let Some((metadata, protocol)) = read_lock.as_ref() {
-            return Ok((metadata.clone(), protocol.clone()));
+        {
+            let read_lock = self
+                .metadata
+                .read()
+                .map_err(|_| Error::Generic("filed to get read lock".into()))?;
+            if let Some((metadata, protocol)) = read_lock.as_ref() {
+                return Ok((metadata.clone(), protocol.clone()));
+            }
         }
-        drop(read_lock);
 
-        let (metadata, protocol) = self
-            .log_segment
-            .read_metadata(self.table_client.as_ref())
-            .await?
-            .ok_or(Error::MissingMetadata)?;
+        let (metadata, protocol) = self.read_metadata().await?;
         let mut meta = self
             .metadata
             .write()
@@ -252,6 +565,30 @@ impl<JRC: Send, PRC: Send + Sync> Snapshot<JRC, PRC> {
         Ok((metadata, protocol))
     }
 
+    async fn read_metadata(&self) -> DeltaResult<(Metadata, Protocol)> {
+        let read = || async {
+            self.log_segment
+                .read_metadata(self.table_client.as_ref())
+                .await?
+                .ok_or(Error::MissingMetadata)
+        };
+        let entry = match self.table_client.get_log_cache() {
+            Some(cache) => cache.entry(&self.table_root, self.version)?,
+            None => None,
+        };
+        let Some(entry) = entry else {
+            return read().await;
+        };
+        // the entry stays locked while reading, so concurrent snapshots read the log only once
+        let mut cached = entry.metadata.lock().await;
+        if let Some(cached) = cached.as_ref() {
+            return Ok(cached.clone());
+        }
+        let read = read().await?;
+        *cached = Some(read.clone());
+        Ok(read)
+    }
+
     /// Table [`Schema`] at this [`Snapshot`]s version.
     pub async fn schema(&self) -> DeltaResult<Schema> {
         self.metadata().await?.schema()
@@ -268,17 +605,247 @@ impl<JRC: Send, PRC: Send + Sync> Snapshot<JRC, PRC> {
         Ok(protocol)
     }
 
-    pub async fn scan(self) -> DeltaResult<ScanBuilder<JRC, PRC>> {
-        let schema = Arc::new(self.schema().await?);
+    /// Names of the columns the table is partitioned by at this [`Snapshot`]s version, in the
+    /// order they are listed in the metadata.
+    ///
+    /// Empty for tables that are not partitioned.
+    pub async fn partition_columns(&self) -> DeltaResult<Vec<String>> {
+        Ok(self.metadata().await?.partition_columns)
+    }
+
+    /// The leaf columns for which writers collect file statistics at this [`Snapshot`]s version,
+    /// as dot separated paths, e.g. `s.a` for the field `a` of the struct column `s`.
+    ///
+    /// Taken from the `delta.dataSkippingStatsColumns` table property if set, or else the first
+    /// `delta.dataSkippingNumIndexedCols` (32 by default) leaf columns of the schema. Predicates
+    /// on other columns can not be used to skip files, see [`Scan::skipping_applicability`].
+    ///
+    /// [`Scan::skipping_applicability`]: crate::scan::Scan::skipping_applicability
+    pub async fn stats_columns(&self) -> DeltaResult<Vec<String>> {
+        let metadata = self.metadata().await?;
+        Ok(stats_columns(&metadata.schema()?, &metadata.configuration))
+    }
+
+    /// Table properties at this [`Snapshot`]s version.
+    ///
+    /// Properties without a value are omitted.
+    pub async fn configuration(&self) -> DeltaResult<HashMap<String, String>> {
+        Ok(self
+            .metadata()
+            .await?
+            .configuration
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .collect())
+    }
+
+    /// The value of the boolean table property `key` at this [`Snapshot`]s version.
+    ///
+    /// See [`Metadata::config_bool`].
+    pub async fn config_bool(&self, key: &str) -> DeltaResult<Option<bool>> {
+        self.metadata().await?.config_bool(key)
+    }
+
+    /// The version of the Iceberg compatibility feature enabled for the table at this
+    /// [`Snapshot`]s version, or `None` if the table is not Iceberg compatible.
+    ///
+    /// Iceberg compatible tables are written with additional constraints, e.g. column mapping
+    /// is enabled and statistics are only written for primitive columns. These constraints do
+    /// not change how the table is read, statistics of nested columns are never used for
+    /// skipping files.
+    pub async fn iceberg_compat_version(&self) -> DeltaResult<Option<u32>> {
+        let metadata = self.metadata().await?;
+        for version in ICEBERG_COMPAT_VERSIONS.into_iter().rev() {
+            let key = format!("delta.enableIcebergCompatV{version}");
+            if metadata.config_bool(&key)? == Some(true) {
+                return Ok(Some(version));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The SQL expressions of the generated columns at this [`Snapshot`]s version, keyed by
+    /// column name. Fields of nested structs are keyed by their dot separated path, e.g. `s.a`.
+    ///
+    /// Expressions are returned as stored in the column metadata and are not parsed.
+    pub async fn generated_columns(&self) -> DeltaResult<HashMap<String, String>> {
+        fn collect(schema: &Schema, prefix: &str, generated: &mut HashMap<String, String>) {
+            for field in schema.fields() {
+                let path = format!("{prefix}{}", field.name);
+                if let Some(MetadataValue::String(expr)) =
+                    field.get_config_value(&ColumnMetadataKey::GenerationExpression)
+                {
+                    generated.insert(path.clone(), expr.clone());
+                }
+                if let DataType::Struct(nested) = &field.data_type {
+                    collect(nested, &format!("{path}."), generated);
+                }
+            }
+        }
+        let mut generated = HashMap::new();
+        collect(&self.schema().await?, "", &mut generated);
+        Ok(generated)
+    }
+
+    /// The SQL expressions of the CHECK constraints at this [`Snapshot`]s version, keyed by
+    /// constraint name.
+    ///
+    /// Constraints are stored as `delta.constraints.<name>` table properties. Expressions are
+    /// returned as stored and are not parsed.
+    pub async fn check_constraints(&self) -> DeltaResult<HashMap<String, String>> {
+        Ok(self
+            .configuration()
+            .await?
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let name = key.strip_prefix(CHECK_CONSTRAINT_KEY_PREFIX)?;
+                Some((name.to_string(), value))
+            })
+            .collect())
+    }
+
+    /// The latest version of the application transaction `app_id` committed to the table at
+    /// this [`Snapshot`]s version, or `None` if the application never committed a transaction.
+    ///
+    /// Writers use this to make their commits idempotent, e.g. streaming sinks for exactly-once
+    /// delivery.
+    pub async fn app_transaction_version(&self, app_id: &str) -> DeltaResult<Option<i64>> {
+        self.log_segment
+            .app_transaction_version(self.table_client.as_ref(), app_id)
+            .await
+    }
+
+    /// The configuration of the metadata domain `domain` at this [`Snapshot`]s version, or
+    /// `None` if the domain does not exist or was removed.
+    ///
+    /// The format of the configuration is defined by the owner of the domain, e.g. the
+    /// `delta.clustering` domain stores the clustering columns as JSON.
+    pub async fn domain_metadata(&self, domain: &str) -> DeltaResult<Option<String>> {
+        self.log_segment
+            .domain_configuration(self.table_client.as_ref(), domain)
+            .await
+    }
+
+    /// The remove actions of files that are not part of the table at this [`Snapshot`]s
+    /// version and were removed more than `retention_millis` milliseconds ago.
+    ///
+    /// These are the files that can be deleted when vacuuming the table. Files that were
+    /// removed and added again are still live and are not reported. Removes without a
+    /// deletion timestamp are considered expired.
+    pub async fn expired_tombstones(&self, retention_millis: i64) -> DeltaResult<Vec<Remove>> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Generic(err.to_string()))?
+            .as_millis() as i64;
+        self.log_segment
+            .tombstones_before(self.table_client.as_ref(), now - retention_millis)
+            .await
+    }
+
+    /// The number of files that are part of the table at this [`Snapshot`]s version.
+    ///
+    /// The add and remove actions of the log are replayed on first use of this method or
+    /// [`Snapshot::total_bytes`], and the result is kept for subsequent calls.
+    pub async fn num_files(&self) -> DeltaResult<usize> {
+        let (num_files, _) = self.live_files_size().await?;
+        Ok(num_files)
+    }
+
+    /// The total size in bytes of the files that are part of the table at this [`Snapshot`]s
+    /// version, as recorded in their add actions.
+    ///
+    /// Shares the log replay with [`Snapshot::num_files`].
+    pub async fn total_bytes(&self) -> DeltaResult<i64> {
+        let (_, total_bytes) = self.live_files_size().await?;
+        Ok(total_bytes)
+    }
+
+    async fn live_files_size(&self) -> DeltaResult<(usize, i64)> {
+        let mut cached = self.files_size.lock().await;
+        if let Some(files_size) = *cached {
+            return Ok(files_size);
+        }
+        let files_size = self
+            .log_segment
+            .live_files_size(self.table_client.as_ref())
+            .await?;
+        Ok(*cached.insert(files_size))
+    }
+
+    /// Create a [`ScanBuilder`] for this [`Snapshot`].
+    ///
+    /// The snapshot is not consumed, so multiple scans can be created from the same snapshot.
+    ///
+    /// Tables with `timestamp_ntz` columns must support the `timestampNtz` reader feature.
+    pub async fn scan(&self) -> DeltaResult<ScanBuilder<JRC, PRC>> {
+        let (metadata, protocol) = self.get_or_insert_metadata().await?;
+        let schema = Arc::new(metadata.schema()?);
+        check_timestamp_ntz_support(&schema, &protocol)?;
         Ok(ScanBuilder::new(
-            self.table_root,
+            self.table_root.clone(),
             schema,
-            self.log_segment,
-            self.table_client,
+            metadata.partition_columns,
+            &metadata.configuration,
+            self.log_segment.clone(),
+            self.version,
+            self.table_client.clone(),
         ))
     }
 }
 
+const TIMESTAMP_NTZ_FEATURE: &str = "timestampNtz";
+
+/// Check that the protocol enables the `timestampNtz` reader feature if the schema contains
+/// `timestamp_ntz` columns.
+fn check_timestamp_ntz_support(schema: &Schema, protocol: &Protocol) -> DeltaResult<()> {
+    fn contains_timestamp_ntz(data_type: &DataType) -> bool {
+        match data_type {
+            DataType::Primitive(primitive) => *primitive == PrimitiveType::TimestampNtz,
+            DataType::Array(array) => contains_timestamp_ntz(&array.element_type),
+            DataType::Struct(inner) => inner
+                .fields
+                .iter()
+                .any(|field| contains_timestamp_ntz(&field.data_type)),
+            DataType::Map(map) => {
+                contains_timestamp_ntz(&map.key_type) || contains_timestamp_ntz(&map.value_type)
+            }
+        }
+    }
+
+    let Some(field) = schema
+        .fields
+        .iter()
+        .find(|field| contains_timestamp_ntz(&field.data_type))
+    else {
+        return Ok(());
+    };
+    let supported = protocol
+        .reader_features
+        .iter()
+        .flatten()
+        .any(|feature| feature == TIMESTAMP_NTZ_FEATURE);
+    if !supported {
+        return Err(Error::Generic(format!(
+            "Column '{}' is of type timestamp_ntz, but the table does not support the {} reader feature",
+            field.name, TIMESTAMP_NTZ_FEATURE
+        )));
+    }
+    Ok(())
+}
+
+/// How [`Snapshot::try_new_with_checkpoint_validation`] handles a checkpoint which does not
+/// match the `_last_checkpoint` file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CheckpointValidation {
+    /// Trust the `_last_checkpoint` file without reading the checkpoint.
+    #[default]
+    Skip,
+    /// Return [`Error::CorruptCheckpoint`] if the checkpoint does not match.
+    Error,
+    /// Ignore a checkpoint which does not match and replay all commits of the log instead.
+    FallbackToReplay,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CheckpointMetadata {
@@ -298,6 +865,88 @@ pub struct CheckpointMetadata {
     pub checksum: Option<String>,
 }
 
+/// Read all actions of the commit file of `version`.
+///
+/// Content which can not be parsed, e.g. because the commit file was only partially written,
+/// is reported as [`Error::CorruptCommit`].
+pub(crate) async fn read_commit<JRC: Send, PRC: Send>(
+    table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+    commit_file: FileMeta,
+    version: Version,
+) -> DeltaResult<Vec<RecordBatch>> {
+    let json_handler = table_client.get_json_handler();
+    let read_contexts = json_handler.contextualize_file_reads(vec![commit_file], None)?;
+    let schema = Arc::new(Schema::try_from(&get_log_schema())?);
+    json_handler
+        .read_json_files(read_contexts, schema)?
+        .try_collect::<Vec<_>>()
+        .await
+        .map_err(|err| match err {
+            Error::Arrow(_) | Error::MalformedJson(_) => Error::CorruptCommit {
+                version,
+                source: Box::new(err),
+            },
+            err => err,
+        })
+}
+
+/// Check that the classic checkpoint in `checkpoint_files` contains as many actions and add
+/// actions as recorded in the `_last_checkpoint` file.
+///
+/// V2 checkpoints are not validated, as their actions may be spread across sidecar files.
+async fn validate_checkpoint<JRC: Send, PRC: Send>(
+    table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+    cp: &CheckpointMetadata,
+    checkpoint_files: &[FileMeta],
+) -> DeltaResult<()> {
+    if checkpoint_files
+        .iter()
+        .any(|f| LogPath(&f.location).is_v2_checkpoint_file())
+    {
+        return Ok(());
+    }
+    let parts = cp.parts.unwrap_or(1) as usize;
+    if checkpoint_files.len() != parts {
+        return Err(Error::CorruptCheckpoint(format!(
+            "expected {} checkpoint parts for version {}, found {}",
+            parts,
+            cp.version,
+            checkpoint_files.len()
+        )));
+    }
+
+    let read_schema = ArrowSchema {
+        fields: Fields::from_iter([ActionType::Add.field()]),
+        metadata: Default::default(),
+    };
+    let parquet_client = table_client.get_parquet_handler();
+    let read_contexts = parquet_client.contextualize_file_reads(checkpoint_files.to_vec(), None)?;
+    let mut batches = parquet_client
+        .read_parquet_files(read_contexts, Arc::new(Schema::try_from(&read_schema)?))?;
+    let (mut num_actions, mut num_adds) = (0, 0);
+    while let Some(batch) = batches.try_next().await? {
+        num_actions += batch.num_rows();
+        num_adds += batch
+            .column_by_name("add")
+            .map(|adds| adds.len() - adds.null_count())
+            .unwrap_or_default();
+    }
+
+    if num_actions != cp.size as usize {
+        return Err(Error::CorruptCheckpoint(format!(
+            "expected {} actions in checkpoint of version {}, found {}",
+            cp.size, cp.version, num_actions
+        )));
+    }
+    match cp.num_of_add_files {
+        Some(expected) if expected as usize != num_adds => Err(Error::CorruptCheckpoint(format!(
+            "expected {} add actions in checkpoint of version {}, found {}",
+            expected, cp.version, num_adds
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Try reading the `_last_checkpoint` file.
 ///
 /// In case the file is not found, `None` is returned.
@@ -316,6 +965,74 @@ async fn read_last_checkpoint(
     }
 }
 
+/// Replace V2 checkpoints among the `checkpoint_files` by the checkpoint and its sidecar files.
+///
+/// The add and remove actions of a V2 checkpoint may be stored in sidecar files, which are
+/// referenced by the checkpoint's sidecar actions. Only parquet V2 checkpoints are supported.
+async fn resolve_sidecars<JRC: Send, PRC: Send>(
+    table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+    log_root: &Url,
+    checkpoint_files: Vec<FileMeta>,
+) -> DeltaResult<Vec<FileMeta>> {
+    // a classic checkpoint may be written alongside the V2 checkpoint for compatibility
+    let Some(checkpoint) = checkpoint_files
+        .iter()
+        .find(|f| LogPath(&f.location).is_v2_checkpoint_file())
+        .cloned()
+    else {
+        return Ok(checkpoint_files);
+    };
+    if LogPath(&checkpoint.location).extension() != Some("parquet") {
+        return Err(Error::UnsupportedFeature(format!(
+            "v2Checkpoint with non-parquet checkpoint file {}",
+            checkpoint.location
+        )));
+    }
+
+    let read_schema = ArrowSchema {
+        fields: Fields::from_iter([ActionType::Sidecar.field()]),
+        metadata: Default::default(),
+    };
+    let parquet_client = table_client.get_parquet_handler();
+    let read_contexts = parquet_client.contextualize_file_reads(vec![checkpoint.clone()], None)?;
+    let batches = parquet_client
+        .read_parquet_files(read_contexts, Arc::new(Schema::try_from(&read_schema)?))?
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    let sidecar_root = LogPath(log_root).child("_sidecars/")?;
+    let mut files = vec![checkpoint];
+    for batch in batches {
+        // checkpoints without sidecars may omit the column
+        let Some(sidecars) = batch.column_by_name("sidecar") else {
+            continue;
+        };
+        let sidecars =
+            sidecars
+                .as_any()
+                .downcast_ref::<StructArray>()
+                .ok_or(Error::UnexpectedColumnType(
+                    "Cannot downcast to StructArray".into(),
+                ))?;
+        let column = |name: &str| {
+            sidecars
+                .column_by_name(name)
+                .ok_or(Error::MissingColumn(format!("sidecar.{}", name)))
+        };
+        let paths = column("path")?.as_string::<i32>();
+        let sizes = column("sizeInBytes")?.as_primitive::<Int64Type>();
+        let modification_times = column("modificationTime")?.as_primitive::<Int64Type>();
+        for row in (0..sidecars.len()).filter(|row| sidecars.is_valid(*row)) {
+            files.push(FileMeta {
+                location: sidecar_root.join(paths.value(row))?,
+                size: sizes.value(row) as usize,
+                last_modified: modification_times.value(row),
+            });
+        }
+    }
+    Ok(files)
+}
+
 /// List all log files after a givben checkpoint.
 async fn list_log_files_with_checkpoint(
     cp: &CheckpointMetadata,
@@ -348,26 +1065,38 @@ async fn list_log_files_with_checkpoint(
     // NOTE this will sort in reverse order
     commit_files.sort_unstable_by(|a, b| b.location.cmp(&a.location));
 
-    let checkpoint_files = files
+    let mut checkpoint_files = files
         .iter()
-        .filter_map(|f| {
-            if LogPath(&f.location).is_checkpoint_file() {
-                Some(f.clone())
-            } else {
-                None
-            }
+        .filter(|f| {
+            let path = LogPath(&f.location);
+            // the log may contain newer checkpoints not referenced by `_last_checkpoint` yet
+            path.is_checkpoint_file() && path.commit_version() == Some(cp.version)
         })
+        .cloned()
         .collect_vec();
+    sort_checkpoint_parts(&mut checkpoint_files);
 
-    // TODO raise a proper error
-    assert_eq!(checkpoint_files.len(), cp.parts.unwrap_or(1) as usize);
+    if checkpoint_files.len() != cp.parts.unwrap_or(1) as usize {
+        return Err(Error::CorruptCheckpoint(format!(
+            "expected {} checkpoint parts for version {}, found {}",
+            cp.parts.unwrap_or(1),
+            cp.version,
+            checkpoint_files.len()
+        )));
+    }
+    if !is_complete_checkpoint(&checkpoint_files) {
+        return Err(Error::CorruptCheckpoint(format!(
+            "incomplete multi-part checkpoint for version {}",
+            cp.version
+        )));
+    }
 
     Ok((commit_files, checkpoint_files))
 }
 
 /// List relevant log files.
 ///
-/// Relevant files are the max checkpoint found and all subsequent commits.
+/// Relevant files are the max complete checkpoint found and all subsequent commits.
 async fn list_log_files(
     fs_client: &dyn FileSystemClient,
     log_root: &Url,
@@ -375,49 +1104,103 @@ async fn list_log_files(
     let version_prefix = format!("{:020}", 0);
     let start_from = log_root.join(&version_prefix)?;
 
-    let mut max_checkpoint_version = -1_i64;
     let mut commit_files = Vec::new();
-    let mut checkpoint_files = Vec::with_capacity(10);
+    let mut checkpoints: BTreeMap<Version, Vec<FileMeta>> = BTreeMap::new();
 
     let mut stream = fs_client.list_from(&start_from).await?;
     while let Some(maybe_meta) = stream.next().await {
         let meta = maybe_meta?;
         if LogPath(&meta.location).is_checkpoint_file() {
-            let version = LogPath(&meta.location).commit_version().unwrap_or(0) as i64;
-            if version > max_checkpoint_version {
-                max_checkpoint_version = version;
-                checkpoint_files.clear();
-                checkpoint_files.push(meta);
-            } else if version == max_checkpoint_version {
-                checkpoint_files.push(meta);
-            }
+            let version = LogPath(&meta.location).commit_version().unwrap_or(0);
+            checkpoints.entry(version).or_default().push(meta);
         } else if LogPath(&meta.location).is_commit_file() {
             commit_files.push(meta);
         }
     }
 
-    commit_files.retain(|f| {
-        LogPath(&f.location).commit_version().unwrap_or(0) as i64 > max_checkpoint_version
-    });
+    // the parts of a multi-part checkpoint may still be written, so fall back to older ones
+    let checkpoint = checkpoints
+        .into_iter()
+        .rev()
+        .find(|(_, files)| is_complete_checkpoint(files));
+    let (checkpoint_version, mut checkpoint_files) = match checkpoint {
+        Some((version, files)) => (Some(version), files),
+        None => (None, Vec::new()),
+    };
+    sort_checkpoint_parts(&mut checkpoint_files);
+
+    commit_files.retain(|f| LogPath(&f.location).commit_version() > checkpoint_version);
     // NOTE this will sort in reverse order
     commit_files.sort_unstable_by(|a, b| b.location.cmp(&a.location));
 
     Ok((commit_files, checkpoint_files))
 }
 
+/// Whether `files`, the checkpoint files of a single version, contain a complete checkpoint.
+///
+/// A multi-part checkpoint is only complete once all of its parts were written. Single file
+/// checkpoints are always complete.
+fn is_complete_checkpoint(files: &[FileMeta]) -> bool {
+    let mut parts = Vec::with_capacity(files.len());
+    for file in files {
+        match LogPath(&file.location).checkpoint_part() {
+            Some(part) => parts.push(part),
+            None => return true,
+        }
+    }
+    parts.sort_unstable();
+    parts.dedup();
+    let Some((_, num_parts)) = parts.first().copied() else {
+        return false;
+    };
+    parts.len() == num_parts as usize
+        && parts
+            .iter()
+            .zip(1..)
+            .all(|(&(part, total), expected)| part == expected && total == num_parts)
+}
+
+/// Order the parts of a multi-part checkpoint by their part number.
+fn sort_checkpoint_parts(files: &mut [FileMeta]) {
+    files.sort_by_key(|file| LogPath(&file.location).checkpoint_part());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use std::collections::HashMap;
     use std::path::PathBuf;
 
     use object_store::local::LocalFileSystem;
     use object_store::path::Path;
+    use object_store::ObjectStore;
 
+    use object_store::memory::InMemory;
+
+    use std::num::NonZeroUsize;
+
+    use crate::client::json::DefaultJsonHandler;
     use crate::client::DefaultTableClient;
+    use crate::expressions::{Expression, Scalar};
     use crate::filesystem::ObjectStoreFileSystemClient;
+    use crate::scan::SkippingApplicability;
     use crate::schema::StructType;
+    use crate::table::{in_memory_table, in_memory_table_with};
+
+    /// A table client over an in-memory table whose `_delta_log` holds the given files, as pairs
+    /// of file name and content.
+    fn in_memory_log(
+        files: impl IntoIterator<Item = (String, Vec<u8>)>,
+    ) -> (Url, Arc<DefaultTableClient>) {
+        let store = InMemory::new();
+        for (name, data) in files {
+            let path = Path::from(format!("_delta_log/{name}"));
+            futures::executor::block_on(store.put(&path, data.into())).unwrap();
+        }
+        let location = Url::parse("memory:///").unwrap();
+        let client = Arc::new(DefaultTableClient::new(Arc::new(store), Path::from("/")));
+        (location, client)
+    }
 
     #[tokio::test]
     async fn test_snapshot_read_metadata() {
@@ -444,6 +1227,499 @@ mod tests {
         assert_eq!(schema, expected);
     }
 
+    #[tokio::test]
+    async fn test_snapshot_configuration() {
+        let commit = r#"{"protocol":{"minReaderVersion":2,"minWriterVersion":5}}
+{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{\"delta.columnMapping.id\":1,\"delta.columnMapping.physicalName\":\"col-1\"}}]}","partitionColumns":[],"configuration":{"delta.columnMapping.mode":"name","delta.columnMapping.maxColumnId":"1","delta.enableChangeDataFeed":"TRUE","delta.appendOnly":"maybe"},"createdTime":1677811175819}}"#;
+        let snapshot = in_memory_table([commit]).snapshot(None).await.unwrap();
+
+        let configuration = snapshot.configuration().await.unwrap();
+        assert_eq!(configuration.len(), 4);
+        assert_eq!(configuration["delta.columnMapping.mode"], "name");
+        assert_eq!(configuration["delta.columnMapping.maxColumnId"], "1");
+
+        assert_eq!(
+            snapshot
+                .config_bool("delta.enableChangeDataFeed")
+                .await
+                .unwrap(),
+            Some(true)
+        );
+        assert_eq!(
+            snapshot
+                .config_bool("delta.enableDeletionVectors")
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(snapshot.config_bool("delta.appendOnly").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generated_columns_and_check_constraints() {
+        let commit = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":4}}
+{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"doubled\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{\"delta.generationExpression\":\"value * 2\"}}]}","partitionColumns":[],"configuration":{"delta.constraints.positive":"value > 0","delta.appendOnly":"true"},"createdTime":1677811175819}}"#;
+        let snapshot = in_memory_table([commit]).snapshot(None).await.unwrap();
+
+        let generated = snapshot.generated_columns().await.unwrap();
+        assert_eq!(
+            generated,
+            HashMap::from([("doubled".to_string(), "value * 2".to_string())])
+        );
+
+        let constraints = snapshot.check_constraints().await.unwrap();
+        assert_eq!(
+            constraints,
+            HashMap::from([("positive".to_string(), "value > 0".to_string())])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nested_generated_columns() {
+        let commit = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":4}}
+{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"s\",\"type\":{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"doubled\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{\"delta.generationExpression\":\"s.value * 2\"}}]},\"nullable\":true,\"metadata\":{}},{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{\"delta.generationExpression\":\"s.value + 1\"}}]}","partitionColumns":[],"configuration":{},"createdTime":1677811175819}}"#;
+        let snapshot = in_memory_table([commit]).snapshot(None).await.unwrap();
+
+        let generated = snapshot.generated_columns().await.unwrap();
+        assert_eq!(
+            generated,
+            HashMap::from([
+                ("s.doubled".to_string(), "s.value * 2".to_string()),
+                ("id".to_string(), "s.value + 1".to_string()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_compat_version() {
+        let commits = [
+            r#"{"protocol":{"minReaderVersion":2,"minWriterVersion":7,"writerFeatures":["columnMapping","icebergCompatV2"]}}
+{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{\"delta.columnMapping.id\":1,\"delta.columnMapping.physicalName\":\"col-1\"}}]}","partitionColumns":[],"configuration":{"delta.columnMapping.mode":"name","delta.columnMapping.maxColumnId":"1","delta.enableIcebergCompatV2":"true"},"createdTime":1677811175819}}"#,
+            r#"{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{\"delta.columnMapping.id\":1,\"delta.columnMapping.physicalName\":\"col-1\"}}]}","partitionColumns":[],"configuration":{"delta.columnMapping.mode":"name","delta.columnMapping.maxColumnId":"1","delta.enableIcebergCompatV2":"false"},"createdTime":1677811175819}}"#,
+        ];
+        let table = in_memory_table(commits);
+
+        let snapshot = table.snapshot(Some(0)).await.unwrap();
+        assert_eq!(snapshot.iceberg_compat_version().await.unwrap(), Some(2));
+        // the table can be read like any other column mapped table
+        let scan = snapshot.scan().await.unwrap().build().unwrap();
+        assert_eq!(scan.physical_read_schema().fields[0].name, "col-1");
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert_eq!(snapshot.iceberg_compat_version().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_partition_columns() {
+        let field = |name: &str| {
+            format!(
+                r#"{{\"name\":\"{name}\",\"type\":\"string\",\"nullable\":true,\"metadata\":{{}}}}"#
+            )
+        };
+        let commits = [
+            format!(
+                r#"{{"protocol":{{"minReaderVersion":1,"minWriterVersion":2}}}}
+{{"metaData":{{"id":"testId","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{},{},{}]}}","partitionColumns":["c","a"],"configuration":{{}}}}}}"#,
+                field("a"),
+                field("b"),
+                field("c")
+            ),
+            format!(
+                r#"{{"metaData":{{"id":"testId","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{}]}}","partitionColumns":[],"configuration":{{}}}}}}"#,
+                field("a")
+            ),
+        ];
+        let table = in_memory_table(commits);
+
+        let snapshot = table.snapshot(Some(0)).await.unwrap();
+        assert_eq!(snapshot.partition_columns().await.unwrap(), vec!["c", "a"]);
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert!(snapshot.partition_columns().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expired_tombstones() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let add = |path: &str| {
+            format!(
+                r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":1587968586000,"dataChange":true}}}}"#
+            )
+        };
+        let remove = |path: &str, timestamp: i64| {
+            format!(
+                r#"{{"remove":{{"path":"{path}","deletionTimestamp":{timestamp},"dataChange":true}}}}"#
+            )
+        };
+        let day = 24 * 60 * 60 * 1000;
+        let commits = [
+            [
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#.to_string(),
+                add("a"),
+                add("b"),
+                add("c"),
+                add("d"),
+            ]
+            .join("\n"),
+            // "a" is expired, "b" was removed recently and "c" is added again below
+            [
+                remove("a", now - 10 * day),
+                remove("b", now - day),
+                remove("c", now - 10 * day),
+            ]
+            .join("\n"),
+            [add("c"), remove("d", now - 10 * day), add("d")].join("\n"),
+        ];
+        let table = in_memory_table(commits);
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        let paths = |tombstones: Vec<Remove>| {
+            tombstones
+                .into_iter()
+                .map(|remove| remove.path)
+                .sorted()
+                .collect::<Vec<_>>()
+        };
+        let week = 7 * day;
+        assert_eq!(
+            paths(snapshot.expired_tombstones(week).await.unwrap()),
+            vec!["a"]
+        );
+        assert_eq!(
+            paths(snapshot.expired_tombstones(0).await.unwrap()),
+            vec!["a", "b"]
+        );
+
+        // at version 1 "c" was not added again yet
+        let snapshot = table.snapshot(Some(1)).await.unwrap();
+        assert_eq!(
+            paths(snapshot.expired_tombstones(week).await.unwrap()),
+            vec!["a", "c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_tombstones_across_batches() {
+        let commits = [
+            [
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+                r#"{"add":{"path":"a","partitionValues":{},"size":1,"modificationTime":1587968586000,"dataChange":true}}"#,
+            ]
+            .join("\n"),
+            // with one row per batch the remove is read before "a" is added again
+            [
+                r#"{"remove":{"path":"a","deletionTimestamp":0,"dataChange":true}}"#,
+                r#"{"add":{"path":"a","partitionValues":{},"size":1,"modificationTime":1587968586000,"dataChange":true}}"#,
+            ]
+            .join("\n"),
+        ];
+        let table = in_memory_table_with(commits, |client| {
+            let store = client
+                .get_object_store_for_url(&Url::parse("memory:///").unwrap())
+                .unwrap();
+            client.with_json_handler(
+                DefaultJsonHandler::new(store).with_batch_size(NonZeroUsize::MIN),
+            )
+        });
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert!(snapshot.expired_tombstones(0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stats_columns() {
+        let field = |name: &str| {
+            format!(
+                r#"{{\"name\":\"{name}\",\"type\":\"long\",\"nullable\":true,\"metadata\":{{}}}}"#
+            )
+        };
+        let fields = ["a", "b", "c"].map(field).join(",");
+        let load = |configuration: &str| {
+            let commit = format!(
+                r#"{{"protocol":{{"minReaderVersion":1,"minWriterVersion":2}}}}
+{{"metaData":{{"id":"testId","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{fields}]}}","partitionColumns":[],"configuration":{{{configuration}}}}}}}"#
+            );
+            in_memory_table([commit])
+        };
+
+        let configured = r#""delta.dataSkippingStatsColumns":"c, a""#;
+        let snapshot = load(configured).snapshot(None).await.unwrap();
+        assert_eq!(snapshot.stats_columns().await.unwrap(), vec!["c", "a"]);
+
+        // predicates on columns without statistics are not applicable for skipping
+        let column = |name: &str| Box::new(Expression::Column(name.to_string()));
+        let literal = || Box::new(Expression::Literal(Scalar::Long(1)));
+        let applicability = |predicate| async {
+            let scan = snapshot.scan().await.unwrap();
+            scan.with_predicate(predicate)
+                .build()
+                .unwrap()
+                .skipping_applicability()
+                .unwrap()
+        };
+        assert_eq!(
+            applicability(Expression::LessThan(column("a"), literal())).await,
+            SkippingApplicability::Full
+        );
+        assert_eq!(
+            applicability(Expression::LessThan(column("b"), literal())).await,
+            SkippingApplicability::NotApplicable
+        );
+
+        let configured = r#""delta.dataSkippingNumIndexedCols":"2""#;
+        let snapshot = load(configured).snapshot(None).await.unwrap();
+        assert_eq!(snapshot.stats_columns().await.unwrap(), vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_nested_stats_columns() {
+        let schema = r#"{\"type\":\"struct\",\"fields\":[{\"name\":\"s\",\"type\":{\"type\":\"struct\",\"fields\":[{\"name\":\"x\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}},{\"name\":\"y\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}}]},\"nullable\":true,\"metadata\":{}},{\"name\":\"b\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}}]}"#;
+        let load = |configuration: &str| {
+            let commit = format!(
+                r#"{{"protocol":{{"minReaderVersion":1,"minWriterVersion":2}}}}
+{{"metaData":{{"id":"testId","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{schema}","partitionColumns":[],"configuration":{{{configuration}}}}}}}"#
+            );
+            in_memory_table([commit])
+        };
+
+        let snapshot = load("").snapshot(None).await.unwrap();
+        assert_eq!(
+            snapshot.stats_columns().await.unwrap(),
+            vec!["s.x", "s.y", "b"]
+        );
+
+        let configured = r#""delta.dataSkippingNumIndexedCols":"2""#;
+        let snapshot = load(configured).snapshot(None).await.unwrap();
+        assert_eq!(snapshot.stats_columns().await.unwrap(), vec!["s.x", "s.y"]);
+
+        let configured = r#""delta.dataSkippingStatsColumns":"b,s.y""#;
+        let snapshot = load(configured).snapshot(None).await.unwrap();
+        assert_eq!(snapshot.stats_columns().await.unwrap(), vec!["b", "s.y"]);
+    }
+
+    #[tokio::test]
+    async fn test_domain_metadata() {
+        let commits = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":7,"writerFeatures":["domainMetadata","clustering"]}}
+{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{}}}
+{"domainMetadata":{"domain":"delta.clustering","configuration":"{\"clusteringColumns\":[]}","removed":false}}
+{"domainMetadata":{"domain":"app","configuration":"{}","removed":false}}"#,
+            r#"{"domainMetadata":{"domain":"delta.clustering","configuration":"{\"clusteringColumns\":[[\"value\"]]}","removed":false}}
+{"domainMetadata":{"domain":"app","configuration":"{}","removed":true}}"#,
+        ];
+        let table = in_memory_table(commits);
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert_eq!(
+            snapshot.domain_metadata("delta.clustering").await.unwrap(),
+            Some(r#"{"clusteringColumns":[["value"]]}"#.to_string())
+        );
+        assert_eq!(snapshot.domain_metadata("app").await.unwrap(), None);
+        assert_eq!(snapshot.domain_metadata("missing").await.unwrap(), None);
+
+        let snapshot = table.snapshot(Some(0)).await.unwrap();
+        assert_eq!(
+            snapshot.domain_metadata("delta.clustering").await.unwrap(),
+            Some(r#"{"clusteringColumns":[]}"#.to_string())
+        );
+        assert_eq!(
+            snapshot.domain_metadata("app").await.unwrap(),
+            Some("{}".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_app_transaction_version() {
+        let commits = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[]}","partitionColumns":[],"configuration":{}}}
+{"txn":{"appId":"app-a","version":1,"lastUpdated":1677811175819}}"#,
+            r#"{"txn":{"appId":"app-b","version":5}}
+{"txn":{"appId":"app-a","version":3}}"#,
+            r#"{"txn":{"appId":"app-a","version":2}}"#,
+        ];
+        let table = in_memory_table(commits);
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert_eq!(
+            snapshot.app_transaction_version("app-a").await.unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            snapshot.app_transaction_version("app-b").await.unwrap(),
+            Some(5)
+        );
+        assert_eq!(
+            snapshot.app_transaction_version("app-c").await.unwrap(),
+            None
+        );
+
+        let snapshot = table.snapshot(Some(0)).await.unwrap();
+        assert_eq!(
+            snapshot.app_transaction_version("app-a").await.unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            snapshot.app_transaction_version("app-b").await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_part_checkpoint() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        use parquet::arrow::ArrowWriter;
+
+        let log_dir = PathBuf::from("./tests/data/with_checkpoint_no_last_checkpoint/_delta_log/");
+        let checkpoint =
+            std::fs::read(log_dir.join("00000000000000000002.checkpoint.parquet")).unwrap();
+        let batches = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(checkpoint))
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let batch = arrow_select::concat::concat_batches(&batches[0].schema(), &batches).unwrap();
+        assert_eq!(batch.num_rows(), 4);
+        // split the actions of the checkpoint into two parts
+        let parts = [batch.slice(0, 2), batch.slice(2, 2)].map(|part| {
+            let mut data = Vec::new();
+            let mut writer = ArrowWriter::try_new(&mut data, part.schema(), None).unwrap();
+            writer.write(&part).unwrap();
+            writer.close().unwrap();
+            data
+        });
+
+        let table_client = |last_checkpoint: Option<&str>, parts: &[Vec<u8>]| {
+            let commits = ["00000000000000000002.json", "00000000000000000003.json"]
+                .map(|name| (name.to_string(), std::fs::read(log_dir.join(name)).unwrap()));
+            let parts = parts.iter().enumerate().map(|(idx, part)| {
+                let name = format!(
+                    "00000000000000000002.checkpoint.{:010}.0000000002.parquet",
+                    idx + 1
+                );
+                (name, part.clone())
+            });
+            let last_checkpoint = last_checkpoint
+                .map(|hint| ("_last_checkpoint".to_string(), hint.as_bytes().to_vec()));
+            in_memory_log(commits.into_iter().chain(parts).chain(last_checkpoint))
+        };
+
+        let last_checkpoint = r#"{"version":2,"size":4,"parts":2,"numOfAddFiles":1}"#;
+        for (last_checkpoint, version) in [(None, None), (Some(last_checkpoint), Some(2))] {
+            let (location, client) = table_client(last_checkpoint, &parts);
+            let snapshot = Snapshot::try_new_with_checkpoint_validation(
+                location,
+                client,
+                version,
+                CheckpointValidation::Error,
+            )
+            .await
+            .unwrap();
+            let names = snapshot
+                .log_segment
+                .checkpoint_files
+                .iter()
+                .map(|file| LogPath(&file.location).checkpoint_part())
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec![Some((1, 2)), Some((2, 2))]);
+            assert_eq!(snapshot.schema().await.unwrap().fields.len(), 3);
+            let scan = snapshot.scan().await.unwrap().build().unwrap();
+            let files = scan.files_stream().unwrap().try_collect::<Vec<_>>().await;
+            assert_eq!(files.unwrap().len(), 1);
+        }
+
+        // a checkpoint with missing parts is not used
+        let (location, client) = table_client(None, &parts[..1]);
+        let snapshot = Snapshot::try_new(location, client, None).await.unwrap();
+        assert!(snapshot.log_segment.checkpoint_files.is_empty());
+        assert_eq!(snapshot.log_segment.commit_files.len(), 2);
+        let (location, client) = table_client(Some(last_checkpoint), &parts[..1]);
+        let result = Snapshot::try_new(location, client, Some(2)).await;
+        assert!(matches!(result, Err(Error::CorruptCheckpoint(_))));
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_validation() {
+        let log_dir = PathBuf::from("./tests/data/with_checkpoint_no_last_checkpoint/_delta_log/");
+        let table_client = |last_checkpoint: &str| {
+            let files = std::fs::read_dir(&log_dir).unwrap().map(|entry| {
+                let entry = entry.unwrap();
+                let name = entry.file_name().to_str().unwrap().to_string();
+                (name, std::fs::read(entry.path()).unwrap())
+            });
+            let last_checkpoint = (
+                "_last_checkpoint".to_string(),
+                last_checkpoint.as_bytes().to_vec(),
+            );
+            in_memory_log(files.chain(std::iter::once(last_checkpoint)))
+        };
+        let load = |last_checkpoint: &str, validation| {
+            let (location, client) = table_client(last_checkpoint);
+            Snapshot::try_new_with_checkpoint_validation(location, client, None, validation)
+        };
+
+        let valid = r#"{"version":2,"size":4,"numOfAddFiles":1}"#;
+        let snapshot = load(valid, CheckpointValidation::Error).await.unwrap();
+        assert_eq!(snapshot.log_segment.checkpoint_files.len(), 1);
+        assert_eq!(snapshot.version(), 3);
+
+        for invalid in [
+            r#"{"version":2,"size":7,"numOfAddFiles":1}"#,
+            r#"{"version":2,"size":4,"numOfAddFiles":2}"#,
+        ] {
+            // the hint is trusted unless validation is requested
+            let snapshot = load(invalid, CheckpointValidation::Skip).await.unwrap();
+            assert_eq!(snapshot.log_segment.checkpoint_files.len(), 1);
+
+            let result = load(invalid, CheckpointValidation::Error).await;
+            assert!(matches!(result, Err(Error::CorruptCheckpoint(_))));
+
+            let snapshot = load(invalid, CheckpointValidation::FallbackToReplay)
+                .await
+                .unwrap();
+            assert!(snapshot.log_segment.checkpoint_files.is_empty());
+            assert_eq!(snapshot.log_segment.commit_files.len(), 4);
+            assert_eq!(snapshot.version(), 3);
+            assert_eq!(snapshot.schema().await.unwrap().fields.len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_latest_committed_snapshot() {
+        let protocol = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#;
+        let commit_info = r#"{"commitInfo":{"timestamp":1587968586154}}"#;
+        let truncated = r#"{"add":{"path":"part-00000.parquet","partitionValues":{},"si"#;
+        // a trailing incomplete commit is not yet committed
+        let table = in_memory_table([protocol, commit_info, truncated]);
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert_eq!(snapshot.version(), 2);
+        let snapshot = table.latest_committed_snapshot().await.unwrap();
+        assert_eq!(snapshot.version(), 1);
+
+        let table = in_memory_table([protocol, commit_info]);
+        let snapshot = table.latest_committed_snapshot().await.unwrap();
+        assert_eq!(snapshot.version(), 1);
+
+        // corrupt commits before the latest one are errors
+        let table = in_memory_table([protocol, truncated, truncated]);
+        let result = table.latest_committed_snapshot().await;
+        assert!(matches!(
+            result,
+            Err(Error::CorruptCommit { version: 1, .. })
+        ));
+
+        let table = in_memory_table([truncated]);
+        let result = table.latest_committed_snapshot().await;
+        assert!(matches!(
+            result,
+            Err(Error::CorruptCommit { version: 0, .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_new_snapshot() {
         let path =
@@ -469,6 +1745,70 @@ mod tests {
         assert_eq!(schema, expected);
     }
 
+    #[tokio::test]
+    async fn test_num_files_and_total_bytes() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let client =
+            Arc::new(DefaultTableClient::try_new(&url, HashMap::<String, String>::new()).unwrap());
+
+        // version 1 replaces the only file with the same file and a deletion vector
+        let snapshot = Snapshot::try_new(url.clone(), client.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.num_files().await.unwrap(), 1);
+        assert_eq!(snapshot.total_bytes().await.unwrap(), 635);
+
+        let snapshot = Snapshot::try_new(url, client, Some(0)).await.unwrap();
+        assert_eq!(snapshot.num_files().await.unwrap(), 1);
+        assert_eq!(snapshot.total_bytes().await.unwrap(), 635);
+
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let client =
+            Arc::new(DefaultTableClient::try_new(&url, HashMap::<String, String>::new()).unwrap());
+        let snapshot = Snapshot::try_new(url, client, None).await.unwrap();
+        assert_eq!(snapshot.num_files().await.unwrap(), 1);
+        assert_eq!(snapshot.total_bytes().await.unwrap(), 548);
+    }
+
+    #[tokio::test]
+    async fn test_num_files_across_batches() {
+        let add = r#"{"add":{"path":"a","partitionValues":{},"size":3,"modificationTime":1587968586000,"dataChange":true}}"#;
+        let commits = [
+            [
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+                add,
+            ]
+            .join("\n"),
+            [r#"{"remove":{"path":"a","dataChange":true}}"#, add].join("\n"),
+        ];
+        let mut store = None;
+        let table = in_memory_table_with(commits, |client| {
+            let object_store = client
+                .get_object_store_for_url(&Url::parse("memory:///").unwrap())
+                .unwrap();
+            store = Some(object_store.clone());
+            client.with_json_handler(
+                DefaultJsonHandler::new(object_store).with_batch_size(NonZeroUsize::MIN),
+            )
+        });
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert_eq!(snapshot.num_files().await.unwrap(), 1);
+
+        // the log is only replayed once
+        let store = store.unwrap();
+        for version in 0..2 {
+            let path = Path::from(format!("_delta_log/{version:020}.json"));
+            store.delete(&path).await.unwrap();
+        }
+        assert_eq!(snapshot.total_bytes().await.unwrap(), 3);
+        assert_eq!(snapshot.num_files().await.unwrap(), 1);
+    }
+
     #[tokio::test]
     async fn test_read_table_with_last_checkpoint() {
         let path = std::fs::canonicalize(PathBuf::from(
@@ -509,4 +1849,32 @@ mod tests {
             Some(3)
         );
     }
+
+    #[tokio::test]
+    async fn test_snapshot_in_memory() {
+        let table = in_memory_table([
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"value\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1677811175819}}"#,
+            r#"{"commitInfo":{"timestamp":1587968586154}}"#,
+        ]);
+
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert_eq!(snapshot.version(), 1);
+        let versions = snapshot
+            .log_segment
+            .commit_files
+            .iter()
+            .map(|meta| LogPath(&meta.location).commit_version())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec![Some(1), Some(0)]);
+        assert_eq!(snapshot.metadata().await.unwrap().id, "testId");
+        let schema = snapshot.schema().await.unwrap();
+        assert_eq!(schema.fields.len(), 1);
+        assert_eq!(schema.fields[0].name, "value");
+
+        let snapshot = table.snapshot(Some(0)).await.unwrap();
+        assert_eq!(snapshot.version(), 0);
+        assert_eq!(snapshot.log_segment.commit_files.len(), 1);
+        assert_eq!(snapshot.protocol().await.unwrap().min_reader_version, 1);
+    }
 }
@@ -1,9 +1,27 @@
 use std::sync::Arc;
 
+use futures::{StreamExt, TryStreamExt};
 use url::Url;
 
-use crate::snapshot::Snapshot;
-use crate::{DeltaResult, TableClient, Version};
+use crate::actions::{parse_actions, Action, ActionType, Add, Metadata, Protocol, Remove};
+use crate::path::LogPath;
+use crate::schema::Schema;
+use crate::snapshot::{read_commit, Snapshot};
+use crate::table_changes::{list_commit_files, read_commit_timestamp, TableChangesScan};
+use crate::{DeltaResult, Error, FileMeta, TableClient, Version};
+
+/// The changes made by a single commit, see [`Table::commit_diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitDiff {
+    /// Files added by the commit.
+    pub added: Vec<Add>,
+    /// Files removed by the commit.
+    pub removed: Vec<Remove>,
+    /// The new metadata of the table, if the commit changed it.
+    pub metadata_changed: Option<Metadata>,
+    /// The new protocol of the table, if the commit changed it.
+    pub protocol_changed: Option<Protocol>,
+}
 
 /// In-memory representation of a Delta table, which acts as an immutable root entity for reading
 /// the different versions (see [`Snapshot`]) of the table located in storage.
@@ -38,12 +56,269 @@ impl<JRC: Send, PRC: Send + Sync> Table<JRC, PRC> {
         &self.location
     }
 
+    /// Whether the location of the table contains a Delta table, i.e. a `_delta_log` directory
+    /// with at least one commit or checkpoint.
+    ///
+    /// Returns `false` if the location or its log does not exist or the log is empty, and an
+    /// error if the log can not be listed, e.g. due to missing permissions.
+    pub async fn exists(&self) -> DeltaResult<bool> {
+        let log_root = LogPath(&self.location).child("_delta_log/")?;
+        let fs_client = self.table_client.get_file_system_client();
+        let mut files = match fs_client
+            .list_from(&log_root.join(&format!("{:020}", 0))?)
+            .await
+        {
+            Ok(files) => files,
+            Err(Error::FileNotFound(_)) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        while let Some(file) = files.next().await {
+            match file {
+                Ok(meta) => {
+                    let path = LogPath(&meta.location);
+                    if path.is_commit_file() || path.is_checkpoint_file() {
+                        return Ok(true);
+                    }
+                }
+                Err(Error::FileNotFound(_)) => return Ok(false),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(false)
+    }
+
     /// Create a [`Snapshot`] of the table corresponding to `version`.
     ///
     /// If no version is supplied, a snapshot for the latest version will be created.
     pub async fn snapshot(&self, version: Option<Version>) -> DeltaResult<Snapshot<JRC, PRC>> {
         Snapshot::try_new(self.location.clone(), self.table_client.clone(), version).await
     }
+
+    /// Create a [`Snapshot`] of the latest version committed at or before `timestamp`, given in
+    /// milliseconds since the Unix epoch.
+    ///
+    /// The commit timestamp is read from the `commitInfo` action of each commit, falling back to
+    /// the modification time of the commit file. Commit timestamps are expected to increase with
+    /// the version, so only the commits visited by a binary search over the versions are read.
+    /// Timestamps after the latest commit resolve to the latest version. Returns
+    /// [`Error::MissingVersionAtTimestamp`] if `timestamp` is before the earliest commit in the
+    /// log.
+    pub async fn snapshot_at_timestamp(&self, timestamp: i64) -> DeltaResult<Snapshot<JRC, PRC>> {
+        let log_root = LogPath(&self.location).child("_delta_log/")?;
+        let mut commit_files = self
+            .table_client
+            .get_file_system_client()
+            .list_from(&log_root.join(&format!("{:020}", 0))?)
+            .await?
+            .try_filter_map(|meta| async move {
+                let path = LogPath(&meta.location);
+                Ok(path
+                    .is_commit_file()
+                    .then(|| path.commit_version())
+                    .flatten()
+                    .map(|version| (version, meta)))
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+        commit_files.sort_unstable_by_key(|(version, _)| *version);
+
+        // the number of commits at or before `timestamp`
+        let (mut low, mut high) = (0, commit_files.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (version, file) = commit_files[mid].clone();
+            if self.commit_timestamp(file, version).await? <= timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        match low.checked_sub(1) {
+            Some(idx) => self.snapshot(Some(commit_files[idx].0)).await,
+            None => Err(Error::MissingVersionAtTimestamp(timestamp)),
+        }
+    }
+
+    /// The timestamp of the commit of `version` in milliseconds since the Unix epoch, falling
+    /// back to the modification time of its `file`.
+    async fn commit_timestamp(&self, file: FileMeta, version: Version) -> DeltaResult<i64> {
+        let last_modified = file.last_modified;
+        let batches = read_commit(self.table_client.as_ref(), file, version).await?;
+        let mut commit_timestamp = None;
+        for batch in &batches {
+            commit_timestamp = commit_timestamp.or(read_commit_timestamp(batch)?);
+        }
+        Ok(commit_timestamp.unwrap_or(last_modified))
+    }
+
+    /// Create a [`Snapshot`] of the latest version whose commit is complete.
+    ///
+    /// See [`Snapshot::try_new_latest_committed`].
+    pub async fn latest_committed_snapshot(&self) -> DeltaResult<Snapshot<JRC, PRC>> {
+        Snapshot::try_new_latest_committed(self.location.clone(), self.table_client.clone()).await
+    }
+
+    /// The [`Schema`] of the table at `version`, or at the latest version if none is supplied.
+    ///
+    /// Only the log is listed and read back from the requested version until the latest
+    /// metadata is found, no files are replayed beyond that.
+    pub async fn schema_at(&self, version: Option<Version>) -> DeltaResult<Schema> {
+        self.snapshot(version).await?.schema().await
+    }
+
+    /// The versions in `from..=to` whose commit changed the schema of the table, each with the
+    /// new schema, in ascending order.
+    ///
+    /// Metadata changes that keep the schema, e.g. of table properties, are not reported. The
+    /// schema defined by version 0 counts as a change. Returns an empty list if the schema did
+    /// not change in the range, and [`Error::MissingVersion`] if a version of the range is
+    /// not part of the log.
+    pub async fn schema_changes(
+        &self,
+        from: Version,
+        to: Version,
+    ) -> DeltaResult<Vec<(Version, Schema)>> {
+        if to < from {
+            return Err(Error::Generic(format!(
+                "end version {} is before start version {}",
+                to, from
+            )));
+        }
+        let mut schema = match from {
+            0 => None,
+            from => Some(self.schema_at(Some(from - 1)).await?),
+        };
+        let log_root = LogPath(&self.location).child("_delta_log/")?;
+        let commit_files =
+            list_commit_files(self.table_client.as_ref(), &log_root, from, Some(to)).await?;
+
+        let mut changes = Vec::new();
+        for (version, commit_file) in commit_files {
+            let batches = read_commit(self.table_client.as_ref(), commit_file, version).await?;
+            for batch in batches {
+                for action in parse_actions(&batch, &[ActionType::Metadata])? {
+                    let Action::Metadata(metadata) = action else {
+                        continue;
+                    };
+                    let new_schema = metadata.schema()?;
+                    if schema.as_ref() != Some(&new_schema) {
+                        changes.push((version, new_schema.clone()));
+                        schema = Some(new_schema);
+                    }
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Create a [`TableChangesScan`] over the changes of the table between `start_version` and
+    /// `end_version` (both inclusive), as recorded by the change data feed.
+    ///
+    /// If no end version is supplied, all changes up to the latest version are included. The
+    /// change data feed must be enabled for every version in the range.
+    pub async fn table_changes(
+        &self,
+        start_version: Version,
+        end_version: Option<Version>,
+    ) -> DeltaResult<TableChangesScan<JRC, PRC>>
+    where
+        PRC: 'static,
+    {
+        TableChangesScan::try_new(
+            self.location.clone(),
+            self.table_client.clone(),
+            start_version,
+            end_version,
+        )
+        .await
+    }
+
+    /// The actions recorded in the commit of `version`, grouped by action type.
+    ///
+    /// Returns [`Error::FileNotFound`] if the table has no commit for `version`, and
+    /// [`Error::CorruptCommit`] if the commit can not be parsed.
+    pub async fn commit_actions(&self, version: Version) -> DeltaResult<Vec<Action>> {
+        let log_root = LogPath(&self.location).child("_delta_log/")?;
+        let commit_files = list_commit_files(
+            self.table_client.as_ref(),
+            &log_root,
+            version,
+            Some(version),
+        )
+        .await;
+        let commit_file = match commit_files {
+            Ok(mut commit_files) => commit_files.pop().map(|(_, file)| file),
+            Err(Error::MissingVersion) => None,
+            Err(err) => return Err(err),
+        };
+        let Some(commit_file) = commit_file else {
+            return Err(Error::FileNotFound(
+                log_root.join(&format!("{:020}.json", version))?.to_string(),
+            ));
+        };
+
+        const ACTION_TYPES: [ActionType; 8] = [
+            ActionType::Metadata,
+            ActionType::Protocol,
+            ActionType::Add,
+            ActionType::Remove,
+            ActionType::Txn,
+            ActionType::CommitInfo,
+            ActionType::Cdc,
+            ActionType::DomainMetadata,
+        ];
+        let batches = read_commit(self.table_client.as_ref(), commit_file, version).await?;
+
+        let mut actions = Vec::new();
+        for batch in batches {
+            actions.extend(parse_actions(&batch, &ACTION_TYPES)?);
+        }
+        Ok(actions)
+    }
+
+    /// The files, metadata and protocol changed by the commit of `version`.
+    ///
+    /// Only the commit itself is read, neither the data files nor the state of the table at the
+    /// previous version. Errors are reported as for [`Table::commit_actions`].
+    pub async fn commit_diff(&self, version: Version) -> DeltaResult<CommitDiff> {
+        let mut diff = CommitDiff::default();
+        for action in self.commit_actions(version).await? {
+            match action {
+                Action::Add(add) => diff.added.push(add),
+                Action::Remove(remove) => diff.removed.push(remove),
+                Action::Metadata(metadata) => diff.metadata_changed = Some(metadata),
+                Action::Protocol(protocol) => diff.protocol_changed = Some(protocol),
+                _ => (),
+            }
+        }
+        Ok(diff)
+    }
+}
+
+/// A [`Table`] at `memory:///` whose log consists of `commits`, the first one being version 0.
+#[cfg(test)]
+pub(crate) fn in_memory_table(
+    commits: impl IntoIterator<Item = impl Into<String>>,
+) -> Table<crate::client::json::JsonReadContext, crate::client::parquet::ParquetReadContext> {
+    in_memory_table_with(commits, |client| client)
+}
+
+/// Like [`in_memory_table`], but the table client is configured with `configure`.
+#[cfg(test)]
+pub(crate) fn in_memory_table_with(
+    commits: impl IntoIterator<Item = impl Into<String>>,
+    configure: impl FnOnce(crate::client::DefaultTableClient) -> crate::client::DefaultTableClient,
+) -> Table<crate::client::json::JsonReadContext, crate::client::parquet::ParquetReadContext> {
+    use object_store::{memory::InMemory, path::Path, ObjectStore};
+
+    let store = Arc::new(InMemory::new());
+    for (version, commit) in commits.into_iter().enumerate() {
+        let path = Path::from(format!("_delta_log/{version:020}.json"));
+        futures::executor::block_on(store.put(&path, commit.into().into())).unwrap();
+    }
+    let location = Url::parse("memory:///").unwrap();
+    let table_client = crate::client::DefaultTableClient::new(store, Path::from("/"));
+    Table::new(location, Arc::new(configure(table_client)))
 }
 
 #[cfg(test)]
@@ -66,4 +341,241 @@ mod tests {
         let snapshot = table.snapshot(None).await.unwrap();
         assert_eq!(snapshot.version(), 1)
     }
+
+    #[tokio::test]
+    async fn test_exists() {
+        let table = |path: &std::path::Path| {
+            let url = url::Url::from_directory_path(path).unwrap();
+            let table_client = Arc::new(
+                DefaultTableClient::try_new(&url, HashMap::<String, String>::new()).unwrap(),
+            );
+            Table::new(url, table_client)
+        };
+
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/")).unwrap();
+        assert!(table(&path).exists().await.unwrap());
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("data.parquet"), b"not a delta table").unwrap();
+        assert!(!table(dir.path()).exists().await.unwrap());
+
+        // a log without commits is not a table yet
+        std::fs::create_dir(dir.path().join("_delta_log")).unwrap();
+        std::fs::write(dir.path().join("_delta_log/_last_checkpoint"), b"{}").unwrap();
+        assert!(!table(dir.path()).exists().await.unwrap());
+
+        assert!(!table(&dir.path().join("missing")).exists().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_schema_at() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let table_client =
+            Arc::new(DefaultTableClient::try_new(&url, HashMap::<String, String>::new()).unwrap());
+        let table = Table::new(url, table_client);
+        let snapshot = table.snapshot(None).await.unwrap();
+        assert_eq!(
+            table.schema_at(None).await.unwrap(),
+            snapshot.schema().await.unwrap()
+        );
+
+        let metadata = |fields: &str| {
+            format!(
+                r#"{{"metaData":{{"id":"testId","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{fields}]}}","partitionColumns":[],"configuration":{{}}}}}}"#
+            )
+        };
+        let field = |name: &str| {
+            format!(
+                r#"{{\"name\":\"{name}\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{{}}}}"#
+            )
+        };
+        let commits = [
+            format!(
+                "{}\n{}",
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+                metadata(&field("a"))
+            ),
+            metadata(&format!("{},{}", field("a"), field("b"))),
+            r#"{"commitInfo":{"timestamp":1587968586154}}"#.to_string(),
+        ];
+        let table = in_memory_table(commits);
+
+        let names = |schema: Schema| {
+            schema
+                .fields
+                .into_iter()
+                .map(|field| field.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(table.schema_at(None).await.unwrap()), vec!["a", "b"]);
+        assert_eq!(
+            names(table.schema_at(Some(1)).await.unwrap()),
+            vec!["a", "b"]
+        );
+        assert_eq!(names(table.schema_at(Some(0)).await.unwrap()), vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_at_timestamp() {
+        let commit_info =
+            |timestamp: i64| format!(r#"{{"commitInfo":{{"timestamp":{timestamp}}}}}"#);
+        let commits = [
+            format!(
+                "{}\n{}",
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+                commit_info(1000)
+            ),
+            commit_info(2000),
+            commit_info(3000),
+            commit_info(4000),
+            // falls back to the modification time of the commit file
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#.to_string(),
+        ];
+        let table = in_memory_table(commits);
+
+        let now = chrono::Utc::now().timestamp_millis();
+        for (timestamp, version) in [
+            (1000, 0),
+            (1999, 0),
+            (2000, 1),
+            (2500, 1),
+            (3000, 2),
+            (5000, 3),
+            (now - 60_000, 3),
+            (now + 60_000, 4),
+        ] {
+            let snapshot = table.snapshot_at_timestamp(timestamp).await.unwrap();
+            assert_eq!(snapshot.version(), version, "timestamp {timestamp}");
+        }
+        assert!(matches!(
+            table.snapshot_at_timestamp(999).await,
+            Err(Error::MissingVersionAtTimestamp(999))
+        ));
+        assert_eq!(table.snapshot(Some(1)).await.unwrap().version(), 1);
+        assert!(matches!(
+            table.snapshot(Some(5)).await,
+            Err(Error::MissingVersion)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_commit_actions() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let table_client =
+            Arc::new(DefaultTableClient::try_new(&url, HashMap::<String, String>::new()).unwrap());
+        let table = Table::new(url, table_client);
+
+        let actions = table.commit_actions(0).await.unwrap();
+        assert_eq!(actions.len(), 4);
+        assert!(matches!(&actions[0], Action::Metadata(metadata) if metadata.id == "testId"));
+        assert!(
+            matches!(&actions[1], Action::Protocol(protocol) if protocol.min_reader_version == 3)
+        );
+        assert!(matches!(&actions[2], Action::Add(add) if add.size == 635));
+        assert!(matches!(
+            &actions[3],
+            Action::CommitInfo(commit_info) if commit_info.operation.as_deref() == Some("WRITE")
+        ));
+
+        assert!(matches!(
+            table.commit_actions(2).await,
+            Err(Error::FileNotFound(path)) if path.ends_with("00000000000000000002.json")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_commit_diff() {
+        let add = |path: &str| {
+            format!(
+                r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#
+            )
+        };
+        let commits = [
+            format!(
+                "{}\n{}\n{}",
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+                r#"{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[]}","partitionColumns":[],"configuration":{},"createdTime":1587968585495}}"#,
+                add("a.parquet"),
+            ),
+            format!(
+                "{}\n{}\n{}",
+                r#"{"commitInfo":{"timestamp":1587968586154,"operation":"OPTIMIZE"}}"#,
+                r#"{"remove":{"path":"a.parquet","deletionTimestamp":1587968586000,"dataChange":true}}"#,
+                add("b.parquet"),
+            ),
+            r#"{"metaData":{"id":"testId","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[]}","partitionColumns":[],"configuration":{"delta.appendOnly":"true"},"createdTime":1587968585495}}"#.to_string(),
+        ];
+        let table = in_memory_table(commits);
+
+        let diff = table.commit_diff(1).await.unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, "b.parquet");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].path, "a.parquet");
+        assert!(diff.metadata_changed.is_none());
+        assert!(diff.protocol_changed.is_none());
+
+        // metadata only commits do not change any files
+        let diff = table.commit_diff(2).await.unwrap();
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        let metadata = diff.metadata_changed.unwrap();
+        assert_eq!(
+            metadata.configuration["delta.appendOnly"].as_deref(),
+            Some("true")
+        );
+
+        let diff = table.commit_diff(0).await.unwrap();
+        assert!(diff.protocol_changed.is_some() && diff.metadata_changed.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_schema_changes() {
+        let metadata = |fields: &str, configuration: &str| {
+            format!(
+                r#"{{"metaData":{{"id":"testId","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{fields}]}}","partitionColumns":[],"configuration":{{{configuration}}},"createdTime":1587968585495}}}}"#
+            )
+        };
+        let a = r#"{\"name\":\"a\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}"#;
+        let b = r#"{\"name\":\"b\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}}"#;
+        let add = r#"{"add":{"path":"a.parquet","partitionValues":{},"size":262,"modificationTime":1587968586000,"dataChange":true}}"#;
+        let commits = [
+            format!(
+                "{}\n{}",
+                r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+                metadata(a, "")
+            ),
+            add.to_string(),
+            // only the table properties change
+            metadata(a, r#""delta.appendOnly":"true""#),
+            metadata(&format!("{a},{b}"), r#""delta.appendOnly":"true""#),
+            add.to_string(),
+        ];
+        let table = in_memory_table(commits);
+
+        let changes = table.schema_changes(1, 4).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        let (version, schema) = &changes[0];
+        assert_eq!(*version, 3);
+        assert_eq!(schema.fields.len(), 2);
+        assert_eq!(schema.fields[1].name, "b");
+
+        assert!(table.schema_changes(1, 2).await.unwrap().is_empty());
+        let changes = table.schema_changes(0, 4).await.unwrap();
+        assert_eq!(
+            changes
+                .iter()
+                .map(|(version, _)| *version)
+                .collect::<Vec<_>>(),
+            vec![0, 3]
+        );
+        assert!(matches!(
+            table.schema_changes(3, 5).await,
+            Err(Error::MissingVersion)
+        ));
+    }
 }
@@ -0,0 +1,463 @@
+//! Reading the change data feed (CDF) of a table.
+//!
+//! The change data feed records the row level changes between versions of a table. When a commit
+//! contains explicit `cdc` actions, the changes are read from the referenced files in the
+//! `_change_data/` folder. Otherwise the changes are derived from the `add` and `remove` actions of
+//! the commit, where all rows of an added file are inserts and all rows of a removed file are
+//! deletes.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow_array::{Int64Array, RecordBatch, StringArray, StructArray, TimestampMicrosecondArray};
+use arrow_schema::{DataType as ArrowDataType, Field, Fields, Schema as ArrowSchema, TimeUnit};
+use arrow_select::concat::concat_batches;
+use futures::{StreamExt, TryStreamExt};
+use url::Url;
+
+use crate::actions::{
+    parse_actions, Action, ActionType, Add, Cdc, DeletionVectorDescriptor, Remove,
+};
+use crate::path::LogPath;
+use crate::scan::file_stream::DataFile;
+use crate::scan::{column_mapping_enabled, DataFileReader};
+use crate::schema::{DataType, PrimitiveType, Schema, SchemaRef, StructField};
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Error, FileMeta, TableClient, Version};
+
+/// Name of the column holding the kind of change, one of `insert`, `delete`, `update_preimage`
+/// or `update_postimage`.
+pub const CHANGE_TYPE_COL_NAME: &str = "_change_type";
+/// Name of the column holding the version of the commit that introduced the change.
+pub const COMMIT_VERSION_COL_NAME: &str = "_commit_version";
+/// Name of the column holding the timestamp of the commit that introduced the change.
+pub const COMMIT_TIMESTAMP_COL_NAME: &str = "_commit_timestamp";
+
+const ENABLE_CHANGE_DATA_FEED_KEY: &str = "delta.enableChangeDataFeed";
+
+/// The actions of a single commit that are relevant for reading its changes.
+#[derive(Debug, Clone)]
+struct CommitChanges {
+    version: Version,
+    /// Commit timestamp in milliseconds since the Unix epoch
+    timestamp: i64,
+    cdc: Vec<Cdc>,
+    adds: Vec<Add>,
+    removes: Vec<Remove>,
+}
+
+/// A scan over the changes of a table between two versions, see [`Table::table_changes`].
+///
+/// Every row yielded by [`TableChangesScan::execute`] contains the columns of the changed data
+/// followed by the [`CHANGE_TYPE_COL_NAME`], [`COMMIT_VERSION_COL_NAME`] and
+/// [`COMMIT_TIMESTAMP_COL_NAME`] columns.
+///
+/// [`Table::table_changes`]: crate::Table::table_changes
+pub struct TableChangesScan<JRC: Send, PRC: Send + Sync> {
+    table_root: Url,
+    table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
+    schema: Schema,
+    partition_columns: Vec<String>,
+    column_mapping: bool,
+    start_version: Version,
+    end_version: Version,
+    commits: Vec<CommitChanges>,
+}
+
+impl<JRC: Send, PRC: Send + Sync> std::fmt::Debug for TableChangesScan<JRC, PRC> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("TableChangesScan")
+            .field("start_version", &self.start_version)
+            .field("end_version", &self.end_version)
+            .finish()
+    }
+}
+
+impl<JRC: Send, PRC: Send + Sync + 'static> TableChangesScan<JRC, PRC> {
+    /// Create a new [`TableChangesScan`] for the versions `start_version..=end_version`.
+    ///
+    /// If no end version is supplied, all changes up to the latest version are included. Returns
+    /// [`Error::ChangeDataFeedNotEnabled`] if the change data feed is not enabled for any of the
+    /// versions in the range.
+    pub(crate) async fn try_new(
+        table_root: Url,
+        table_client: Arc<dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>>,
+        start_version: Version,
+        end_version: Option<Version>,
+    ) -> DeltaResult<Self> {
+        if let Some(end_version) = end_version {
+            if end_version < start_version {
+                return Err(Error::Generic(format!(
+                    "end version {} is before start version {}",
+                    end_version, start_version
+                )));
+            }
+        }
+
+        let start_snapshot = Snapshot::try_new(
+            table_root.clone(),
+            table_client.clone(),
+            Some(start_version),
+        )
+        .await?;
+        let mut metadata = start_snapshot.metadata().await?;
+
+        let log_root = LogPath(&table_root).child("_delta_log/")?;
+        let commit_files =
+            list_commit_files(table_client.as_ref(), &log_root, start_version, end_version).await?;
+
+        let read_schema = ArrowSchema {
+            fields: Fields::from_iter([
+                ActionType::Add.field(),
+                ActionType::Remove.field(),
+                ActionType::Cdc.field(),
+                ActionType::CommitInfo.field(),
+                ActionType::Metadata.field(),
+            ]),
+            metadata: Default::default(),
+        };
+        let read_schema = Arc::new(Schema::try_from(&read_schema)?);
+        let json_handler = table_client.get_json_handler();
+
+        let mut commits = Vec::with_capacity(commit_files.len());
+        for (version, file) in commit_files {
+            let last_modified = file.last_modified;
+            let read_contexts = json_handler.contextualize_file_reads(vec![file], None)?;
+            let batches = json_handler
+                .read_json_files(read_contexts, read_schema.clone())?
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            let mut changes = CommitChanges {
+                version,
+                // the file modification time is only used if the commit carries no timestamp
+                timestamp: last_modified,
+                cdc: Vec::new(),
+                adds: Vec::new(),
+                removes: Vec::new(),
+            };
+            let mut commit_timestamp = None;
+            for batch in batches {
+                if commit_timestamp.is_none() {
+                    commit_timestamp = read_commit_timestamp(&batch)?;
+                }
+                let actions = parse_actions(
+                    &batch,
+                    &[
+                        ActionType::Metadata,
+                        ActionType::Cdc,
+                        ActionType::Add,
+                        ActionType::Remove,
+                    ],
+                )?;
+                for action in actions {
+                    match action {
+                        // the metadata of the start version is already reflected in the snapshot
+                        Action::Metadata(meta) if version > start_version => metadata = meta,
+                        Action::Cdc(cdc) => changes.cdc.push(cdc),
+                        Action::Add(add) if add.data_change => changes.adds.push(add),
+                        Action::Remove(remove) if remove.data_change => {
+                            changes.removes.push(remove)
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            if let Some(timestamp) = commit_timestamp {
+                changes.timestamp = timestamp;
+            }
+
+            if !metadata
+                .config_bool(ENABLE_CHANGE_DATA_FEED_KEY)?
+                .unwrap_or(false)
+            {
+                return Err(Error::ChangeDataFeedNotEnabled(version));
+            }
+            commits.push(changes);
+        }
+
+        let end_version = commits
+            .last()
+            .map(|commit| commit.version)
+            .unwrap_or(start_version);
+
+        Ok(Self {
+            table_root,
+            table_client,
+            schema: metadata.schema()?,
+            column_mapping: column_mapping_enabled(&metadata.configuration),
+            partition_columns: metadata.partition_columns,
+            start_version,
+            end_version,
+            commits,
+        })
+    }
+
+    /// The first version included in the changes.
+    pub fn start_version(&self) -> Version {
+        self.start_version
+    }
+
+    /// The last version included in the changes.
+    pub fn end_version(&self) -> Version {
+        self.end_version
+    }
+
+    /// Schema of the table data at the end version, excluding the change data feed columns.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Read all changes in the version range, ordered by commit version.
+    pub async fn execute(&self) -> DeltaResult<Vec<RecordBatch>> {
+        let mut cdc_schema = self.schema.clone();
+        cdc_schema.fields.push(StructField::new(
+            CHANGE_TYPE_COL_NAME,
+            DataType::Primitive(PrimitiveType::String),
+            false,
+        ));
+        let cdc_reader = Arc::new(self.data_file_reader(Arc::new(cdc_schema)));
+        let data_reader = Arc::new(self.data_file_reader(Arc::new(self.schema.clone())));
+
+        let mut results = Vec::new();
+        for commit in &self.commits {
+            if !commit.cdc.is_empty() {
+                for cdc in &commit.cdc {
+                    let file = changed_file(&cdc.path, &cdc.partition_values, cdc.size, &None);
+                    let batch = self.read_file(cdc_reader.clone(), file).await?;
+                    if let Some(batch) = batch {
+                        results.push(annotate_batch(&batch, None, commit)?);
+                    }
+                }
+                continue;
+            }
+
+            // without explicit change data, all rows of added files are inserts and
+            // all rows of removed files are deletes.
+            for add in &commit.adds {
+                let file = changed_file(
+                    &add.path,
+                    &add.partition_values,
+                    add.size,
+                    &add.deletion_vector,
+                );
+                let batch = self.read_file(data_reader.clone(), file).await?;
+                if let Some(batch) = batch {
+                    results.push(annotate_batch(&batch, Some("insert"), commit)?);
+                }
+            }
+            for remove in &commit.removes {
+                let size = match remove.size {
+                    Some(size) => size,
+                    None => self.removed_file_size(commit.version, &remove.path).await?,
+                };
+                let file = changed_file(
+                    &remove.path,
+                    &remove.partition_values.clone().unwrap_or_default(),
+                    size,
+                    &remove.deletion_vector,
+                );
+                let batch = self.read_file(data_reader.clone(), file).await?;
+                if let Some(batch) = batch {
+                    results.push(annotate_batch(&batch, Some("delete"), commit)?);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// A reader for changed files, returning batches with the columns of `schema` where the
+    /// values of partition columns are taken from the partition values of the actions.
+    fn data_file_reader(&self, schema: SchemaRef) -> DataFileReader<PRC> {
+        DataFileReader::new(
+            self.table_client.get_parquet_handler(),
+            self.table_root.clone(),
+            schema,
+            self.partition_columns.clone(),
+            self.column_mapping,
+        )
+    }
+
+    /// Read the remaining rows of a changed `file` into a single batch, or `None` if it has none.
+    async fn read_file(
+        &self,
+        reader: Arc<DataFileReader<PRC>>,
+        file: Add,
+    ) -> DeltaResult<Option<RecordBatch>> {
+        let dv = match &file.deletion_vector {
+            Some(dv_def) => Some(dv_def.read(
+                self.table_client.get_file_system_client(),
+                self.table_root.clone(),
+            )?),
+            None => None,
+        };
+        let batches = reader
+            .read(DataFile { add: file, dv })
+            .try_collect::<Vec<_>>()
+            .await?;
+        if batches.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(concat_batches(&batches[0].schema(), &batches)?))
+    }
+
+    /// The size of a file removed in `version` whose `remove` action does not record it, taken
+    /// from the `add` action of the file in the previous version.
+    async fn removed_file_size(&self, version: Version, path: &str) -> DeltaResult<i64> {
+        let snapshot = Snapshot::try_new(
+            self.table_root.clone(),
+            self.table_client.clone(),
+            Some(version.saturating_sub(1)),
+        )
+        .await?;
+        let mut files = snapshot
+            .scan()
+            .await?
+            .passthrough()
+            .build()?
+            .files_stream()?;
+        while let Some(add) = files.try_next().await? {
+            if add.path == path {
+                return Ok(add.size);
+            }
+        }
+        Err(Error::FileNotFound(path.to_string()))
+    }
+}
+
+/// An [`Add`] describing a file whose rows changed, to be read with a [`DataFileReader`].
+fn changed_file(
+    path: &str,
+    partition_values: &HashMap<String, Option<String>>,
+    size: i64,
+    deletion_vector: &Option<DeletionVectorDescriptor>,
+) -> Add {
+    Add {
+        path: path.to_string(),
+        partition_values: partition_values.clone(),
+        size,
+        modification_time: 0,
+        data_change: true,
+        stats: None,
+        tags: HashMap::new(),
+        deletion_vector: deletion_vector.clone(),
+        base_row_id: None,
+        default_row_commit_version: None,
+    }
+}
+
+/// Extract the commit timestamp in milliseconds from the `commitInfo` action, if present.
+pub(crate) fn read_commit_timestamp(batch: &RecordBatch) -> DeltaResult<Option<i64>> {
+    let Some(commit_info) = batch.column_by_name("commitInfo") else {
+        return Ok(None);
+    };
+    let timestamps = commit_info
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .and_then(|arr| arr.column_by_name("timestamp"))
+        .and_then(|col| col.as_any().downcast_ref::<Int64Array>())
+        .ok_or(Error::UnexpectedColumnType(
+            "Cannot downcast commitInfo.timestamp to Int64Array".into(),
+        ))?;
+    Ok(timestamps.iter().flatten().next())
+}
+
+/// Append the change data feed columns to a batch of changed rows.
+///
+/// If `change_type` is `None`, the batch is expected to carry its own [`CHANGE_TYPE_COL_NAME`]
+/// column, as is the case for files in the `_change_data/` folder.
+fn annotate_batch(
+    batch: &RecordBatch,
+    change_type: Option<&str>,
+    commit: &CommitChanges,
+) -> DeltaResult<RecordBatch> {
+    let num_rows = batch.num_rows();
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(schema.fields().len() + 3);
+    let mut columns = Vec::with_capacity(schema.fields().len() + 3);
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        if field.name() != CHANGE_TYPE_COL_NAME {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+        }
+    }
+
+    let change_types = match change_type {
+        Some(change_type) => Arc::new(StringArray::from(vec![change_type; num_rows])),
+        None => batch
+            .column_by_name(CHANGE_TYPE_COL_NAME)
+            .ok_or(Error::MissingColumn(CHANGE_TYPE_COL_NAME.into()))?
+            .clone(),
+    };
+    fields.push(Field::new(CHANGE_TYPE_COL_NAME, ArrowDataType::Utf8, false));
+    columns.push(change_types);
+
+    fields.push(Field::new(
+        COMMIT_VERSION_COL_NAME,
+        ArrowDataType::Int64,
+        false,
+    ));
+    columns.push(Arc::new(Int64Array::from(vec![
+        commit.version as i64;
+        num_rows
+    ])));
+
+    fields.push(Field::new(
+        COMMIT_TIMESTAMP_COL_NAME,
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        false,
+    ));
+    columns.push(Arc::new(
+        TimestampMicrosecondArray::from(vec![commit.timestamp * 1000; num_rows])
+            .with_timezone("UTC"),
+    ));
+
+    Ok(RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(fields)),
+        columns,
+    )?)
+}
+
+/// List the commit files for the versions `start_version..=end_version` in ascending order.
+pub(crate) async fn list_commit_files<JRC: Send, PRC: Send>(
+    table_client: &dyn TableClient<JsonReadContext = JRC, ParquetReadContext = PRC>,
+    log_root: &Url,
+    start_version: Version,
+    end_version: Option<Version>,
+) -> DeltaResult<Vec<(Version, FileMeta)>> {
+    let fs_client = table_client.get_file_system_client();
+    let start_from = log_root.join(&format!("{:020}", start_version))?;
+
+    let mut commit_files = Vec::new();
+    let mut stream = fs_client.list_from(&start_from).await?;
+    while let Some(maybe_meta) = stream.next().await {
+        let meta = maybe_meta?;
+        if !LogPath(&meta.location).is_commit_file() {
+            continue;
+        }
+        if let Some(version) = LogPath(&meta.location).commit_version() {
+            if version >= start_version && end_version.is_none_or(|end| version <= end) {
+                commit_files.push((version, meta));
+            }
+        }
+    }
+    commit_files.sort_unstable_by_key(|(version, _)| *version);
+
+    // the range must be contiguous and cover the requested end version
+    let expected = start_version..;
+    if commit_files
+        .iter()
+        .zip(expected)
+        .any(|((version, _), expected)| *version != expected)
+    {
+        return Err(Error::MissingVersion);
+    }
+    match (end_version, commit_files.last()) {
+        (_, None) => return Err(Error::MissingVersion),
+        (Some(end), Some((version, _))) if *version != end => return Err(Error::MissingVersion),
+        _ => (),
+    }
+
+    Ok(commit_files)
+}
@@ -17,7 +17,7 @@ async fn dv_table() -> Result<(), Box<dyn std::error::Error>> {
 
     let table = Table::new(url, table_client);
     let snapshot = table.snapshot(None).await?;
-    let scan = snapshot.scan().await?.build();
+    let scan = snapshot.scan().await?.build()?;
 
     let stream = scan.execute().await?.into_iter();
     for batch in stream {
@@ -39,7 +39,7 @@ async fn non_dv_table() -> Result<(), Box<dyn std::error::Error>> {
 
     let table = Table::new(url, table_client);
     let snapshot = table.snapshot(None).await?;
-    let scan = snapshot.scan().await?.build();
+    let scan = snapshot.scan().await?.build()?;
 
     let stream = scan.execute().await?.into_iter();
     for batch in stream {
@@ -1,11 +1,19 @@
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
-use arrow::array::{ArrayRef, Int32Array, StringArray};
+use arrow::array::{
+    ArrayRef, Int32Array, Int64Array, StringArray, StructArray, TimestampMicrosecondArray,
+};
+use arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema};
 use arrow::error::ArrowError;
 use arrow::record_batch::RecordBatch;
 use deltakernel::client::DefaultTableClient;
-use deltakernel::expressions::Expression;
-use deltakernel::Table;
+use deltakernel::expressions::{Expression, Scalar};
+use deltakernel::scan::SkippingApplicability;
+use deltakernel::schema::{DataType, StructType};
+use deltakernel::snapshot::LogCache;
+use deltakernel::{ActionType, Error, Table};
+use futures::TryStreamExt;
 use object_store::{memory::InMemory, path::Path, ObjectStore};
 use parquet::arrow::arrow_writer::ArrowWriter;
 use parquet::file::properties::WriterProperties;
@@ -16,7 +24,7 @@ const PARQUET_FILE2: &str = "part-00001-c506e79a-0bf8-4e2b-a42b-9731b2e490ae-c00
 
 const METADATA: &str = r#"{"commitInfo":{"timestamp":1587968586154,"operation":"WRITE","operationParameters":{"mode":"ErrorIfExists","partitionBy":"[]"},"isBlindAppend":true}}
 {"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
-{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1587968585495}}"#;
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1587968585495}}"#;
 
 enum TestAction {
     Add(String),
@@ -28,7 +36,7 @@ fn generate_commit(actions: Vec<TestAction>) -> String {
     actions
             .into_iter()
             .map(|test_action| match test_action {
-                TestAction::Add(path) => format!(r#"{{"{action}":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true, "stats":"{{\"numRecords\":2,\"nullCount\":{{\"ids\":0}},\"minValues\":{{\"ids\": 0}},\"maxValues\":{{\"ids\":2}}}}"}}}}"#, action = "add", path = path),
+                TestAction::Add(path) => format!(r#"{{"{action}":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true, "stats":"{{\"numRecords\":2,\"nullCount\":{{\"id\":0}},\"minValues\":{{\"id\": 0}},\"maxValues\":{{\"id\":2}}}}"}}}}"#, action = "add", path = path),
                 TestAction::Remove(path) => format!(r#"{{"{action}":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#, action = "remove", path = path),
                 TestAction::Metadata => METADATA.into(),
             })
@@ -92,7 +100,7 @@ async fn single_commit_two_add_files() -> Result<(), Box<dyn std::error::Error>>
     let expected_data = vec![batch.clone(), batch];
 
     let snapshot = table.snapshot(None).await?;
-    let scan = snapshot.scan().await?.build();
+    let scan = snapshot.scan().await?.build()?;
 
     let mut files = 0;
     let stream = scan.execute().await?.into_iter().zip(expected_data);
@@ -138,7 +146,7 @@ async fn two_commits() -> Result<(), Box<dyn std::error::Error>> {
     let expected_data = vec![batch.clone(), batch];
 
     let snapshot = table.snapshot(None).await.unwrap();
-    let scan = snapshot.scan().await?.build();
+    let scan = snapshot.scan().await?.build()?;
 
     let mut files = 0;
     let stream = scan.execute().await?.into_iter().zip(expected_data);
@@ -188,7 +196,7 @@ async fn remove_action() -> Result<(), Box<dyn std::error::Error>> {
     let expected_data = vec![batch];
 
     let snapshot = table.snapshot(None).await?;
-    let scan = snapshot.scan().await?.build();
+    let scan = snapshot.scan().await?.build()?;
 
     let stream = scan.execute().await?.into_iter().zip(expected_data);
 
@@ -207,7 +215,7 @@ async fn stats() -> Result<(), Box<dyn std::error::Error>> {
         actions
             .into_iter()
             .map(|test_action| match test_action {
-                TestAction::Add(path) => format!(r#"{{"{action}":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true, "stats":"{{\"numRecords\":2,\"nullCount\":{{\"ids\":0}},\"minValues\":{{\"ids\": 3}},\"maxValues\":{{\"ids\":5}}}}"}}}}"#, action = "add", path = path),
+                TestAction::Add(path) => format!(r#"{{"{action}":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true, "stats":"{{\"numRecords\":2,\"nullCount\":{{\"id\":0}},\"minValues\":{{\"id\": 3}},\"maxValues\":{{\"id\":5}}}}"}}}}"#, action = "add", path = path),
                 TestAction::Remove(path) => format!(r#"{{"{action}":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#, action = "remove", path = path),
                 TestAction::Metadata => METADATA.into(),
             })
@@ -225,7 +233,7 @@ async fn stats() -> Result<(), Box<dyn std::error::Error>> {
         ]),
     )
     .await?;
-    // storage.add_commit(1, &format!("{}\n", r#"{{"add":{{"path":"doesnotexist","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true, "stats":"{{\"numRecords\":2,\"nullCount\":{{\"ids\":0}},\"minValues\":{{\"ids\": 0}},\"maxValues\":{{\"ids\":2}}}}"}}}}"#));
+    // storage.add_commit(1, &format!("{}\n", r#"{{"add":{{"path":"doesnotexist","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true, "stats":"{{\"numRecords\":2,\"nullCount\":{{\"id\":0}},\"minValues\":{{\"id\": 0}},\"maxValues\":{{\"id\":2}}}}"}}}}"#));
     add_commit(
         storage.as_ref(),
         1,
@@ -246,10 +254,10 @@ async fn stats() -> Result<(), Box<dyn std::error::Error>> {
     let snapshot = table.snapshot(None).await?;
 
     let predicate = Expression::LessThan(
-        Box::new(Expression::Column(String::from("ids"))),
-        Box::new(Expression::Literal(2)),
+        Box::new(Expression::Column(String::from("id"))),
+        Box::new(Expression::Literal(Scalar::Integer(2))),
     );
-    let scan = snapshot.scan().await?.with_predicate(predicate).build();
+    let scan = snapshot.scan().await?.with_predicate(predicate).build()?;
 
     let mut files = 0;
     let stream = scan.execute().await?.into_iter().zip(expected_data);
@@ -261,3 +269,2058 @@ async fn stats() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(1, files, "Expected to have scanned one file");
     Ok(())
 }
+
+#[tokio::test]
+async fn overwrite_file() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        1,
+        generate_commit(vec![
+            TestAction::Remove(PARQUET_FILE1.to_string()),
+            // a remove without a prior add is ignored
+            TestAction::Remove("doesnotexist".to_string()),
+            TestAction::Add(PARQUET_FILE2.to_string()),
+        ]),
+    )
+    .await?;
+    // only the file that is still part of the table exists in storage
+    storage
+        .put(&Path::from(PARQUET_FILE2), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///").unwrap();
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+
+    let scan = snapshot.scan().await?.build()?;
+    let files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path(), PARQUET_FILE2);
+    assert_eq!(scan.execute().await?, vec![batch.clone()]);
+
+    // removes must also be honored when data skipping is applied
+    let predicate = Expression::LessThan(
+        Box::new(Expression::Column(String::from("id"))),
+        Box::new(Expression::Literal(Scalar::Integer(2))),
+    );
+    let scan = snapshot.scan().await?.with_predicate(predicate).build()?;
+    let files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path(), PARQUET_FILE2);
+    assert_eq!(scan.execute().await?, vec![batch]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn remove_file_with_deletion_vector() -> Result<(), Box<dyn std::error::Error>> {
+    fn add_with_dv(dv: &str) -> String {
+        format!(
+            r#"{{"add":{{"path":"{PARQUET_FILE1}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"deletionVector":{dv}}}}}"#
+        )
+    }
+    fn remove_with_dv(dv: &str) -> String {
+        format!(
+            r#"{{"remove":{{"path":"{PARQUET_FILE1}","deletionTimestamp":1587968586000,"dataChange":true,"deletionVector":{dv}}}}}"#
+        )
+    }
+    let dv1 = r#"{"storageType":"u","pathOrInlineDv":"vBn[lx{q8@P<9BNH/isA","offset":1,"sizeInBytes":36,"cardinality":2}"#;
+    let dv2 = r#"{"storageType":"u","pathOrInlineDv":"ab^-aqEH.-t@S}K{vb[*k^","offset":1,"sizeInBytes":36,"cardinality":3}"#;
+
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        1,
+        generate_commit(vec![TestAction::Remove(PARQUET_FILE1.to_string())]) + &add_with_dv(dv1),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        2,
+        format!("{}\n{}\n", remove_with_dv(dv1), add_with_dv(dv2)),
+    )
+    .await?;
+
+    let location = Url::parse("memory:///").unwrap();
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let scan = snapshot.scan().await?.build()?;
+    let files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].deletion_vector().unwrap().cardinality, 3);
+    Ok(())
+}
+
+const CDF_METADATA: &str = r#"{"commitInfo":{"timestamp":1587968586154,"operation":"WRITE","operationParameters":{"mode":"ErrorIfExists","partitionBy":"[]"},"isBlindAppend":true}}
+{"protocol":{"minReaderVersion":1,"minWriterVersion":4}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{"delta.enableChangeDataFeed":"true"},"createdTime":1587968585495}}"#;
+
+#[tokio::test]
+async fn table_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        CDF_METADATA.to_string()
+            + "\n"
+            + &generate_commit(vec![TestAction::Add(PARQUET_FILE1.to_string())]),
+    )
+    .await?;
+    // a commit without change data files, changes are derived from adds and removes
+    add_commit(
+        storage.as_ref(),
+        1,
+        generate_commit(vec![
+            TestAction::Remove(PARQUET_FILE1.to_string()),
+            TestAction::Add(PARQUET_FILE2.to_string()),
+        ]),
+    )
+    .await?;
+    // a commit with change data files, the adds and removes are ignored
+    let cdc_file =
+        "_change_data/cdc-00000-3c6e7d45-6ac6-4bb2-8af3-3d3a9d6b3f8a.c000.snappy.parquet";
+    add_commit(
+        storage.as_ref(),
+        2,
+        [
+            r#"{"commitInfo":{"timestamp":1587968590000,"operation":"UPDATE"}}"#.to_string(),
+            format!(r#"{{"cdc":{{"path":"{cdc_file}","partitionValues":{{}},"size":262,"dataChange":false}}}}"#),
+            generate_commit(vec![
+                TestAction::Remove(PARQUET_FILE2.to_string()),
+                TestAction::Add(PARQUET_FILE1.to_string()),
+            ]),
+        ]
+        .join("\n"),
+    )
+    .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+        .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE2), load_parquet(&batch).into())
+        .await?;
+    let cdc_batch = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(Int32Array::from(vec![2, 2])) as ArrayRef),
+        (
+            "val",
+            Arc::new(StringArray::from(vec!["b", "x"])) as ArrayRef,
+        ),
+        (
+            "_change_type",
+            Arc::new(StringArray::from(vec![
+                "update_preimage",
+                "update_postimage",
+            ])) as ArrayRef,
+        ),
+    ])?;
+    storage
+        .put(&Path::from(cdc_file), load_parquet(&cdc_batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+
+    let changes = table.table_changes(0, None).await?;
+    assert_eq!(changes.start_version(), 0);
+    assert_eq!(changes.end_version(), 2);
+
+    let batches = changes.execute().await?;
+    let mut rows = Vec::new();
+    for batch in &batches {
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        let ids = column("id");
+        let ids = ids.as_any().downcast_ref::<Int32Array>().unwrap();
+        let change_types = column("_change_type");
+        let change_types = change_types.as_any().downcast_ref::<StringArray>().unwrap();
+        let versions = column("_commit_version");
+        let versions = versions.as_any().downcast_ref::<Int64Array>().unwrap();
+        let timestamps = column("_commit_timestamp");
+        let timestamps = timestamps
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        for i in 0..batch.num_rows() {
+            rows.push((
+                ids.value(i),
+                change_types.value(i).to_string(),
+                versions.value(i),
+                timestamps.value(i),
+            ));
+        }
+    }
+
+    let expected = [
+        (1, "insert", 0, 1587968586154000),
+        (2, "insert", 0, 1587968586154000),
+        (3, "insert", 0, 1587968586154000),
+        (1, "insert", 1, 1587968586000000),
+        (2, "insert", 1, 1587968586000000),
+        (3, "insert", 1, 1587968586000000),
+        (1, "delete", 1, 1587968586000000),
+        (2, "delete", 1, 1587968586000000),
+        (3, "delete", 1, 1587968586000000),
+        (2, "update_preimage", 2, 1587968590000000),
+        (2, "update_postimage", 2, 1587968590000000),
+    ];
+    assert_eq!(rows.len(), expected.len());
+    for (row, expected) in rows.iter().zip(expected) {
+        assert_eq!(row.0, expected.0);
+        assert_eq!(row.1, expected.1);
+        assert_eq!(row.2, expected.2);
+        // commit 1 has no commitInfo, so its timestamp is taken from the log file
+        if expected.2 != 1 {
+            assert_eq!(row.3, expected.3);
+        }
+    }
+
+    let changes = table.table_changes(1, Some(1)).await?;
+    assert_eq!(changes.end_version(), 1);
+    assert_eq!(changes.execute().await?.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn partitioned_table_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":4}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["part"],"configuration":{"delta.enableChangeDataFeed":"true"},"createdTime":1587968585495}}"#;
+    let data_file = "part=a/part-00000.parquet";
+    let data = load_parquet(&batch);
+    let size = data.len();
+    add_commit(
+        storage.as_ref(),
+        0,
+        [
+            metadata.to_string(),
+            format!(r#"{{"add":{{"path":"{data_file}","partitionValues":{{"part":"a"}},"size":{size},"modificationTime":1587968586000,"dataChange":true}}}}"#),
+        ]
+        .join("\n"),
+    )
+    .await?;
+    // the remove does not record the size of the file, it is taken from the add
+    add_commit(
+        storage.as_ref(),
+        1,
+        format!(r#"{{"remove":{{"path":"{data_file}","deletionTimestamp":1587968587000,"dataChange":true,"partitionValues":{{"part":"a"}}}}}}"#),
+    )
+    .await?;
+    let cdc_file = "_change_data/part=b/cdc-00000.parquet";
+    add_commit(
+        storage.as_ref(),
+        2,
+        format!(r#"{{"cdc":{{"path":"{cdc_file}","partitionValues":{{"part":"b"}},"size":262,"dataChange":false}}}}"#),
+    )
+    .await?;
+    storage.put(&Path::from(data_file), data.into()).await?;
+    let cdc_batch = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(Int32Array::from(vec![4])) as ArrayRef),
+        ("val", Arc::new(StringArray::from(vec!["d"])) as ArrayRef),
+        (
+            "_change_type",
+            Arc::new(StringArray::from(vec!["insert"])) as ArrayRef,
+        ),
+    ])?;
+    storage
+        .put(&Path::from(cdc_file), load_parquet(&cdc_batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+
+    let batches = table.table_changes(0, None).await?.execute().await?;
+    let mut rows = Vec::new();
+    for batch in &batches {
+        assert_eq!(
+            batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "id",
+                "val",
+                "part",
+                "_change_type",
+                "_commit_version",
+                "_commit_timestamp"
+            ]
+        );
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        let ids = column("id");
+        let ids = ids.as_any().downcast_ref::<Int32Array>().unwrap();
+        let parts = column("part");
+        let parts = parts.as_any().downcast_ref::<StringArray>().unwrap();
+        let change_types = column("_change_type");
+        let change_types = change_types.as_any().downcast_ref::<StringArray>().unwrap();
+        for i in 0..batch.num_rows() {
+            rows.push((
+                ids.value(i),
+                parts.value(i).to_string(),
+                change_types.value(i).to_string(),
+            ));
+        }
+    }
+    let rows: Vec<_> = rows
+        .iter()
+        .map(|(id, part, change_type)| (*id, part.as_str(), change_type.as_str()))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            (1, "a", "insert"),
+            (2, "a", "insert"),
+            (3, "a", "insert"),
+            (1, "a", "delete"),
+            (2, "a", "delete"),
+            (3, "a", "delete"),
+            (4, "b", "insert"),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn table_changes_not_enabled() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+
+    let result = table.table_changes(0, None).await;
+    assert!(matches!(
+        result,
+        Err(deltakernel::Error::ChangeDataFeedNotEnabled(0))
+    ));
+    assert!(matches!(
+        table.table_changes(0, Some(3)).await,
+        Err(deltakernel::Error::MissingVersion)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn partition_skipping() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_int\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["part_int"],"configuration":{},"createdTime":1587968585495}}"#;
+    let add = |path: &str, value: &str| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{"part_int":{value}}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#
+        )
+    };
+    let files = [
+        ("part_int=5/part-00000.parquet", r#""5""#),
+        ("part_int=6/part-00000.parquet", r#""6""#),
+        (
+            "part_int=__HIVE_DEFAULT_PARTITION__/part-00000.parquet",
+            r#""__HIVE_DEFAULT_PARTITION__""#,
+        ),
+        ("part_int=null/part-00000.parquet", "null"),
+    ];
+    let commit = std::iter::once(metadata.to_string())
+        .chain(files.iter().map(|(path, value)| add(path, value)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    add_commit(storage.as_ref(), 0, commit).await?;
+    for (path, _) in files {
+        storage
+            .put(&Path::from(path), load_parquet(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+
+    let scan = snapshot.scan().await?.build()?;
+    assert_eq!(scan.files_stream()?.try_collect::<Vec<_>>().await?.len(), 4);
+
+    for (literal, expected) in [
+        (Scalar::Integer(5), "part_int=5/part-00000.parquet"),
+        (Scalar::Long(6), "part_int=6/part-00000.parquet"),
+    ] {
+        let predicate = Expression::Equal(
+            Box::new(Expression::Column("part_int".to_string())),
+            Box::new(Expression::Literal(literal)),
+        );
+        let scan = snapshot.scan().await?.with_predicate(predicate).build()?;
+        let files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path(), expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn combined_predicates() -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Arc::new(InMemory::new());
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1587968585495}}"#;
+    let add = |path: &str, min: i32, max: i32| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"stats":"{{\"numRecords\":3,\"nullCount\":{{\"id\":0}},\"minValues\":{{\"id\":{min}}},\"maxValues\":{{\"id\":{max}}}}}"}}}}"#
+        )
+    };
+    let commit = [
+        metadata.to_string(),
+        add("part-00000.parquet", 0, 2),
+        add("part-00001.parquet", 10, 12),
+        add("part-00002.parquet", 20, 22),
+    ]
+    .join("\n");
+    add_commit(storage.as_ref(), 0, commit).await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+
+    let lower = Expression::GreaterThan(
+        Box::new(Expression::Column("id".to_string())),
+        Box::new(Expression::Literal(Scalar::Integer(5))),
+    );
+    let upper = Expression::LessThan(
+        Box::new(Expression::Column("id".to_string())),
+        Box::new(Expression::Literal(Scalar::Integer(15))),
+    );
+    let paths = |files: Vec<deltakernel::actions::Add>| {
+        let mut paths = files.into_iter().map(|add| add.path).collect::<Vec<_>>();
+        paths.sort();
+        paths
+    };
+
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_predicates(vec![lower.clone(), upper.clone()])
+        .build()?;
+    let combined = paths(scan.files_stream()?.try_collect().await?);
+    assert_eq!(combined, vec!["part-00001.parquet"]);
+
+    let predicate = Expression::And(Box::new(lower), Box::new(upper));
+    let scan = snapshot.scan().await?.with_predicate(predicate).build()?;
+    assert_eq!(paths(scan.files_stream()?.try_collect().await?), combined);
+
+    let scan = snapshot.scan().await?.with_predicates(vec![]).build()?;
+    assert!(scan.predicate().is_none());
+    assert_eq!(scan.files_stream()?.try_collect::<Vec<_>>().await?.len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn partition_columns_in_scan() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_int\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_ts\",\"type\":\"timestamp\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["part_int","part_ts"],"configuration":{},"createdTime":1587968585495}}"#;
+    let add = |path: &str, part_int: &str| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{"part_int":{part_int},"part_ts":"2021-01-01 00:00:01"}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#
+        )
+    };
+    let files = [
+        ("part_int=5/part-00000.parquet", r#""5""#),
+        (
+            "part_int=__HIVE_DEFAULT_PARTITION__/part-00000.parquet",
+            r#""__HIVE_DEFAULT_PARTITION__""#,
+        ),
+    ];
+    let commit = std::iter::once(metadata.to_string())
+        .chain(files.iter().map(|(path, value)| add(path, value)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    add_commit(storage.as_ref(), 0, commit).await?;
+    for (path, _) in files {
+        storage
+            .put(&Path::from(path), load_parquet(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+
+    let scan = snapshot.scan().await?.build()?;
+    let batches = scan.execute().await?;
+    assert_eq!(batches.len(), 2);
+    let ts = 1609459201000000;
+    for (batch, part_int) in batches.iter().zip([Some(5), None]) {
+        assert_eq!(batch.num_columns(), 4);
+        assert_eq!(batch.column(0), generate_simple_batch()?.column(0));
+        let expected: ArrayRef = Arc::new(Int32Array::from(vec![part_int; 3]));
+        assert_eq!(batch.column_by_name("part_int").unwrap(), &expected);
+        let expected: ArrayRef =
+            Arc::new(TimestampMicrosecondArray::from(vec![ts; 3]).with_timezone("UTC"));
+        assert_eq!(batch.column_by_name("part_ts").unwrap(), &expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn projection_with_partition_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_str\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_int\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["part_str","part_int"],"configuration":{},"createdTime":1587968585495}}"#;
+    let path = "part_str=x/part_int=5/part-00000.parquet";
+    let add = format!(
+        r#"{{"add":{{"path":"{path}","partitionValues":{{"part_str":"x","part_int":"5"}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#
+    );
+    add_commit(storage.as_ref(), 0, format!("{metadata}\n{add}")).await?;
+    storage
+        .put(&Path::from(path), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let table_schema = snapshot.schema().await?;
+    let project = |names: &[&str]| {
+        Arc::new(StructType::new(
+            names
+                .iter()
+                .map(|name| table_schema.field(name).unwrap().clone())
+                .collect(),
+        ))
+    };
+
+    let schema = project(&["part_int", "val", "part_str", "id"]);
+    let scan = snapshot.scan().await?.with_schema(schema).build()?;
+    let batches = scan.execute().await?;
+    assert_eq!(batches.len(), 1);
+    let names = batches[0]
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["part_int", "val", "part_str", "id"]);
+    let expected: Vec<ArrayRef> = vec![
+        Arc::new(Int32Array::from(vec![5, 5, 5])),
+        Arc::new(StringArray::from(vec!["a", "b", "c"])),
+        Arc::new(StringArray::from(vec!["x", "x", "x"])),
+        Arc::new(Int32Array::from(vec![1, 2, 3])),
+    ];
+    assert_eq!(batches[0].columns(), expected.as_slice());
+
+    // only partition columns, the rows still come from the data file
+    let schema = project(&["part_str"]);
+    let scan = snapshot.scan().await?.with_schema(schema).build()?;
+    let batches = scan.execute().await?;
+    assert_eq!(batches.len(), 1);
+    let expected: ArrayRef = Arc::new(StringArray::from(vec!["x", "x", "x"]));
+    assert_eq!(batches[0].columns(), &[expected]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn exact_schema() -> Result<(), Box<dyn std::error::Error>> {
+    // the file stores `id` as a long with field metadata and `val` as non-nullable
+    let id_field = ArrowField::new("id", ArrowDataType::Int64, true)
+        .with_metadata([("comment".to_string(), "written by writer".to_string())].into());
+    let val_field = ArrowField::new("val", ArrowDataType::Utf8, false);
+    let batch = RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![id_field, val_field])),
+        vec![
+            Arc::new(Int64Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["a", "b", "c"])),
+        ],
+    )?;
+    let storage = Arc::new(InMemory::new());
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_str\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_int\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["part_str","part_int"],"configuration":{},"createdTime":1587968585495}}"#;
+    let path = "part_str=x/part_int=5/part-00000.parquet";
+    let add = format!(
+        r#"{{"add":{{"path":"{path}","partitionValues":{{"part_str":"x","part_int":"5"}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#
+    );
+    add_commit(storage.as_ref(), 0, format!("{metadata}\n{add}")).await?;
+    storage
+        .put(&Path::from(path), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let table_schema = snapshot.schema().await?;
+    let schema = Arc::new(StructType::new(
+        ["val", "part_int", "id"]
+            .iter()
+            .map(|name| table_schema.field(name).unwrap().clone())
+            .collect(),
+    ));
+
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_schema(schema.clone())
+        .with_exact_schema(true)
+        .build()?;
+    let expected_schema = Arc::new(ArrowSchema::try_from(scan.logical_schema().as_ref())?);
+    let batches = scan.execute().await?;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].schema(), expected_schema);
+    let expected: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from(vec!["a", "b", "c"])),
+        Arc::new(Int32Array::from(vec![5, 5, 5])),
+        Arc::new(Int32Array::from(vec![1, 2, 3])),
+    ];
+    assert_eq!(batches[0].columns(), expected.as_slice());
+
+    // by default the columns keep the fields of the data file
+    let scan = snapshot.scan().await?.with_schema(schema).build()?;
+    let batches = scan.execute().await?;
+    assert_ne!(batches[0].schema(), expected_schema);
+    assert!(!batches[0].schema().field(0).is_nullable());
+    Ok(())
+}
+
+#[tokio::test]
+async fn multi_batch_file() -> Result<(), Box<dyn std::error::Error>> {
+    // the default parquet reader decodes files in batches of 1024 rows
+    let num_rows = 2500;
+    let ids = Int32Array::from_iter_values(0..num_rows);
+    let vals = StringArray::from_iter_values((0..num_rows).map(|i| i.to_string()));
+    let batch = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(ids) as ArrayRef),
+        ("val", Arc::new(vals) as ArrayRef),
+    ])?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let scan = snapshot.scan().await?.build()?;
+
+    let batches = scan.execute().await?;
+    let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    assert_eq!(rows, num_rows as usize);
+    let ids = batches
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .column_by_name("id")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap()
+                .values()
+                .to_vec()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(ids, (0..num_rows).collect::<Vec<_>>());
+    Ok(())
+}
+
+fn add_with_inline_dv(path: &str, dv: &str) -> String {
+    format!(
+        r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"deletionVector":{{"storageType":"i","pathOrInlineDv":"{dv}","sizeInBytes":44,"cardinality":3}}}}}}"#
+    )
+}
+
+#[tokio::test]
+async fn passthrough_scan() -> Result<(), Box<dyn std::error::Error>> {
+    // neither the data files nor the deletion vector exist, so reading them would fail
+    let storage = Arc::new(InMemory::new());
+    let add = format!(
+        r#"{{"add":{{"path":"{PARQUET_FILE1}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"deletionVector":{{"storageType":"p","pathOrInlineDv":"memory:///deletion_vector.bin","offset":1,"sizeInBytes":36,"cardinality":2}}}}}}"#
+    );
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE2.to_string()),
+        ]) + &add,
+    )
+    .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let scan = table
+        .snapshot(None)
+        .await?
+        .scan()
+        .await?
+        .passthrough()
+        .build()?;
+
+    let files = scan.files()?.try_concat().await?;
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|file| file.dv.is_none()));
+    // files are listed in log order
+    assert!(files[0].add.deletion_vector.is_none());
+    let dv = files[1].add.deletion_vector.as_ref().unwrap();
+    assert_eq!(dv.cardinality, 2);
+
+    assert!(matches!(scan.execute().await, Err(Error::Generic(_))));
+    assert!(scan.execute_stream().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn fully_deleted_file() -> Result<(), Box<dyn std::error::Error>> {
+    // inline deletion vector deleting rows 0, 1 and 2
+    let dv = "0000000000000000rr910000000000iXQKl0rr91000625c8Xg000310SSi2";
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![TestAction::Metadata])
+            + &add_with_inline_dv(PARQUET_FILE1, dv)
+            + "\n"
+            + &generate_commit(vec![TestAction::Add(PARQUET_FILE2.to_string())]),
+    )
+    .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+        .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE2), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let scan = snapshot.scan().await?.build()?;
+
+    assert_eq!(scan.files_stream()?.try_collect::<Vec<_>>().await?.len(), 2);
+    let batches = scan.execute().await?;
+    assert_eq!(batches, vec![batch]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn row_index_column() -> Result<(), Box<dyn std::error::Error>> {
+    // inline deletion vector deleting rows 0, 1 and 2
+    let dv = "0000000000000000rr910000000000iXQKl0rr91000625c8Xg000310SSi2";
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "id",
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])) as ArrayRef,
+        ),
+        (
+            "val",
+            Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e"])) as ArrayRef,
+        ),
+    ])?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![TestAction::Metadata])
+            + &add_with_inline_dv(PARQUET_FILE1, dv)
+            + "\n"
+            + &generate_commit(vec![TestAction::Add(PARQUET_FILE2.to_string())]),
+    )
+    .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+        .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE2), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_row_index("_row_index".to_string())
+        .build()?;
+
+    let batches = scan.execute().await?;
+    let columns = |name: &str| {
+        batches
+            .iter()
+            .map(|batch| batch.column_by_name(name).unwrap().clone())
+            .collect::<Vec<_>>()
+    };
+    // the indices of the first file skip the deleted rows, the second file starts from 0
+    let expected_ids: Vec<ArrayRef> = vec![
+        Arc::new(Int32Array::from(vec![4, 5])),
+        Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])),
+    ];
+    let expected_indices: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(vec![3, 4])),
+        Arc::new(Int64Array::from(vec![0, 1, 2, 3, 4])),
+    ];
+    assert_eq!(columns("id"), expected_ids);
+    assert_eq!(columns("_row_index"), expected_indices);
+    assert_eq!(batches[0].schema().fields().len(), 3);
+
+    let conflicting = snapshot
+        .scan()
+        .await?
+        .with_row_index("id".to_string())
+        .build();
+    assert!(matches!(conflicting, Err(Error::Generic(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn stream_row_groups() -> Result<(), Box<dyn std::error::Error>> {
+    // inline deletion vector deleting rows 0, 1 and 2
+    let dv = "0000000000000000rr910000000000iXQKl0rr91000625c8Xg000310SSi2";
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "id",
+            Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5])) as ArrayRef,
+        ),
+        (
+            "val",
+            Arc::new(StringArray::from(vec!["a", "b", "c", "d", "e"])) as ArrayRef,
+        ),
+    ])?;
+    // write row groups of two rows each
+    let mut data: Vec<u8> = Vec::new();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(2)
+        .build();
+    let mut writer = ArrowWriter::try_new(&mut data, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![TestAction::Metadata]) + &add_with_inline_dv(PARQUET_FILE1, dv),
+    )
+    .await?;
+    storage.put(&Path::from(PARQUET_FILE1), data.into()).await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_row_index("_row_index".to_string())
+        .build()?;
+
+    // the first row group is fully deleted, the others are yielded one at a time
+    let mut stream = scan.execute_stream()?;
+    let mut batches = Vec::new();
+    while let Some(batch) = stream.try_next().await? {
+        batches.push(batch);
+    }
+    let column = |name: &str| {
+        batches
+            .iter()
+            .map(|batch| batch.column_by_name(name).unwrap().clone())
+            .collect::<Vec<_>>()
+    };
+    let expected_ids: Vec<ArrayRef> = vec![
+        Arc::new(Int32Array::from(vec![4])),
+        Arc::new(Int32Array::from(vec![5])),
+    ];
+    let expected_indices: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(vec![3])),
+        Arc::new(Int64Array::from(vec![4])),
+    ];
+    assert_eq!(column("id"), expected_ids);
+    assert_eq!(column("_row_index"), expected_indices);
+    Ok(())
+}
+
+#[tokio::test]
+async fn evolved_schema() -> Result<(), Box<dyn std::error::Error>> {
+    // the file was written before the columns were reordered and `extra` was added
+    let metadata = |nullable: bool| {
+        METADATA.replace(
+            r#"[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}}]"#,
+            &format!(
+                r#"[{{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{{}}}},{{\"name\":\"extra\",\"type\":\"long\",\"nullable\":{nullable},\"metadata\":{{}}}},{{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{{}}}}]"#
+            ),
+        )
+    };
+    let batch = generate_simple_batch()?;
+    let read = |nullable: bool| {
+        let metadata = metadata(nullable);
+        let data = load_parquet(&batch);
+        async move {
+            assert!(metadata.contains("extra"));
+            let storage = Arc::new(InMemory::new());
+            let commit = metadata
+                + "\n"
+                + &generate_commit(vec![TestAction::Add(PARQUET_FILE1.to_string())]);
+            add_commit(storage.as_ref(), 0, commit).await?;
+            storage.put(&Path::from(PARQUET_FILE1), data.into()).await?;
+
+            let location = Url::parse("memory:///")?;
+            let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+            let table = Table::new(location, table_client);
+            let scan = table.snapshot(None).await?.scan().await?.build()?;
+            Ok::<_, Box<dyn std::error::Error>>(scan.execute().await)
+        }
+    };
+
+    let batches = read(true).await??;
+    assert_eq!(batches.len(), 1);
+    let names = batches[0]
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["val", "extra", "id"]);
+    assert_eq!(batches[0].column(0), batch.column(1));
+    assert_eq!(batches[0].column(1).null_count(), 3);
+    assert_eq!(batches[0].column(2), batch.column(0));
+
+    // a file without a required column can not be read
+    let result = read(false).await?;
+    assert!(matches!(result, Err(Error::MissingColumn(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn malformed_stats() -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Arc::new(InMemory::new());
+    // invalid escape sequence and lone surrogate in the stats json
+    let add = |path: &str, stats: &str| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"stats":"{stats}"}}}}"#
+        )
+    };
+    let commit = generate_commit(vec![
+        TestAction::Metadata,
+        TestAction::Add(PARQUET_FILE1.to_string()),
+    ]) + &add(
+        "invalid-escape.parquet",
+        r#"{\"minValues\":{\"val\":\"\\x\"}}"#,
+    ) + "\n"
+        + &add(
+            "lone-surrogate.parquet",
+            r#"{\"minValues\":{\"val\":\"\\ud800\"}}"#,
+        );
+    add_commit(storage.as_ref(), 0, commit).await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let predicate = Expression::GreaterThan(
+        Box::new(Expression::Column("id".to_string())),
+        Box::new(Expression::Literal(Scalar::Integer(10))),
+    );
+    let scan = table
+        .snapshot(None)
+        .await?
+        .scan()
+        .await?
+        .with_predicate(predicate.clone())
+        .build()?;
+
+    // the file with usable stats is skipped, the others are kept
+    let mut paths = scan
+        .files_stream()?
+        .map_ok(|add| add.path)
+        .try_collect::<Vec<_>>()
+        .await?;
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec!["invalid-escape.parquet", "lone-surrogate.parquet"]
+    );
+
+    // the stats can not be parsed
+    let scan = table
+        .snapshot(None)
+        .await?
+        .scan()
+        .await?
+        .with_predicate(predicate)
+        .with_strict_skipping(true)
+        .build()?;
+    assert!(scan.files_stream()?.try_collect::<Vec<_>>().await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn add_tags() -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Arc::new(InMemory::new());
+    let add = format!(
+        r#"{{"add":{{"path":"{PARQUET_FILE1}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"tags":{{"ZCUBE_ID":"zcube-1","ZCUBE_ZORDER_BY":null}}}}}}"#
+    );
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE2.to_string()),
+        ]) + &add,
+    )
+    .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let scan = table.snapshot(None).await?.scan().await?.build()?;
+
+    let mut files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+    files.sort_by(|a, b| a.path().cmp(b.path()));
+    assert_eq!(files.len(), 2);
+    let tags = files[0].tags();
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags["ZCUBE_ID"].as_deref(), Some("zcube-1"));
+    assert_eq!(tags["ZCUBE_ZORDER_BY"], None);
+    assert!(files[1].tags().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn execute_surfaces_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![TestAction::Metadata]) + &add_with_inline_dv(PARQUET_FILE1, "!!"),
+    )
+    .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let scan = snapshot.scan().await?.build()?;
+
+    // the deletion vector can not be decoded
+    assert!(scan.execute().await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn skipping_on_indexed_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    // statistics are only collected for the first column
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{"delta.dataSkippingNumIndexedCols":"1"},"createdTime":1587968585495}}"#;
+    let add = |path: &str, min: i32, max: i32| {
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true,"stats":"{{\"numRecords\":3,\"nullCount\":{{\"id\":0}},\"minValues\":{{\"id\":{min}}},\"maxValues\":{{\"id\":{max}}}}}"}}}}"#
+        )
+    };
+    let commit = [
+        metadata.to_string(),
+        add(PARQUET_FILE1, 1, 3),
+        add(PARQUET_FILE2, 4, 6),
+    ]
+    .join("\n");
+    add_commit(storage.as_ref(), 0, commit).await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+
+    let indexed = || {
+        Box::new(Expression::LessThan(
+            Box::new(Expression::Column("id".to_string())),
+            Box::new(Expression::Literal(Scalar::Integer(2))),
+        ))
+    };
+    let not_indexed = || {
+        Box::new(Expression::Equal(
+            Box::new(Expression::Column("val".to_string())),
+            Box::new(Expression::Literal("a".into())),
+        ))
+    };
+
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_predicate(Expression::And(indexed(), not_indexed()))
+        .build()?;
+    assert_eq!(
+        scan.skipping_applicability()?,
+        SkippingApplicability::Partial
+    );
+    let files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path(), PARQUET_FILE1);
+
+    // a predicate only on columns without statistics keeps all files
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_predicate(*not_indexed())
+        .build()?;
+    assert_eq!(
+        scan.skipping_applicability()?,
+        SkippingApplicability::NotApplicable
+    );
+    assert_eq!(scan.files_stream()?.try_collect::<Vec<_>>().await?.len(), 2);
+
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+        .await?;
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_predicate(Expression::And(indexed(), not_indexed()))
+        .build()?;
+    assert_eq!(scan.execute().await?, vec![batch]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn incremental_files() -> Result<(), Box<dyn std::error::Error>> {
+    const PARQUET_FILE3: &str = "part-00002.snappy.parquet";
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        1,
+        generate_commit(vec![TestAction::Add(PARQUET_FILE2.to_string())]),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        2,
+        generate_commit(vec![
+            TestAction::Remove(PARQUET_FILE1.to_string()),
+            TestAction::Remove(PARQUET_FILE2.to_string()),
+            TestAction::Add(PARQUET_FILE3.to_string()),
+        ]),
+    )
+    .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let scan = table.snapshot(None).await?.scan().await?.build()?;
+
+    let paths = |from, to| {
+        let scan = &scan;
+        async move {
+            let files = scan.incremental(from, to).await?;
+            let mut adds = files
+                .adds
+                .iter()
+                .map(|add| add.path.clone())
+                .collect::<Vec<_>>();
+            let mut removes = files
+                .removes
+                .iter()
+                .map(|remove| remove.path.clone())
+                .collect::<Vec<_>>();
+            adds.sort();
+            removes.sort();
+            Ok::<_, deltakernel::Error>((adds, removes))
+        }
+    };
+
+    assert_eq!(
+        paths(0, 0).await?,
+        (vec![PARQUET_FILE1.to_string()], vec![])
+    );
+    assert_eq!(
+        paths(0, 1).await?,
+        (
+            vec![PARQUET_FILE1.to_string(), PARQUET_FILE2.to_string()],
+            vec![]
+        )
+    );
+    // file 2 is added and removed within the range
+    assert_eq!(
+        paths(1, 2).await?,
+        (
+            vec![PARQUET_FILE3.to_string()],
+            vec![PARQUET_FILE1.to_string()]
+        )
+    );
+    assert_eq!(
+        paths(2, 2).await?,
+        (
+            vec![PARQUET_FILE3.to_string()],
+            vec![PARQUET_FILE1.to_string(), PARQUET_FILE2.to_string()]
+        )
+    );
+    assert_eq!(
+        paths(0, 2).await?,
+        (vec![PARQUET_FILE3.to_string()], vec![])
+    );
+
+    assert!(scan.incremental(2, 1).await.is_err());
+    assert!(scan.incremental(2, 3).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn incremental_files_skip_compaction() -> Result<(), Box<dyn std::error::Error>> {
+    const PARQUET_FILE3: &str = "part-00002.snappy.parquet";
+    let compacted = |action: &str, path: &str| {
+        format!(
+            r#"{{"{action}":{{"path":"{path}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":false}}}}"#
+        )
+    };
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        1,
+        generate_commit(vec![TestAction::Add(PARQUET_FILE2.to_string())]),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        2,
+        [
+            compacted("remove", PARQUET_FILE1),
+            compacted("remove", PARQUET_FILE2),
+            compacted("add", PARQUET_FILE3),
+        ]
+        .join("\n"),
+    )
+    .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let scan = table.snapshot(None).await?.scan().await?.build()?;
+
+    // the compaction does not change any data
+    assert_eq!(scan.incremental(2, 2).await?, Default::default());
+
+    let files = scan.incremental(1, 2).await?;
+    let adds = files
+        .adds
+        .iter()
+        .map(|add| add.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(adds, vec![PARQUET_FILE2]);
+    assert!(files.removes.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn execute_with_batch_size() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    let paths = (0..4)
+        .map(|i| format!("part-{i:05}.snappy.parquet"))
+        .collect::<Vec<_>>();
+    let actions = std::iter::once(TestAction::Metadata)
+        .chain(paths.iter().map(|path| TestAction::Add(path.clone())))
+        .collect();
+    add_commit(storage.as_ref(), 0, generate_commit(actions)).await?;
+    for path in &paths {
+        storage
+            .put(&Path::from(path.as_str()), load_parquet(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+
+    let scan = snapshot.scan().await?.build()?;
+    let unbatched = scan.execute().await?;
+    assert_eq!(unbatched.len(), 4);
+
+    for (batch_size, expected) in [
+        (2, vec![2; 6]),
+        (5, vec![5, 5, 2]),
+        (12, vec![12]),
+        (20, vec![12]),
+    ] {
+        let scan = snapshot
+            .scan()
+            .await?
+            .with_batch_size(NonZeroUsize::new(batch_size).unwrap())
+            .build()?;
+        let batches = scan.execute().await?;
+        let sizes = batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+        assert_eq!(sizes, expected, "batch size {batch_size}");
+        assert_eq!(
+            arrow::compute::concat_batches(&batches[0].schema(), &batches)?,
+            arrow::compute::concat_batches(&unbatched[0].schema(), &unbatched)?
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_table() -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![TestAction::Metadata]),
+    )
+    .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    assert_eq!(snapshot.schema().await?.fields.len(), 2);
+
+    let scan = snapshot.scan().await?.build()?;
+    assert!(scan
+        .files_stream()?
+        .try_collect::<Vec<_>>()
+        .await?
+        .is_empty());
+    assert!(scan.execute().await?.is_empty());
+    assert!(scan.incremental(0, 0).await?.adds.is_empty());
+
+    let predicate = Expression::LessThan(
+        Box::new(Expression::Column(String::from("id"))),
+        Box::new(Expression::Literal(Scalar::Integer(2))),
+    );
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_predicate(predicate)
+        .with_batch_size(NonZeroUsize::new(10).unwrap())
+        .build()?;
+    assert!(scan.execute().await?.is_empty());
+    Ok(())
+}
+
+/// Object store recording how often each file is read.
+#[derive(Debug, Default)]
+struct RecordingStore {
+    inner: InMemory,
+    reads: std::sync::Mutex<std::collections::HashMap<Path, usize>>,
+}
+
+impl RecordingStore {
+    fn record(&self, location: &Path) {
+        *self
+            .reads
+            .lock()
+            .unwrap()
+            .entry(location.clone())
+            .or_default() += 1;
+    }
+
+    /// The number of reads of each file with the given `extension`.
+    fn reads(&self, extension: &str) -> std::collections::HashMap<Path, usize> {
+        let reads = self.reads.lock().unwrap();
+        reads
+            .iter()
+            .filter(|(location, _)| location.extension() == Some(extension))
+            .map(|(location, count)| (location.clone(), *count))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for RecordingStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RecordingStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for RecordingStore {
+    async fn put(&self, location: &Path, bytes: bytes::Bytes) -> object_store::Result<()> {
+        self.inner.put(location, bytes).await
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> object_store::Result<(
+        object_store::MultipartId,
+        Box<dyn tokio::io::AsyncWrite + Unpin + Send>,
+    )> {
+        self.inner.put_multipart(location).await
+    }
+
+    async fn abort_multipart(
+        &self,
+        location: &Path,
+        multipart_id: &object_store::MultipartId,
+    ) -> object_store::Result<()> {
+        self.inner.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: object_store::GetOptions,
+    ) -> object_store::Result<object_store::GetResult> {
+        self.record(location);
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_range(
+        &self,
+        location: &Path,
+        range: std::ops::Range<usize>,
+    ) -> object_store::Result<bytes::Bytes> {
+        self.record(location);
+        self.inner.get_range(location, range).await
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[std::ops::Range<usize>],
+    ) -> object_store::Result<Vec<bytes::Bytes>> {
+        self.record(location);
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> object_store::Result<object_store::ObjectMeta> {
+        self.record(location);
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> object_store::Result<()> {
+        self.inner.delete(location).await
+    }
+
+    async fn list(
+        &self,
+        prefix: Option<&Path>,
+    ) -> object_store::Result<
+        futures::stream::BoxStream<'_, object_store::Result<object_store::ObjectMeta>>,
+    > {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> object_store::Result<
+        futures::stream::BoxStream<'_, object_store::Result<object_store::ObjectMeta>>,
+    > {
+        self.inner.list_with_offset(prefix, offset).await
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        prefix: Option<&Path>,
+    ) -> object_store::Result<object_store::ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+#[tokio::test]
+async fn drop_execute_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(RecordingStore::default());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+            TestAction::Add(PARQUET_FILE2.to_string()),
+        ]),
+    )
+    .await?;
+    for file in [PARQUET_FILE1, PARQUET_FILE2] {
+        storage
+            .put(&Path::from(file), load_parquet(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let scan = table.snapshot(None).await?.scan().await?.build()?;
+
+    let mut stream = scan.execute_stream()?;
+    assert_eq!(stream.try_next().await?, Some(batch));
+    drop(stream);
+    tokio::task::yield_now().await;
+    assert_eq!(storage.reads("parquet").len(), 1);
+
+    // the files are read when the scan runs to completion
+    assert_eq!(scan.execute().await?.len(), 2);
+    assert_eq!(storage.reads("parquet").len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn first_batch_before_log_replay() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(RecordingStore::default());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![TestAction::Metadata]),
+    )
+    .await?;
+    for (version, file) in [(1, PARQUET_FILE1), (2, PARQUET_FILE2)] {
+        add_commit(
+            storage.as_ref(),
+            version,
+            generate_commit(vec![TestAction::Add(file.to_string())]),
+        )
+        .await?;
+        storage
+            .put(&Path::from(file), load_parquet(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    snapshot.metadata().await?;
+    let commit_reads = |version: u64| {
+        let path = Path::from(format!("_delta_log/{:0>20}.json", version));
+        storage
+            .reads("json")
+            .get(&path)
+            .copied()
+            .unwrap_or_default()
+    };
+    let initial_reads = commit_reads(0);
+
+    // the file of the newest commit is read before the oldest commit is replayed, only the
+    // next commit may already be opened
+    let scan = snapshot.scan().await?.build()?;
+    let mut stream = scan.execute_stream()?;
+    assert_eq!(stream.try_next().await?, Some(batch.clone()));
+    assert_eq!(commit_reads(0), initial_reads);
+    assert_eq!(storage.reads("parquet").len(), 1);
+    drop(stream);
+
+    // sorting the files requires replaying the whole log first
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_deterministic_order(true)
+        .build()?;
+    let mut stream = scan.execute_stream()?;
+    assert_eq!(stream.try_next().await?, Some(batch));
+    assert_eq!(commit_reads(0), initial_reads + 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn log_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Arc::new(RecordingStore::default());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = DefaultTableClient::new(storage.clone(), Path::from("/"))
+        .with_log_cache(Arc::new(LogCache::new()));
+    let table = Table::new(location, Arc::new(table_client));
+    let commit_reads = |version: u64| {
+        let path = Path::from(format!("_delta_log/{:0>20}.json", version));
+        storage
+            .reads("json")
+            .get(&path)
+            .copied()
+            .unwrap_or_default()
+    };
+
+    // concurrent snapshots of the same version read the log once
+    let (first, second) = (table.snapshot(None).await?, table.snapshot(None).await?);
+    let (first, second) = futures::join!(first.metadata(), second.metadata());
+    assert_eq!(first?, second?);
+    table.snapshot(None).await?.scan().await?;
+    assert_eq!(commit_reads(0), 1);
+
+    // the file actions replayed by scans are read once as well
+    for _ in 0..2 {
+        let scan = table.snapshot(None).await?.scan().await?.build()?;
+        for _ in 0..2 {
+            let files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+            assert_eq!(files.len(), 1);
+        }
+    }
+    assert_eq!(commit_reads(0), 2);
+
+    // a new version invalidates the cached metadata
+    add_commit(
+        storage.as_ref(),
+        1,
+        generate_commit(vec![TestAction::Add(PARQUET_FILE2.to_string())]),
+    )
+    .await?;
+    table.snapshot(None).await?.metadata().await?;
+    table.snapshot(None).await?.metadata().await?;
+    assert_eq!((commit_reads(0), commit_reads(1)), (3, 1));
+    Ok(())
+}
+
+/// Write the json `actions` of the given types to a parquet file, as found in checkpoints.
+fn actions_parquet(actions: &str, action_types: &[ActionType]) -> Vec<u8> {
+    let fields = action_types.iter().map(|action| action.field());
+    let schema = Arc::new(arrow::datatypes::Schema::new(fields.collect::<Vec<_>>()));
+    let batch = arrow::json::ReaderBuilder::new(schema)
+        .build(actions.as_bytes())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    load_parquet(&batch)
+}
+
+#[tokio::test]
+async fn checkpoint_parsed_partition_values() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    let metadata = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}
+{"metaData":{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"val\",\"type\":\"string\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_int\",\"type\":\"integer\",\"nullable\":true,\"metadata\":{}},{\"name\":\"part_date\",\"type\":\"date\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":["part_int","part_date"],"configuration":{},"createdTime":1587968585495}}"#;
+    // the first file only has typed partition values, the second one only the string map
+    let adds = [
+        r#"{"add":{"path":"part-00000.parquet","partitionValues":{},"partitionValues_parsed":{"part_int":7,"part_date":"2021-01-02"},"size":262,"modificationTime":1587968586000,"dataChange":true}}"#,
+        r#"{"add":{"path":"part-00001.parquet","partitionValues":{"part_int":"8","part_date":null},"size":262,"modificationTime":1587968586000,"dataChange":true}}"#,
+    ];
+    let checkpoint = format!("{metadata}\n{}\n", adds.join("\n"));
+
+    // the checkpoint schema has the typed partition values next to the add fields
+    let ArrowDataType::Struct(add_fields) = ActionType::Add.field().data_type().clone() else {
+        panic!("add action is a struct");
+    };
+    let parsed = ArrowField::new(
+        "partitionValues_parsed",
+        ArrowDataType::Struct(
+            vec![
+                ArrowField::new("part_int", ArrowDataType::Int32, true),
+                ArrowField::new("part_date", ArrowDataType::Date32, true),
+            ]
+            .into(),
+        ),
+        true,
+    );
+    let add_fields = add_fields
+        .iter()
+        .cloned()
+        .chain(std::iter::once(Arc::new(parsed)))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(ArrowSchema::new(vec![
+        ActionType::Protocol.field(),
+        ActionType::Metadata.field(),
+        ArrowField::new("add", ArrowDataType::Struct(add_fields.into()), true),
+        ActionType::Remove.field(),
+    ]));
+    let actions = arrow::json::ReaderBuilder::new(schema)
+        .build(checkpoint.as_bytes())?
+        .next()
+        .unwrap()?;
+    storage
+        .put(
+            &Path::from("_delta_log/00000000000000000000.checkpoint.parquet"),
+            load_parquet(&actions).into(),
+        )
+        .await?;
+    for path in ["part-00000.parquet", "part-00001.parquet"] {
+        storage
+            .put(&Path::from(path), load_parquet(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+
+    let predicate = Expression::Equal(
+        Box::new(Expression::Column("part_int".to_string())),
+        Box::new(Expression::Literal(Scalar::Integer(7))),
+    );
+    let scan = snapshot.scan().await?.with_predicate(predicate).build()?;
+    let files = scan.files_stream()?.try_collect::<Vec<_>>().await?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path(), "part-00000.parquet");
+    assert_eq!(files[0].partition_values["part_int"].as_deref(), Some("7"));
+    assert_eq!(
+        files[0].partition_values["part_date"].as_deref(),
+        Some("2021-01-02")
+    );
+
+    let scan = snapshot.scan().await?.build()?;
+    let batches = scan.execute().await?;
+    assert_eq!(batches.len(), 2);
+    let expected: Vec<ArrayRef> = vec![
+        Arc::new(Int32Array::from(vec![7, 7, 7])),
+        Arc::new(Int32Array::from(vec![8, 8, 8])),
+    ];
+    assert_eq!(batches[0].column(2), &expected[0]);
+    assert_eq!(batches[1].column(2), &expected[1]);
+    assert_eq!(batches[1].column(3).null_count(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn v2_checkpoint_with_sidecars() -> Result<(), Box<dyn std::error::Error>> {
+    let batch = generate_simple_batch()?;
+    let storage = Arc::new(InMemory::new());
+    let sidecar = |file: &str| {
+        format!(r#"{{"sidecar":{{"path":"{file}.parquet","sizeInBytes":1,"modificationTime":0}}}}"#)
+    };
+    let checkpoint = format!("{METADATA}\n{}\n{}\n", sidecar("first"), sidecar("second"));
+    storage
+        .put(
+            &Path::from(
+                "_delta_log/00000000000000000000.checkpoint.80a083e8-7026-4e79-81be-64bd76c43a11.parquet",
+            ),
+            actions_parquet(
+                &checkpoint,
+                &[ActionType::Metadata, ActionType::Protocol, ActionType::Sidecar],
+            )
+            .into(),
+        )
+        .await?;
+    for (name, file) in [("first", PARQUET_FILE1), ("second", PARQUET_FILE2)] {
+        let adds = generate_commit(vec![TestAction::Add(file.to_string())]);
+        storage
+            .put(
+                &Path::from(format!("_delta_log/_sidecars/{name}.parquet")),
+                actions_parquet(&adds, &[ActionType::Add, ActionType::Remove]).into(),
+            )
+            .await?;
+        storage
+            .put(&Path::from(file), load_parquet(&batch).into())
+            .await?;
+    }
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    assert_eq!(snapshot.version(), 0);
+    let scan = snapshot.scan().await?.build()?;
+    assert_eq!(scan.execute().await?, vec![batch.clone(), batch]);
+
+    // json V2 checkpoints are not supported yet
+    storage
+        .put(
+            &Path::from(
+                "_delta_log/00000000000000000001.checkpoint.5bb0dbe4-0d51-4fc4-8f8a-4a797dfd2e9a.json",
+            ),
+            checkpoint.into(),
+        )
+        .await?;
+    assert!(matches!(
+        table.snapshot(None).await,
+        Err(Error::UnsupportedFeature(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn deterministic_file_order() -> Result<(), Box<dyn std::error::Error>> {
+    let batch1 = generate_simple_batch()?;
+    let batch2 = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(Int32Array::from(vec![4, 5])) as ArrayRef),
+        (
+            "val",
+            Arc::new(StringArray::from(vec!["d", "e"])) as ArrayRef,
+        ),
+    ])?;
+    let storage = Arc::new(InMemory::new());
+    add_commit(
+        storage.as_ref(),
+        0,
+        generate_commit(vec![
+            TestAction::Metadata,
+            TestAction::Add(PARQUET_FILE1.to_string()),
+        ]),
+    )
+    .await?;
+    add_commit(
+        storage.as_ref(),
+        1,
+        generate_commit(vec![TestAction::Add(PARQUET_FILE2.to_string())]),
+    )
+    .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE1), load_parquet(&batch1).into())
+        .await?;
+    storage
+        .put(&Path::from(PARQUET_FILE2), load_parquet(&batch2).into())
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_deterministic_order(true)
+        .build()?;
+
+    let list_files = || async {
+        let files = scan.files()?.try_collect::<Vec<_>>().await?;
+        Ok::<_, Error>(
+            files
+                .into_iter()
+                .flatten()
+                .map(|f| f.add.path)
+                .collect::<Vec<_>>(),
+        )
+    };
+    // files are listed in path order, even though the newest commit is replayed first
+    let files = list_files().await?;
+    assert_eq!(files, vec![PARQUET_FILE1, PARQUET_FILE2]);
+    assert_eq!(files, list_files().await?);
+
+    // and read in the same order
+    assert_eq!(scan.execute().await?, vec![batch1.clone(), batch2.clone()]);
+    assert_eq!(scan.execute().await?, vec![batch1, batch2]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn column_mapped_scan_schemas() -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Arc::new(InMemory::new());
+    let field = |name: &str, data_type: &str, physical_name: &str| {
+        format!(
+            r#"{{\"name\":\"{name}\",\"type\":{data_type},\"nullable\":true,\"metadata\":{{\"delta.columnMapping.physicalName\":\"{physical_name}\"}}}}"#
+        )
+    };
+    let nested = format!(
+        r#"{{\"type\":\"struct\",\"fields\":[{}]}}"#,
+        field("name", r#"\"string\""#, "col-3")
+    );
+    let fields = [
+        field("id", r#"\"integer\""#, "col-1"),
+        field("info", &nested, "col-2"),
+        field("part", r#"\"string\""#, "col-4"),
+    ]
+    .join(",");
+    let metadata = format!(
+        r#"{{"protocol":{{"minReaderVersion":2,"minWriterVersion":5}}}}
+{{"metaData":{{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{fields}]}}","partitionColumns":["part"],"configuration":{{"delta.columnMapping.mode":"name"}},"createdTime":1587968585495}}}}"#
+    );
+    add_commit(storage.as_ref(), 0, metadata).await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let table_schema = snapshot.schema().await?;
+    let projected = StructType::new(
+        ["info", "part"]
+            .iter()
+            .map(|name| table_schema.field(name).unwrap().clone())
+            .collect(),
+    );
+    let scan = snapshot
+        .scan()
+        .await?
+        .with_schema(Arc::new(projected.clone()))
+        .build()?;
+
+    assert_eq!(scan.logical_schema().as_ref(), &projected);
+    let physical = scan.physical_read_schema();
+    assert_eq!(physical.fields.len(), 1);
+    assert_eq!(physical.fields[0].name, "col-2");
+    let DataType::Struct(inner) = &physical.fields[0].data_type else {
+        panic!("expected a struct field");
+    };
+    assert_eq!(inner.fields[0].name, "col-3");
+    Ok(())
+}
+
+#[tokio::test]
+async fn column_mapped_execute() -> Result<(), Box<dyn std::error::Error>> {
+    let storage = Arc::new(InMemory::new());
+    let field = |name: &str, data_type: &str, physical_name: &str| {
+        format!(
+            r#"{{\"name\":\"{name}\",\"type\":{data_type},\"nullable\":true,\"metadata\":{{\"delta.columnMapping.physicalName\":\"{physical_name}\"}}}}"#
+        )
+    };
+    let nested = format!(
+        r#"{{\"type\":\"struct\",\"fields\":[{}]}}"#,
+        field("name", r#"\"string\""#, "col-3")
+    );
+    let fields = [
+        field("id", r#"\"integer\""#, "col-1"),
+        field("info", &nested, "col-2"),
+        field("part", r#"\"string\""#, "col-4"),
+    ]
+    .join(",");
+    let metadata = format!(
+        r#"{{"protocol":{{"minReaderVersion":2,"minWriterVersion":5}}}}
+{{"metaData":{{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{fields}]}}","partitionColumns":["part"],"configuration":{{"delta.columnMapping.mode":"name"}},"createdTime":1587968585495}}}}
+{{"add":{{"path":"col-4=a/part-00000.parquet","partitionValues":{{"col-4":"a"}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#
+    );
+    add_commit(storage.as_ref(), 0, metadata).await?;
+
+    let names: ArrayRef = Arc::new(StringArray::from(vec!["x", "y"]));
+    let info = StructArray::from(vec![(
+        Arc::new(ArrowField::new("col-3", ArrowDataType::Utf8, true)),
+        names.clone(),
+    )]);
+    let ids: ArrayRef = Arc::new(Int32Array::from(vec![1, 2]));
+    let data = RecordBatch::try_from_iter(vec![
+        ("col-1", ids.clone()),
+        ("col-2", Arc::new(info) as ArrayRef),
+    ])?;
+    storage
+        .put(
+            &Path::from("col-4=a/part-00000.parquet"),
+            load_parquet(&data).into(),
+        )
+        .await?;
+
+    let location = Url::parse("memory:///")?;
+    let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+    let table = Table::new(location, table_client);
+    let snapshot = table.snapshot(None).await?;
+    let batches = snapshot.scan().await?.build()?.execute().await?;
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    let schema = batch.schema();
+    let column_names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(column_names, vec!["id", "info", "part"]);
+    assert_eq!(batch.column(0), &ids);
+    let info = batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_eq!(info.column_names(), vec!["name"]);
+    assert_eq!(info.column(0), &names);
+    let part: ArrayRef = Arc::new(StringArray::from(vec!["a", "a"]));
+    assert_eq!(batch.column(2), &part);
+    Ok(())
+}
+
+#[tokio::test]
+async fn timestamp_ntz_column() -> Result<(), Box<dyn std::error::Error>> {
+    let timestamps = TimestampMicrosecondArray::from(vec![0, 1_000_000, 1609459201000000]);
+    let batch = RecordBatch::try_from_iter(vec![("ts", Arc::new(timestamps) as ArrayRef)])?;
+    let metadata = |reader_features: &str| {
+        format!(
+            r#"{{"protocol":{{"minReaderVersion":3,"minWriterVersion":7,"readerFeatures":[{reader_features}],"writerFeatures":[{reader_features}]}}}}
+{{"metaData":{{"id":"5fba94ed-9794-4965-ba6e-6ee3c0d22af9","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{{\"name\":\"ts\",\"type\":\"timestamp_ntz\",\"nullable\":true,\"metadata\":{{}}}}]}}","partitionColumns":[],"configuration":{{}},"createdTime":1587968585495}}}}
+{{"add":{{"path":"{PARQUET_FILE1}","partitionValues":{{}},"size":262,"modificationTime":1587968586000,"dataChange":true}}}}"#
+        )
+    };
+    let table = |reader_features: &str| {
+        let storage = Arc::new(InMemory::new());
+        let commit = metadata(reader_features);
+        let batch = batch.clone();
+        async move {
+            add_commit(storage.as_ref(), 0, commit).await?;
+            storage
+                .put(&Path::from(PARQUET_FILE1), load_parquet(&batch).into())
+                .await?;
+            let location = Url::parse("memory:///")?;
+            let table_client = Arc::new(DefaultTableClient::new(storage.clone(), Path::from("/")));
+            Ok::<_, Box<dyn std::error::Error>>(Table::new(location, table_client))
+        }
+    };
+
+    let snapshot = table(r#""timestampNtz""#).await?.snapshot(None).await?;
+    let batches = snapshot.scan().await?.build()?.execute().await?;
+    assert_eq!(batches, vec![batch.clone()]);
+    assert_eq!(
+        batches[0].schema().field(0).data_type(),
+        &arrow::datatypes::DataType::Timestamp(arrow::datatypes::TimeUnit::Microsecond, None)
+    );
+
+    // the column requires the reader feature
+    let snapshot = table("").await?.snapshot(None).await?;
+    assert!(snapshot.scan().await.is_err());
+    Ok(())
+}